@@ -4,4 +4,4 @@ home() {
 
 home
 
-cargo test --package bootstrap --test phoenix bootstrap_markets -- --exact --nocapture
\ No newline at end of file
+cargo run --package bootstrap --bin bootstrap -- --manifest crates/bootstrap/manifests/localnet.json "$@"
@@ -0,0 +1,278 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub const SOL_DECIMALS: u8 = 9;
+pub const USDC_DECIMALS: u8 = 6;
+
+/// More fractional digits than any mint uses, and small enough that scaling stays in a u128.
+const MAX_SCALE: u8 = 18;
+
+/// A non-negative decimal exactly as written, e.g. `0.29`, before it is tied to a mint.
+/// Stored as `digits / 10^scale` without trailing fractional zeros, so equal values compare equal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct UiAmount {
+    digits: u128,
+    scale: u8,
+}
+
+impl UiAmount {
+    pub fn new(mut digits: u128, mut scale: u8) -> Self {
+        while scale > 0 && digits.is_multiple_of(10) {
+            digits /= 10;
+            scale -= 1;
+        }
+        Self { digits, scale }
+    }
+
+    pub fn from_atoms(atoms: u64, decimals: u8) -> Self {
+        Self::new(atoms as u128, decimals)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits == 0
+    }
+
+    /// Base units at `decimals`. Fails rather than rounding if the amount is more precise than
+    /// the mint, or too large for a u64.
+    pub fn to_atoms(&self, decimals: u8) -> anyhow::Result<u64> {
+        if self.scale > decimals {
+            return Err(anyhow::anyhow!(
+                "{} has more than {} decimals",
+                self,
+                decimals
+            ));
+        }
+        10u128
+            .checked_pow((decimals - self.scale) as u32)
+            .and_then(|factor| self.digits.checked_mul(factor))
+            .and_then(|atoms| u64::try_from(atoms).ok())
+            .ok_or(anyhow::anyhow!(
+                "{} overflows at {} decimals",
+                self,
+                decimals
+            ))
+    }
+
+    /// `None` on overflow.
+    pub fn checked_mul(&self, factor: u64) -> Option<UiAmount> {
+        Some(Self::new(
+            self.digits.checked_mul(factor as u128)?,
+            self.scale,
+        ))
+    }
+
+    pub fn with_decimals(&self, decimals: u8) -> anyhow::Result<TokenAmount> {
+        Ok(TokenAmount::new(self.to_atoms(decimals)?, decimals))
+    }
+
+    /// Lossy, for prices and reports.
+    pub fn to_f64(&self) -> f64 {
+        self.digits as f64 / 10f64.powi(self.scale as i32)
+    }
+}
+
+impl From<u64> for UiAmount {
+    fn from(units: u64) -> Self {
+        Self::new(units as u128, 0)
+    }
+}
+
+impl FromStr for UiAmount {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let invalid = || anyhow::anyhow!("Invalid amount {:?}, expected e.g. 12 or 0.29", s);
+        let (whole, fraction) = match s.trim().split_once('.') {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (s.trim(), ""),
+        };
+        if (whole.is_empty() && fraction.is_empty())
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        if fraction.len() > MAX_SCALE as usize {
+            return Err(anyhow::anyhow!(
+                "Amount {} has more than {} decimals",
+                s,
+                MAX_SCALE
+            ));
+        }
+        let digits = format!("{}{}", whole, fraction)
+            .parse::<u128>()
+            .map_err(|_| invalid())?;
+        Ok(Self::new(digits, fraction.len() as u8))
+    }
+}
+
+/// Places the decimal point in the digits' text rather than dividing by `10^scale`, which
+/// overflows a u128 past 38 decimals and mints may declare up to 255.
+impl fmt::Display for UiAmount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let scale = self.scale as usize;
+        let digits = format!("{:0width$}", self.digits, width = scale + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}", whole)?;
+        if scale > 0 {
+            write!(f, ".{}", fraction)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for UiAmount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Accepts `"0.29"` and, for hand written JSON, bare numbers. Floats go through their shortest
+/// decimal representation, so `0.29` stays `0.29`.
+impl<'de> Deserialize<'de> for UiAmount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Int(u64),
+            Float(f64),
+            Str(String),
+        }
+        let s = match Repr::deserialize(deserializer)? {
+            Repr::Int(units) => return Ok(UiAmount::from(units)),
+            Repr::Float(value) => value.to_string(),
+            Repr::Str(s) => s,
+        };
+        UiAmount::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// An amount in a mint's base units together with the mint's decimals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TokenAmount {
+    pub atoms: u64,
+    pub decimals: u8,
+}
+
+impl TokenAmount {
+    pub fn new(atoms: u64, decimals: u8) -> Self {
+        Self { atoms, decimals }
+    }
+
+    /// Parses a UI amount such as `"0.29"` exactly, see [`UiAmount::to_atoms`].
+    pub fn parse(s: &str, decimals: u8) -> anyhow::Result<Self> {
+        UiAmount::from_str(s)?.with_decimals(decimals)
+    }
+
+    pub fn ui(&self) -> UiAmount {
+        UiAmount::from_atoms(self.atoms, self.decimals)
+    }
+
+    /// The atoms, for a mint of `decimals`. Fails rather than rescaling if the amount was made
+    /// for another mint's decimals.
+    pub fn to_atoms(&self, decimals: u8) -> anyhow::Result<u64> {
+        if self.decimals != decimals {
+            return Err(anyhow::anyhow!(
+                "{} has {} decimals, the mint has {}",
+                self,
+                self.decimals,
+                decimals
+            ));
+        }
+        Ok(self.atoms)
+    }
+
+    /// Lots of `atoms_per_lot`. Fails rather than rounding if the amount isn't a whole number
+    /// of lots.
+    pub fn to_lots(&self, atoms_per_lot: u64) -> anyhow::Result<u64> {
+        if atoms_per_lot == 0 {
+            return Err(anyhow::anyhow!("Lot size must not be zero"));
+        }
+        if !self.atoms.is_multiple_of(atoms_per_lot) {
+            return Err(anyhow::anyhow!(
+                "{} is not a whole number of {} atom lots",
+                self,
+                atoms_per_lot
+            ));
+        }
+        Ok(self.atoms / atoms_per_lot)
+    }
+
+    pub fn from_lots(lots: u64, atoms_per_lot: u64, decimals: u8) -> anyhow::Result<Self> {
+        let atoms = lots.checked_mul(atoms_per_lot).ok_or(anyhow::anyhow!(
+            "{} lots of {} atoms overflow",
+            lots,
+            atoms_per_lot
+        ))?;
+        Ok(Self::new(atoms, decimals))
+    }
+
+    /// `None` on overflow or if the decimals differ.
+    pub fn checked_add(&self, other: TokenAmount) -> Option<TokenAmount> {
+        if self.decimals != other.decimals {
+            return None;
+        }
+        let atoms = self.atoms.checked_add(other.atoms)?;
+        Some(Self::new(atoms, self.decimals))
+    }
+
+    pub fn checked_sub(&self, other: TokenAmount) -> Option<TokenAmount> {
+        if self.decimals != other.decimals {
+            return None;
+        }
+        let atoms = self.atoms.checked_sub(other.atoms)?;
+        Some(Self::new(atoms, self.decimals))
+    }
+
+    /// Lossy, for valuations and reports.
+    pub fn to_f64(&self) -> f64 {
+        self.atoms as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.ui().fmt(f)
+    }
+}
+
+/// A signed change of a token balance: `amount` gained, or given up if `negative`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TokenDelta {
+    pub amount: TokenAmount,
+    pub negative: bool,
+}
+
+impl TokenDelta {
+    /// `lots` of `atoms_per_lot`, negative if the lots were given up.
+    pub fn from_lots(lots: i128, atoms_per_lot: u64, decimals: u8) -> anyhow::Result<Self> {
+        let abs = u64::try_from(lots.unsigned_abs())
+            .map_err(|_| anyhow::anyhow!("{} lots overflow", lots))?;
+        Ok(Self {
+            amount: TokenAmount::from_lots(abs, atoms_per_lot, decimals)?,
+            negative: lots < 0,
+        })
+    }
+
+    /// Lossy, for valuations and reports.
+    pub fn to_f64(&self) -> f64 {
+        if self.negative {
+            -self.amount.to_f64()
+        } else {
+            self.amount.to_f64()
+        }
+    }
+}
+
+impl fmt::Display for TokenDelta {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.negative && self.amount.atoms > 0 {
+            write!(f, "-")?;
+        }
+        self.amount.fmt(f)
+    }
+}
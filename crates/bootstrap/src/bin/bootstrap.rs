@@ -0,0 +1,91 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use clap::Parser;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signer::Signer;
+
+use bootstrap::helpers::signature_link;
+use bootstrap::market_bootstrap::{bootstrap_manifest, BootstrapManifest, BootstrapStatus};
+use bootstrap::signer::load_signer;
+
+/// Provision the Phoenix mints and markets described by a manifest.
+/// Rerunning against the same cluster skips whatever already exists.
+#[derive(Parser)]
+#[command(name = "bootstrap", version)]
+struct Args {
+    #[arg(long, short = 'u', default_value = "http://localhost:8899")]
+    url: String,
+    /// Payer, and the markets' authority: a keypair file, base58 secret key, `env:NAME` or
+    /// `mnemonic:<words>[?key=0/0]`. Defaults to the manifest's authority
+    #[arg(long, short = 'k')]
+    keypair: Option<String>,
+    /// JSON manifest of mints and markets. Defaults to the mock SOL/USDC and JUP/SOL markets
+    #[arg(long, short = 'm')]
+    manifest: Option<PathBuf>,
+    /// Use the native mint for the manifest's SOL markets, as on mainnet, instead of a mock SOL
+    #[arg(long)]
+    native_sol: bool,
+    /// processed, confirmed or finalized
+    #[arg(long, default_value = "processed")]
+    commitment: String,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let commitment = CommitmentConfig::from_str(&args.commitment)
+        .map_err(|e| anyhow::anyhow!("Invalid commitment {}: {:?}", args.commitment, e))?;
+    let mut manifest = match &args.manifest {
+        Some(path) => BootstrapManifest::load(path)?,
+        None => BootstrapManifest::mock(),
+    };
+    if args.native_sol {
+        manifest = manifest.with_native_sol();
+    }
+    let payer: Box<dyn Signer + Send + Sync> = match &args.keypair {
+        Some(source) => load_signer(source)?,
+        None => Box::new(
+            manifest
+                .authority
+                .as_ref()
+                .ok_or(anyhow::anyhow!(
+                    "The manifest has no authority, pass --keypair"
+                ))?
+                .load(&manifest.base_dir)?,
+        ),
+    };
+
+    let client = RpcClient::new_with_timeouts_and_commitment(
+        args.url.clone(),
+        Duration::from_secs(5),
+        commitment,
+        Duration::from_secs(5),
+    );
+    let report = bootstrap_manifest(&client, &*payer, &manifest).await?;
+    // progress goes to stderr, leaving stdout to the market addresses
+    for step in &report.steps {
+        match &step.status {
+            BootstrapStatus::Created(sig) => eprintln!(
+                "created {} {}: {}",
+                step.name,
+                step.address,
+                signature_link(&client, sig)
+            ),
+            BootstrapStatus::Existing => eprintln!("{} {} already exists", step.name, step.address),
+            BootstrapStatus::Skipped(reason) => eprintln!("skipped {}: {}", step.name, reason),
+        }
+    }
+    eprintln!(
+        "created {} of {} accounts for {} lamports",
+        report.created().count(),
+        report.steps.len(),
+        report.lamports_spent
+    );
+    for (market_manifest, market) in manifest.markets.iter().zip(&report.markets) {
+        println!("{}: {}", market_manifest.name(), market.market);
+    }
+    Ok(())
+}
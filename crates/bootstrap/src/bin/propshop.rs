@@ -0,0 +1,953 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde_json::{json, Value};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+use bootstrap::amount::UiAmount;
+use bootstrap::drift_vaults::{self, DriftFund, DRIFT_VAULTS_PROGRAM_ID};
+use bootstrap::fund::{Fund, FundOverview};
+use bootstrap::helpers::{airdrop, encode_name, get_account, get_mint, signature_link};
+use bootstrap::market::PhoenixMarket;
+use bootstrap::market_bootstrap::{bootstrap_manifest, BootstrapManifest, BootstrapStatus};
+use bootstrap::nav::{NavSnapshot, NavStore};
+use bootstrap::phoenix_vaults::{self, PhoenixFund, PHOENIX_VAULTS_PROGRAM_ID};
+use bootstrap::signer;
+use bootstrap::vault_update::{UpdateVaultConfig, PERCENTAGE_PRECISION};
+
+/// Operate Prop Shop funds and the Phoenix markets they trade.
+#[derive(Parser)]
+#[command(name = "propshop", version)]
+struct Cli {
+    /// RPC URL, or one of `localhost`, `devnet` and `mainnet-beta`
+    #[arg(long, short = 'u', global = true, default_value = "localhost")]
+    url: String,
+    /// Keypair that signs and pays: a keypair file, base58 secret key, `env:NAME` or
+    /// `mnemonic:<words>[?key=0/0]`. Defaults to ~/.config/solana/id.json
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<String>,
+    /// Print the result as one line of JSON, after any progress output
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Phoenix markets
+    #[command(subcommand)]
+    Markets(MarketsCommand),
+    /// Create, configure and inspect vaults
+    #[command(subcommand)]
+    Fund(FundCommand),
+    /// Deposit into and withdraw from a vault as an investor
+    #[command(subcommand)]
+    Invest(InvestCommand),
+    /// Manager deposits and withdrawals, and drift_vaults delegate and keeper operations
+    #[command(subcommand)]
+    Manager(ManagerCommand),
+    /// Protocol fees and withdrawals, and the phoenix_vaults market registry
+    #[command(subcommand)]
+    Protocol(ProtocolCommand),
+    /// Record vaults' NAV per share over time
+    #[command(subcommand)]
+    Nav(NavCommand),
+    /// Request SOL from the cluster faucet
+    Airdrop {
+        /// SOL to request
+        amount: UiAmount,
+        /// Recipient, defaults to the keypair
+        #[arg(long)]
+        to: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum MarketsCommand {
+    /// Create the mints and markets of a manifest, skipping any that exist
+    Bootstrap {
+        /// JSON manifest, defaults to the mock SOL/USDC and JUP/SOL markets
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+    },
+    /// Print a market's parameters and top of book
+    Inspect {
+        market: Pubkey,
+        /// Book levels per side
+        #[arg(long, default_value_t = 5)]
+        levels: u64,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum VenueArg {
+    Drift,
+    Phoenix,
+}
+
+#[derive(Subcommand)]
+enum FundCommand {
+    /// Initialize a vault managed by the keypair
+    Create(CreateFundArgs),
+    /// Send the minimal `updateVault` to reach the given settings
+    Update {
+        vault: Pubkey,
+        #[command(flatten)]
+        settings: FundSettingsArgs,
+        /// Delegate that trades the vault (phoenix_vaults only)
+        #[arg(long)]
+        delegate: Option<Pubkey>,
+        /// Print the changes without sending them
+        #[arg(long)]
+        dry_run: bool,
+        /// Send the changes without asking for confirmation
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Overview of one vault
+    Show {
+        vault: Pubkey,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+    /// Overviews of every vault
+    List {
+        /// Only list vaults of this venue
+        #[arg(long, value_enum)]
+        venue: Option<VenueArg>,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+}
+
+#[derive(Args)]
+struct CreateFundArgs {
+    #[arg(long, value_enum)]
+    venue: VenueArg,
+    /// Up to 32 bytes, the vault address is derived from it
+    #[arg(long)]
+    name: String,
+    /// Drift spot market deposits are made in (drift_vaults only)
+    #[arg(long, default_value_t = 0)]
+    spot_market_index: u16,
+    /// Seconds between a withdraw request and the withdrawal
+    #[arg(long, default_value_t = 7 * 86_400)]
+    redeem_period: i64,
+    /// Deposit capacity in tokens, 0 for no limit
+    #[arg(long, default_value = "0")]
+    max_tokens: UiAmount,
+    /// Annual management fee, in percent
+    #[arg(long, default_value_t = 0.0)]
+    management_fee: f64,
+    /// In tokens
+    #[arg(long, default_value = "0")]
+    min_deposit_amount: UiAmount,
+    /// Manager's share of investor profits, in percent
+    #[arg(long, default_value_t = 20.0)]
+    profit_share: f64,
+    /// In percent
+    #[arg(long, default_value_t = 0.0)]
+    hurdle_rate: f64,
+    /// Only the manager can add investors
+    #[arg(long)]
+    permissioned: bool,
+    /// Protocol that receives protocol fees, defaults to the keypair (phoenix_vaults only)
+    #[arg(long)]
+    protocol: Option<Pubkey>,
+    /// Annual protocol fee, in percent (phoenix_vaults only)
+    #[arg(long, default_value_t = 0.0)]
+    protocol_fee: f64,
+    /// Protocol's share of investor profits, in percent (phoenix_vaults only)
+    #[arg(long, default_value_t = 0.0)]
+    protocol_profit_share: f64,
+}
+
+/// Optional vault settings, in UI units. Omitted settings are left unchanged.
+#[derive(Args)]
+struct FundSettingsArgs {
+    /// Seconds between a withdraw request and the withdrawal
+    #[arg(long)]
+    redeem_period: Option<i64>,
+    /// Deposit capacity in tokens
+    #[arg(long)]
+    max_tokens: Option<UiAmount>,
+    /// Annual management fee, in percent
+    #[arg(long)]
+    management_fee: Option<f64>,
+    /// In tokens
+    #[arg(long)]
+    min_deposit_amount: Option<UiAmount>,
+    /// Manager's share of investor profits, in percent
+    #[arg(long)]
+    profit_share: Option<f64>,
+    /// In percent
+    #[arg(long)]
+    hurdle_rate: Option<f64>,
+    #[arg(long)]
+    permissioned: Option<bool>,
+}
+
+/// phoenix_vaults passes every registry market as a remaining account.
+#[derive(Args)]
+struct MarketsArg {
+    /// Phoenix markets to pass to phoenix_vaults, defaults to the registry's SOL/USDC market
+    #[arg(long = "market")]
+    markets: Vec<Pubkey>,
+}
+
+#[derive(Subcommand)]
+enum InvestCommand {
+    /// Deposit tokens, creating the investor account if needed
+    Deposit {
+        vault: Pubkey,
+        /// In tokens
+        amount: UiAmount,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+    /// Request a withdrawal, claimable after the redeem period
+    RequestWithdraw {
+        vault: Pubkey,
+        /// In tokens
+        amount: UiAmount,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+    /// Cancel the pending withdraw request
+    Cancel {
+        vault: Pubkey,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+    /// Complete a matured withdraw request
+    Withdraw {
+        vault: Pubkey,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+}
+
+#[derive(Subcommand)]
+enum ManagerCommand {
+    /// Deposit tokens as the vault's manager
+    Deposit {
+        vault: Pubkey,
+        /// In tokens
+        amount: UiAmount,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+    /// Request a withdrawal of the manager's shares, claimable after the redeem period
+    RequestWithdraw {
+        vault: Pubkey,
+        /// In tokens
+        amount: UiAmount,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+    /// Cancel the manager's pending withdraw request
+    Cancel {
+        vault: Pubkey,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+    /// Complete the manager's matured withdraw request
+    Withdraw {
+        vault: Pubkey,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+    /// Compare the vault's delegate with the Drift user's
+    DelegateStatus { vault: Pubkey },
+    /// Set a new delegate and confirm Drift picked it up
+    RotateDelegate { vault: Pubkey, delegate: Pubkey },
+    /// Restore the Drift user's delegate to the vault's
+    ResetDelegate { vault: Pubkey },
+    MarginTrading {
+        vault: Pubkey,
+        #[arg(action = clap::ArgAction::Set)]
+        enabled: bool,
+    },
+    /// Apply profit shares and force withdraw matured requests
+    Keeper {
+        #[arg(required = true)]
+        vaults: Vec<Pubkey>,
+        /// Print the actions without sending them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProtocolCommand {
+    /// Request a withdrawal of the protocol's shares, claimable after the redeem period
+    RequestWithdraw {
+        vault: Pubkey,
+        /// In tokens
+        amount: UiAmount,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+    /// Cancel the protocol's pending withdraw request
+    Cancel {
+        vault: Pubkey,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+    /// Complete the protocol's matured withdraw request
+    Withdraw {
+        vault: Pubkey,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+    /// Print the phoenix_vaults market registry
+    Registry,
+    /// Print the protocol fees of a vault
+    Fees { vault: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum NavCommand {
+    /// Snapshot the vaults' equity and total shares and append them to the store
+    Record {
+        #[arg(required = true)]
+        vaults: Vec<Pubkey>,
+        /// JSON lines file the snapshots are appended to
+        #[arg(long, default_value = "nav.jsonl")]
+        store: PathBuf,
+        /// Keep recording every this many seconds instead of once
+        #[arg(long)]
+        interval: Option<u64>,
+        #[command(flatten)]
+        markets: MarketsArg,
+    },
+    /// Print a vault's recorded NAV per share and its time weighted return
+    Show {
+        vault: Pubkey,
+        /// JSON lines file the snapshots were appended to
+        #[arg(long, default_value = "nav.jsonl")]
+        store: PathBuf,
+    },
+}
+
+fn cluster_url(url: &str) -> String {
+    match url {
+        "l" | "localhost" => "http://localhost:8899",
+        "d" | "devnet" => "https://api.devnet.solana.com",
+        "m" | "mainnet-beta" => "https://api.mainnet-beta.solana.com",
+        _ => url,
+    }
+    .to_string()
+}
+
+fn load_signer(source: Option<&String>) -> anyhow::Result<Box<dyn Signer + Send + Sync>> {
+    signer::load_signer(source.map(String::as_str).unwrap_or("default"))
+}
+
+/// Asks on stderr so `--json` output stays clean. Anything but `y` or `yes` declines.
+fn confirm(prompt: &str) -> anyhow::Result<bool> {
+    eprint!("{} [y/N] ", prompt);
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Fees and profit shares in PERCENTAGE_PRECISION, rejecting anything outside 0-100%.
+fn percent(value: f64) -> anyhow::Result<u32> {
+    if !(0.0..=100.0).contains(&value) {
+        return Err(anyhow::anyhow!(
+            "{} is not a percentage between 0 and 100",
+            value
+        ));
+    }
+    Ok((value / 100.0 * PERCENTAGE_PRECISION as f64).round() as u32)
+}
+
+async fn mint_decimals(client: &RpcClient, mint: &Pubkey) -> anyhow::Result<u8> {
+    Ok(get_mint(client, mint).await?.decimals)
+}
+
+fn signature_json(client: &RpcClient, signature: &Signature) -> Value {
+    json!({
+        "signature": signature.to_string(),
+        "explorer": signature_link(client, signature),
+    })
+}
+
+/// The registry's SOL/USDC market unless markets were given explicitly.
+async fn phoenix_markets(client: &RpcClient, markets: &MarketsArg) -> anyhow::Result<Vec<Pubkey>> {
+    if !markets.markets.is_empty() {
+        return Ok(markets.markets.clone());
+    }
+    let registry = phoenix_vaults::fetch_market_registry(client).await?;
+    Ok(vec![registry.sol_usdc_market])
+}
+
+/// The vault as a [`Fund`], picked by the program that owns it, and its deposit mint decimals.
+async fn load_fund<'a>(
+    client: &'a RpcClient,
+    vault: Pubkey,
+    markets: &MarketsArg,
+) -> anyhow::Result<(Box<dyn Fund + 'a>, u8)> {
+    let account = get_account(client, &vault).await?;
+    if account.owner == DRIFT_VAULTS_PROGRAM_ID {
+        let vault_acct = drift_vaults::fetch_vault(client, &vault).await?;
+        let spot_market =
+            drift_vaults::fetch_spot_market_info(client, vault_acct.spot_market_index).await?;
+        let decimals = mint_decimals(client, &spot_market.mint).await?;
+        Ok((Box::new(DriftFund::new(client, vault)), decimals))
+    } else if account.owner == PHOENIX_VAULTS_PROGRAM_ID {
+        let vault_acct = phoenix_vaults::fetch_vault(client, &vault).await?;
+        let decimals = mint_decimals(client, &vault_acct.usdc_mint).await?;
+        let markets = phoenix_markets(client, markets).await?;
+        Ok((Box::new(PhoenixFund::new(client, vault, markets)), decimals))
+    } else {
+        Err(anyhow::anyhow!(
+            "{} is not a vault, it is owned by {}",
+            vault,
+            account.owner
+        ))
+    }
+}
+
+async fn run(cli: Cli) -> anyhow::Result<Value> {
+    let client =
+        RpcClient::new_with_commitment(cluster_url(&cli.url), CommitmentConfig::confirmed());
+    match cli.command {
+        Command::Markets(cmd) => markets(&client, cli.keypair.as_ref(), cmd).await,
+        Command::Fund(cmd) => fund(&client, cli.keypair.as_ref(), cmd).await,
+        Command::Invest(cmd) => invest(&client, &*load_signer(cli.keypair.as_ref())?, cmd).await,
+        Command::Manager(cmd) => manager(&client, &*load_signer(cli.keypair.as_ref())?, cmd).await,
+        Command::Protocol(cmd) => protocol(&client, cli.keypair.as_ref(), cmd).await,
+        Command::Nav(cmd) => nav(&client, cli.json, cmd).await,
+        Command::Airdrop { amount, to } => {
+            let to = match to {
+                Some(to) => to,
+                None => load_signer(cli.keypair.as_ref())?.pubkey(),
+            };
+            let sig = airdrop(&client, &to, amount).await?;
+            Ok(signature_json(&client, &sig))
+        }
+    }
+}
+
+async fn markets(
+    client: &RpcClient,
+    keypair: Option<&String>,
+    cmd: MarketsCommand,
+) -> anyhow::Result<Value> {
+    match cmd {
+        MarketsCommand::Bootstrap { manifest } => {
+            let manifest = match manifest {
+                Some(path) => BootstrapManifest::load(&path)?,
+                None => BootstrapManifest::mock(),
+            };
+            // the manifest's authority owns the markets, so it pays unless a keypair is given
+            let payer: Box<dyn Signer + Send + Sync> = match (keypair, &manifest.authority) {
+                (None, Some(authority)) => Box::new(authority.load(&manifest.base_dir)?),
+                _ => load_signer(keypair)?,
+            };
+            let report = bootstrap_manifest(client, &*payer, &manifest).await?;
+            let created: Vec<Value> = report
+                .steps
+                .iter()
+                .filter_map(|step| match step.status {
+                    BootstrapStatus::Created(sig) => {
+                        let mut out = signature_json(client, &sig);
+                        out["name"] = json!(step.name);
+                        out["address"] = json!(step.address.to_string());
+                        Some(out)
+                    }
+                    _ => None,
+                })
+                .collect();
+            Ok(json!({
+                "markets": report.markets,
+                "created": created,
+                "lamportsSpent": report.lamports_spent,
+            }))
+        }
+        MarketsCommand::Inspect { market, levels } => {
+            let market = PhoenixMarket::fetch(client, &market).await?;
+            let ladder = market.ladder(levels)?;
+            let levels = |orders: &[phoenix::state::markets::LadderOrder]| {
+                orders
+                    .iter()
+                    .map(|l| {
+                        Ok(json!({
+                            "price": market.ticks_to_price(l.price_in_ticks),
+                            "size": market.base_lots_to_amount(l.size_in_base_lots)?.ui(),
+                        }))
+                    })
+                    .collect::<anyhow::Result<Vec<Value>>>()
+            };
+            Ok(json!({
+                "market": market.key.to_string(),
+                "baseMint": market.base_mint().to_string(),
+                "quoteMint": market.quote_mint().to_string(),
+                "baseDecimals": market.base_decimals(),
+                "quoteDecimals": market.quote_decimals(),
+                "baseAtomsPerBaseLot": market.base_atoms_per_base_lot(),
+                "quoteAtomsPerQuoteLot": market.quote_atoms_per_quote_lot(),
+                "tickSizeInQuoteAtomsPerBaseUnit": market.tick_size_in_quote_atoms_per_base_unit(),
+                "bids": levels(&ladder.bids)?,
+                "asks": levels(&ladder.asks)?,
+            }))
+        }
+    }
+}
+
+fn overview_json(overview: &FundOverview) -> anyhow::Result<Value> {
+    Ok(serde_json::to_value(overview)?)
+}
+
+async fn fund(
+    client: &RpcClient,
+    keypair: Option<&String>,
+    cmd: FundCommand,
+) -> anyhow::Result<Value> {
+    match cmd {
+        FundCommand::Create(args) => {
+            let manager = load_signer(keypair)?;
+            let (vault, sig) = match args.venue {
+                VenueArg::Drift => {
+                    let spot_market =
+                        drift_vaults::fetch_spot_market_info(client, args.spot_market_index)
+                            .await?;
+                    let decimals = mint_decimals(client, &spot_market.mint).await?;
+                    let params = drift_vaults::VaultParams {
+                        name: encode_name(&args.name),
+                        spot_market_index: args.spot_market_index,
+                        redeem_period: args.redeem_period,
+                        max_tokens: args.max_tokens.to_atoms(decimals)?,
+                        management_fee: percent(args.management_fee)? as i64,
+                        min_deposit_amount: args.min_deposit_amount.to_atoms(decimals)?,
+                        profit_share: percent(args.profit_share)?,
+                        hurdle_rate: percent(args.hurdle_rate)?,
+                        permissioned: args.permissioned,
+                    };
+                    drift_vaults::initialize_vault(client, &*manager, params).await?
+                }
+                VenueArg::Phoenix => {
+                    let registry = phoenix_vaults::fetch_market_registry(client).await?;
+                    let decimals = mint_decimals(client, &registry.usdc_mint).await?;
+                    let params = phoenix_vaults::VaultParams {
+                        name: encode_name(&args.name),
+                        redeem_period: args.redeem_period,
+                        max_tokens: args.max_tokens.to_atoms(decimals)?,
+                        management_fee: percent(args.management_fee)? as i64,
+                        min_deposit_amount: args.min_deposit_amount.to_atoms(decimals)?,
+                        profit_share: percent(args.profit_share)?,
+                        hurdle_rate: percent(args.hurdle_rate)?,
+                        permissioned: args.permissioned,
+                        protocol: args.protocol.unwrap_or(manager.pubkey()),
+                        protocol_fee: percent(args.protocol_fee)? as u64,
+                        protocol_profit_share: percent(args.protocol_profit_share)?,
+                    };
+                    phoenix_vaults::initialize_vault(client, &*manager, params).await?
+                }
+            };
+            let mut out = signature_json(client, &sig);
+            out["vault"] = json!(vault.to_string());
+            Ok(out)
+        }
+        FundCommand::Update {
+            vault,
+            settings,
+            delegate,
+            dry_run,
+            yes,
+        } => {
+            let (fund, decimals) =
+                load_fund(client, vault, &MarketsArg { markets: vec![] }).await?;
+            let config = UpdateVaultConfig {
+                redeem_period: settings.redeem_period,
+                max_tokens: settings
+                    .max_tokens
+                    .map(|v| v.to_atoms(decimals))
+                    .transpose()?,
+                management_fee: settings
+                    .management_fee
+                    .map(|v| percent(v).map(|v| v as i64))
+                    .transpose()?,
+                min_deposit_amount: settings
+                    .min_deposit_amount
+                    .map(|v| v.to_atoms(decimals))
+                    .transpose()?,
+                profit_share: settings.profit_share.map(percent).transpose()?,
+                hurdle_rate: settings.hurdle_rate.map(percent).transpose()?,
+                permissioned: settings.permissioned,
+                delegate,
+            };
+            let plan = fund.plan_update(&config).await?;
+            let changes: Vec<Value> = plan
+                .changes
+                .iter()
+                .map(|c| json!({ "field": c.field, "before": c.before, "after": c.after }))
+                .collect();
+            if dry_run {
+                return Ok(json!({ "vault": vault.to_string(), "changes": changes }));
+            }
+            let manager = load_signer(keypair)?;
+            if !plan.is_empty() {
+                eprint!("{}", plan);
+                if !yes && !confirm("Send this update?")? {
+                    return Err(anyhow::anyhow!("Update cancelled"));
+                }
+            }
+            let sig = fund.send_update(&*manager, &plan).await?;
+            Ok(json!({
+                "vault": vault.to_string(),
+                "changes": changes,
+                "signature": sig.map(|s| s.to_string()),
+            }))
+        }
+        FundCommand::Show { vault, markets } => {
+            let (fund, _) = load_fund(client, vault, &markets).await?;
+            overview_json(&fund.overview().await?)
+        }
+        FundCommand::List { venue, markets } => {
+            let mut overviews = vec![];
+            if !matches!(venue, Some(VenueArg::Phoenix)) {
+                overviews.extend(drift_vaults::fetch_fund_overviews(client).await?);
+            }
+            if !matches!(venue, Some(VenueArg::Drift)) {
+                let markets = phoenix_markets(client, &markets).await?;
+                overviews.extend(phoenix_vaults::fetch_fund_overviews(client, &markets).await?);
+            }
+            overviews.sort_by(|a, b| b.tvl.total_cmp(&a.tvl));
+            Ok(Value::Array(
+                overviews
+                    .iter()
+                    .map(overview_json)
+                    .collect::<anyhow::Result<_>>()?,
+            ))
+        }
+    }
+}
+
+async fn invest(
+    client: &RpcClient,
+    investor: &(dyn Signer + Sync),
+    cmd: InvestCommand,
+) -> anyhow::Result<Value> {
+    let sig = match cmd {
+        InvestCommand::Deposit {
+            vault,
+            amount,
+            markets,
+        } => {
+            let (fund, decimals) = load_fund(client, vault, &markets).await?;
+            fund.deposit(investor, amount.with_decimals(decimals)?)
+                .await?
+        }
+        InvestCommand::RequestWithdraw {
+            vault,
+            amount,
+            markets,
+        } => {
+            let (fund, decimals) = load_fund(client, vault, &markets).await?;
+            fund.request_withdraw(investor, amount.with_decimals(decimals)?)
+                .await?
+        }
+        InvestCommand::Cancel { vault, markets } => {
+            let (fund, _) = load_fund(client, vault, &markets).await?;
+            fund.cancel_withdraw_request(investor).await?
+        }
+        InvestCommand::Withdraw { vault, markets } => {
+            let (fund, _) = load_fund(client, vault, &markets).await?;
+            fund.withdraw(investor).await?
+        }
+    };
+    Ok(signature_json(client, &sig))
+}
+
+async fn manager(
+    client: &RpcClient,
+    manager: &(dyn Signer + Sync),
+    cmd: ManagerCommand,
+) -> anyhow::Result<Value> {
+    match cmd {
+        ManagerCommand::Deposit {
+            vault,
+            amount,
+            markets,
+        } => {
+            let (fund, decimals) = load_fund(client, vault, &markets).await?;
+            let sig = fund
+                .manager_deposit(manager, amount.with_decimals(decimals)?)
+                .await?;
+            Ok(signature_json(client, &sig))
+        }
+        ManagerCommand::RequestWithdraw {
+            vault,
+            amount,
+            markets,
+        } => {
+            let (fund, decimals) = load_fund(client, vault, &markets).await?;
+            let sig = fund
+                .manager_request_withdraw(manager, amount.with_decimals(decimals)?)
+                .await?;
+            Ok(signature_json(client, &sig))
+        }
+        ManagerCommand::Cancel { vault, markets } => {
+            let (fund, _) = load_fund(client, vault, &markets).await?;
+            let sig = fund.manager_cancel_withdraw_request(manager).await?;
+            Ok(signature_json(client, &sig))
+        }
+        ManagerCommand::Withdraw { vault, markets } => {
+            let (fund, _) = load_fund(client, vault, &markets).await?;
+            let sig = fund.manager_withdraw(manager).await?;
+            Ok(signature_json(client, &sig))
+        }
+        ManagerCommand::DelegateStatus { vault } => {
+            let status = drift_vaults::fetch_delegate_status(client, &vault).await?;
+            Ok(json!({
+                "vault": status.vault.to_string(),
+                "vaultDelegate": status.vault_delegate.to_string(),
+                "userDelegate": status.user_delegate.to_string(),
+                "synced": status.is_synced(),
+            }))
+        }
+        ManagerCommand::RotateDelegate { vault, delegate } => {
+            let (previous, sig) =
+                drift_vaults::rotate_delegate(client, manager, &vault, &delegate).await?;
+            let mut json = signature_json(client, &sig);
+            json["previousDelegate"] = json!(previous.to_string());
+            json["delegate"] = json!(delegate.to_string());
+            Ok(json)
+        }
+        ManagerCommand::ResetDelegate { vault } => {
+            let sig = drift_vaults::reset_delegate(client, manager, &vault).await?;
+            Ok(signature_json(client, &sig))
+        }
+        ManagerCommand::MarginTrading { vault, enabled } => {
+            let sig = drift_vaults::update_margin_trading_enabled(client, manager, &vault, enabled)
+                .await?;
+            Ok(signature_json(client, &sig))
+        }
+        ManagerCommand::Keeper { vaults, dry_run } => {
+            let reports = drift_vaults::run_keeper(client, manager, &vaults, dry_run).await;
+            Ok(Value::Array(reports.iter().map(keeper_json).collect()))
+        }
+    }
+}
+
+fn keeper_json(report: &drift_vaults::KeeperReport) -> Value {
+    let results: Vec<Value> = report
+        .results
+        .iter()
+        .map(|(action, outcome)| match outcome {
+            drift_vaults::KeeperOutcome::Planned => json!({ "action": action.to_string() }),
+            drift_vaults::KeeperOutcome::Sent(sig) => {
+                json!({ "action": action.to_string(), "signature": sig.to_string() })
+            }
+            drift_vaults::KeeperOutcome::Failed(error) => {
+                json!({ "action": action.to_string(), "error": error })
+            }
+        })
+        .collect();
+    let skipped: Vec<Value> = report
+        .skipped
+        .iter()
+        .map(|(key, error)| json!({ "vaultDepositor": key.to_string(), "error": error }))
+        .collect();
+    json!({
+        "vault": report.vault.to_string(),
+        "actions": results,
+        "skipped": skipped,
+        "error": report.error,
+    })
+}
+
+async fn protocol(
+    client: &RpcClient,
+    keypair: Option<&String>,
+    cmd: ProtocolCommand,
+) -> anyhow::Result<Value> {
+    match cmd {
+        ProtocolCommand::RequestWithdraw {
+            vault,
+            amount,
+            markets,
+        } => {
+            let (fund, decimals) = load_fund(client, vault, &markets).await?;
+            let sig = fund
+                .protocol_request_withdraw(&*load_signer(keypair)?, amount.with_decimals(decimals)?)
+                .await?;
+            Ok(signature_json(client, &sig))
+        }
+        ProtocolCommand::Cancel { vault, markets } => {
+            let (fund, _) = load_fund(client, vault, &markets).await?;
+            let sig = fund
+                .protocol_cancel_withdraw_request(&*load_signer(keypair)?)
+                .await?;
+            Ok(signature_json(client, &sig))
+        }
+        ProtocolCommand::Withdraw { vault, markets } => {
+            let (fund, _) = load_fund(client, vault, &markets).await?;
+            let sig = fund.protocol_withdraw(&*load_signer(keypair)?).await?;
+            Ok(signature_json(client, &sig))
+        }
+        ProtocolCommand::Registry => {
+            let registry = phoenix_vaults::fetch_market_registry(client).await?;
+            Ok(json!({
+                "address": phoenix_vaults::get_market_registry_address().0.to_string(),
+                "authority": registry.authority.to_string(),
+                "solUsdcMarket": registry.sol_usdc_market.to_string(),
+                "usdcMint": registry.usdc_mint.to_string(),
+                "solMint": registry.sol_mint.to_string(),
+            }))
+        }
+        ProtocolCommand::Fees { vault } => {
+            let account = get_account(client, &vault).await?;
+            if account.owner == PHOENIX_VAULTS_PROGRAM_ID {
+                let vault = phoenix_vaults::fetch_vault(client, &vault).await?;
+                return Ok(json!({
+                    "protocol": vault.protocol.to_string(),
+                    "protocolFee": vault.protocol_fee,
+                    "protocolProfitShare": vault.protocol_profit_share,
+                    "protocolTotalFee": vault.protocol_total_fee,
+                    "protocolTotalProfitShare": vault.protocol_total_profit_share,
+                    "protocolTotalWithdraws": vault.protocol_total_withdraws,
+                }));
+            }
+            let vault_acct = drift_vaults::fetch_vault(client, &vault).await?;
+            if !vault_acct.vault_protocol {
+                return Ok(Value::Null);
+            }
+            let vp = drift_vaults::fetch_vault_protocol(client, &vault).await?;
+            Ok(json!({
+                "protocol": vp.protocol.to_string(),
+                "protocolFee": vp.protocol_fee,
+                "protocolProfitShare": vp.protocol_profit_share,
+                "protocolTotalFee": vp.protocol_total_fee,
+                "protocolTotalProfitShare": vp.protocol_total_profit_share,
+                "protocolTotalWithdraws": vp.protocol_total_withdraws,
+            }))
+        }
+    }
+}
+
+async fn nav(client: &RpcClient, json: bool, cmd: NavCommand) -> anyhow::Result<Value> {
+    match cmd {
+        NavCommand::Record {
+            vaults,
+            store,
+            interval,
+            markets,
+        } => {
+            let store = NavStore::new(store);
+            let mut history = store.load()?;
+            let mut funds = Vec::with_capacity(vaults.len());
+            for vault in vaults {
+                funds.push(load_fund(client, vault, &markets).await?.0);
+            }
+            loop {
+                let results: Vec<Value> = store
+                    .record_funds(&mut history, &funds)
+                    .await
+                    .into_iter()
+                    .map(|(vault, result)| match result {
+                        Ok(snapshot) => nav_snapshot_json(&snapshot),
+                        Err(e) => json!({ "vault": vault.to_string(), "error": e.to_string() }),
+                    })
+                    .collect();
+                let Some(interval) = interval else {
+                    return Ok(Value::Array(results));
+                };
+                print_value(&Value::Array(results), json)?;
+                tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+            }
+        }
+        NavCommand::Show { vault, store } => {
+            let history = NavStore::new(store).load()?;
+            let series = history.series(&vault);
+            let twr = match (series.first(), series.last()) {
+                (Some(first), Some(last)) => {
+                    history.time_weighted_return(&vault, first.ts, last.ts)
+                }
+                _ => None,
+            };
+            Ok(json!({
+                "vault": vault.to_string(),
+                "timeWeightedReturn": twr,
+                "snapshots": series.iter().map(nav_snapshot_json).collect::<Vec<_>>(),
+            }))
+        }
+    }
+}
+
+fn nav_snapshot_json(snapshot: &NavSnapshot) -> Value {
+    json!({
+        "vault": snapshot.vault.to_string(),
+        "ts": snapshot.ts,
+        "equity": snapshot.equity,
+        "totalShares": snapshot.total_shares.to_string(),
+        "navPerShare": snapshot.nav_per_share(),
+    })
+}
+
+/// `key: value` lines, nesting objects and arrays by indentation.
+fn print_human(value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match value {
+                    Value::Object(_) | Value::Array(_) => {
+                        println!("{}{}:", pad, key);
+                        print_human(value, indent + 2);
+                    }
+                    _ => println!("{}{}: {}", pad, key, scalar(value)),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                match item {
+                    Value::Object(_) | Value::Array(_) => {
+                        if i > 0 {
+                            println!();
+                        }
+                        print_human(item, indent);
+                    }
+                    _ => println!("{}- {}", pad, scalar(item)),
+                }
+            }
+        }
+        _ => println!("{}{}", pad, scalar(value)),
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "-".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+fn print_value(value: &Value, json: bool) -> anyhow::Result<()> {
+    if json {
+        println!("{}", serde_json::to_string(value)?);
+    } else {
+        print_human(value, 0);
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let json = cli.json;
+    let value = run(cli).await?;
+    print_value(&value, json)
+}
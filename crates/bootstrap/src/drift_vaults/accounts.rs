@@ -0,0 +1,204 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::fund::{FundSummary, Venue};
+use crate::vault_update::VaultSettings;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WithdrawRequest {
+    /// request shares of vault withdraw
+    pub shares: u128,
+    /// requested value (in vault spot_market_index) of shares for withdraw
+    pub value: u64,
+    /// request ts of vault withdraw
+    pub ts: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Vault {
+    /// The name of the vault. Vault pubkey is derived from this name.
+    pub name: [u8; 32],
+    /// The vault's pubkey. It is a pda of name and also used as the authority for drift user
+    pub pubkey: Pubkey,
+    /// The manager of the vault who has ability to update vault params
+    pub manager: Pubkey,
+    /// The vaults token account. Used to receive tokens between deposits and withdrawals
+    pub token_account: Pubkey,
+    /// The drift user stats account for the vault
+    pub user_stats: Pubkey,
+    /// The drift user account for the vault
+    pub user: Pubkey,
+    /// The vaults designated delegate for drift user account
+    /// can differ from actual user delegate if vault is in liquidation
+    pub delegate: Pubkey,
+    /// The delegate handling liquidation for depositor
+    pub liquidation_delegate: Pubkey,
+    /// The sum of all shares held by the users (vault depositors)
+    pub user_shares: u128,
+    /// The sum of all shares: deposits from users, manager deposits, manager profit/fee, and protocol profit/fee.
+    pub total_shares: u128,
+    /// Last fee update unix timestamp
+    pub last_fee_update_ts: i64,
+    /// When the liquidation starts
+    pub liquidation_start_ts: i64,
+    /// The period (in seconds) that a vault depositor must wait after requesting a withdrawal to finalize withdrawal.
+    pub redeem_period: i64,
+    /// The sum of all outstanding withdraw requests
+    pub total_withdraw_requested: u64,
+    /// Max token capacity, once hit/passed vault will reject new deposits (updatable)
+    pub max_tokens: u64,
+    /// The annual fee charged on deposits by the manager.
+    pub management_fee: i64,
+    /// Timestamp vault initialized
+    pub init_ts: i64,
+    /// The net deposits for the vault
+    pub net_deposits: i64,
+    /// The net deposits for the manager
+    pub manager_net_deposits: i64,
+    /// Total deposits
+    pub total_deposits: u64,
+    /// Total withdraws
+    pub total_withdraws: u64,
+    /// Total deposits for the manager
+    pub manager_total_deposits: u64,
+    /// Total withdraws for the manager
+    pub manager_total_withdraws: u64,
+    /// Total management fee accrued by the manager
+    pub manager_total_fee: i64,
+    /// Total profit share accrued by the manager
+    pub manager_total_profit_share: u64,
+    /// The minimum deposit amount
+    pub min_deposit_amount: u64,
+    pub last_manager_withdraw_request: WithdrawRequest,
+    /// The base 10 exponent of the shares (given massive share inflation can occur at near zero vault equity)
+    pub shares_base: u32,
+    /// Percentage the manager charges on all profits realized by depositors: PERCENTAGE_PRECISION
+    pub profit_share: u32,
+    /// Vault manager only collect incentive fees during periods when returns are higher than this amount: PERCENTAGE_PRECISION
+    pub hurdle_rate: u32,
+    /// The spot market index the vault deposits into/withdraws from
+    pub spot_market_index: u16,
+    /// The bump for the vault pda
+    pub bump: u8,
+    /// Whether anybody can be a depositor
+    pub permissioned: bool,
+    /// The optional [`VaultProtocol`] account.
+    pub vault_protocol: bool,
+    pub padding1: [u8; 7],
+    pub padding: [u64; 7],
+}
+
+impl Discriminator for Vault {
+    const DISCRIMINATOR: [u8; 8] = [211, 8, 232, 43, 2, 152, 117, 119];
+}
+
+impl Vault {
+    /// `deposit_decimals` are the decimals of the mint the vault takes deposits in.
+    pub fn settings(&self, deposit_decimals: u8) -> VaultSettings {
+        VaultSettings {
+            redeem_period: self.redeem_period,
+            max_tokens: self.max_tokens,
+            management_fee: self.management_fee,
+            min_deposit_amount: self.min_deposit_amount,
+            profit_share: self.profit_share,
+            hurdle_rate: self.hurdle_rate,
+            permissioned: self.permissioned,
+            delegate: self.delegate,
+            total_withdraw_requested: self.total_withdraw_requested,
+            deposit_decimals,
+        }
+    }
+
+    /// Drift vaults keep protocol fees on a separate [`VaultProtocol`], if the vault has one.
+    pub fn fund_summary(&self, vault_protocol: Option<&VaultProtocol>) -> FundSummary {
+        FundSummary {
+            vault: self.pubkey,
+            manager: self.manager,
+            venue: Venue::Drift,
+            name: self.name,
+            init_ts: self.init_ts,
+            manager_total_profit_share: self.manager_total_profit_share,
+            protocol_total_profit_share: vault_protocol
+                .map(|vp| vp.protocol_total_profit_share)
+                .unwrap_or(0),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VaultDepositor {
+    /// The vault deposited into
+    pub vault: Pubkey,
+    /// The vault depositor account's pubkey. It is a pda of vault and authority
+    pub pubkey: Pubkey,
+    /// The authority is the address w permission to deposit/withdraw
+    pub authority: Pubkey,
+    /// share of vault owned by this depositor. vault_shares / vault.total_shares is depositor's ownership of vault_equity
+    pub vault_shares: u128,
+    /// last withdraw request
+    pub last_withdraw_request: WithdrawRequest,
+    /// creation ts of vault depositor
+    pub last_valid_ts: i64,
+    /// lifetime net deposits of vault depositor for the vault
+    pub net_deposits: i64,
+    /// lifetime total deposits
+    pub total_deposits: u64,
+    /// lifetime total withdraws
+    pub total_withdraws: u64,
+    /// the token amount of gains the vault depositor has paid performance fees on
+    pub cumulative_profit_share_amount: i64,
+    pub profit_share_fee_paid: u64,
+    /// the exponent for vault_shares decimal places
+    pub vault_shares_base: u32,
+    pub padding1: u32,
+    pub padding: [u64; 8],
+}
+
+impl Discriminator for VaultDepositor {
+    const DISCRIMINATOR: [u8; 8] = [87, 109, 182, 106, 87, 96, 63, 211];
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VaultProtocol {
+    /// The protocol, company, or entity that services the product using this vault.
+    pub protocol: Pubkey,
+    /// The shares from profit share and annual fee unclaimed by the protocol.
+    pub protocol_profit_and_fee_shares: u128,
+    /// The annual fee charged on deposits by the protocol.
+    pub protocol_fee: u64,
+    /// Total withdraws for the protocol
+    pub protocol_total_withdraws: u64,
+    /// Total fee charged by the protocol (annual management fee + profit share).
+    pub protocol_total_fee: u64,
+    /// Total profit share charged by the protocol
+    pub protocol_total_profit_share: u64,
+    pub last_protocol_withdraw_request: WithdrawRequest,
+    /// Percentage the protocol charges on all profits realized by depositors: PERCENTAGE_PRECISION
+    pub protocol_profit_share: u32,
+    pub bump: u8,
+    pub version: u8,
+    pub padding: [u8; 2],
+}
+
+impl Discriminator for VaultProtocol {
+    const DISCRIMINATOR: [u8; 8] = [106, 130, 5, 195, 126, 82, 249, 53];
+}
+
+/// Drift program account tracking a staker's share of a spot market's insurance fund.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct InsuranceFundStake {
+    pub authority: Pubkey,
+    pub if_shares: u128,
+    pub last_withdraw_request_shares: u128,
+    pub if_base: u128,
+    pub last_valid_ts: i64,
+    pub last_withdraw_request_value: u64,
+    pub last_withdraw_request_ts: i64,
+    pub cost_basis: i64,
+    pub market_index: u16,
+    pub padding: [u8; 14],
+}
+
+impl Discriminator for InsuranceFundStake {
+    const DISCRIMINATOR: [u8; 8] = [110, 202, 14, 42, 95, 73, 90, 95];
+}
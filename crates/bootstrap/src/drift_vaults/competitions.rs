@@ -0,0 +1,64 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+use crate::helpers::{get_account, send_and_confirm_tx, SignerRef};
+
+use super::*;
+
+/// drift_competitions `Competitor` starts with the discriminator, authority, competition and user stats.
+const COMPETITOR_AUTHORITY_OFFSET: usize = 8;
+const COMPETITOR_COMPETITION_OFFSET: usize = COMPETITOR_AUTHORITY_OFFSET + 32;
+const COMPETITOR_USER_STATS_OFFSET: usize = COMPETITOR_COMPETITION_OFFSET + 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompetitorInfo {
+    pub pubkey: Pubkey,
+    pub authority: Pubkey,
+    pub competition: Pubkey,
+    pub user_stats: Pubkey,
+}
+
+fn read_pubkey(data: &[u8], offset: usize, key: &Pubkey) -> anyhow::Result<Pubkey> {
+    let bytes = data
+        .get(offset..offset + 32)
+        .ok_or(anyhow::anyhow!("Competitor account too small: {:?}", key))?;
+    Ok(Pubkey::try_from(bytes)?)
+}
+
+impl CompetitorInfo {
+    /// Reads the fields of the `Competitor` account at `pubkey`.
+    pub fn parse(pubkey: Pubkey, data: &[u8]) -> anyhow::Result<Self> {
+        Ok(Self {
+            pubkey,
+            authority: read_pubkey(data, COMPETITOR_AUTHORITY_OFFSET, &pubkey)?,
+            competition: read_pubkey(data, COMPETITOR_COMPETITION_OFFSET, &pubkey)?,
+            user_stats: read_pubkey(data, COMPETITOR_USER_STATS_OFFSET, &pubkey)?,
+        })
+    }
+}
+
+pub async fn enroll_competitor<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    vault: &Pubkey,
+    competition: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let ix = initialize_competitor_ix(vault, &manager.pubkey(), &manager.pubkey(), competition);
+    send_and_confirm_tx(client, manager, &[ix], &[&SignerRef(manager)]).await
+}
+
+/// The vault's entry in `competition`, if it has enrolled.
+pub async fn fetch_competitor(
+    client: &RpcClient,
+    competition: &Pubkey,
+    vault: &Pubkey,
+) -> anyhow::Result<Option<CompetitorInfo>> {
+    let pubkey = get_competitor_address(competition, vault).0;
+    let account = match get_account(client, &pubkey).await {
+        Ok(account) => account,
+        Err(_) => return Ok(None),
+    };
+    Ok(Some(CompetitorInfo::parse(pubkey, &account.data)?))
+}
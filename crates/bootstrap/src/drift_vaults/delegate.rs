@@ -0,0 +1,112 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+use crate::helpers::{get_account, send_and_confirm_tx, SignerRef};
+
+use super::*;
+
+/// Drift `User` accounts start with the discriminator, authority and then the delegate.
+const USER_DELEGATE_OFFSET: usize = 8 + 32;
+
+/// The delegate recorded on the vault versus the one Drift will actually let trade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DelegateStatus {
+    pub vault: Pubkey,
+    pub vault_delegate: Pubkey,
+    pub user_delegate: Pubkey,
+}
+
+impl DelegateStatus {
+    pub fn is_synced(&self) -> bool {
+        self.vault_delegate == self.user_delegate
+    }
+}
+
+pub async fn fetch_user_delegate(client: &RpcClient, user: &Pubkey) -> anyhow::Result<Pubkey> {
+    let account = get_account(client, user).await?;
+    let bytes = account
+        .data
+        .get(USER_DELEGATE_OFFSET..USER_DELEGATE_OFFSET + 32)
+        .ok_or(anyhow::anyhow!("Drift user account too small: {:?}", user))?;
+    Ok(Pubkey::try_from(bytes)?)
+}
+
+pub async fn fetch_delegate_status(
+    client: &RpcClient,
+    vault: &Pubkey,
+) -> anyhow::Result<DelegateStatus> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let user_delegate = fetch_user_delegate(client, &vault_acct.user).await?;
+    Ok(DelegateStatus {
+        vault: *vault,
+        vault_delegate: vault_acct.delegate,
+        user_delegate,
+    })
+}
+
+/// Errors unless the Drift `User` delegate matches the vault's delegate.
+pub async fn verify_delegate(client: &RpcClient, vault: &Pubkey) -> anyhow::Result<Pubkey> {
+    let status = fetch_delegate_status(client, vault).await?;
+    if !status.is_synced() {
+        return Err(anyhow::anyhow!(
+            "Vault {} delegate {} does not match Drift user delegate {}",
+            vault,
+            status.vault_delegate,
+            status.user_delegate
+        ));
+    }
+    Ok(status.vault_delegate)
+}
+
+pub async fn update_delegate<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    vault: &Pubkey,
+    delegate: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let ix = update_delegate_ix(vault, &manager.pubkey(), delegate)?;
+    send_and_confirm_tx(client, manager, &[ix], &[&SignerRef(manager)]).await
+}
+
+pub async fn reset_delegate<S: Signer + ?Sized>(
+    client: &RpcClient,
+    authority: &S,
+    vault: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let ix = reset_delegate_ix(vault, &authority.pubkey());
+    send_and_confirm_tx(client, authority, &[ix], &[&SignerRef(authority)]).await
+}
+
+/// Replaces a (possibly compromised) delegate and confirms Drift picked up the new key.
+/// Returns the replaced delegate.
+pub async fn rotate_delegate<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    vault: &Pubkey,
+    new_delegate: &Pubkey,
+) -> anyhow::Result<(Pubkey, Signature)> {
+    let before = fetch_delegate_status(client, vault).await?;
+    let sig = update_delegate(client, manager, vault, new_delegate).await?;
+    let delegate = verify_delegate(client, vault).await?;
+    if delegate != *new_delegate {
+        return Err(anyhow::anyhow!(
+            "Vault {} delegate is {} after rotation, expected {}",
+            vault,
+            delegate,
+            new_delegate
+        ));
+    }
+    Ok((before.vault_delegate, sig))
+}
+
+pub async fn update_margin_trading_enabled<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    vault: &Pubkey,
+    enabled: bool,
+) -> anyhow::Result<Signature> {
+    let ix = update_margin_trading_enabled_ix(vault, &manager.pubkey(), enabled)?;
+    send_and_confirm_tx(client, manager, &[ix], &[&SignerRef(manager)]).await
+}
@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::fund::DepositPrice;
+use crate::helpers::{get_account, get_multiple_accounts};
+
+use super::*;
+
+/// `User.spot_positions`: 8 positions of 40 bytes after the discriminator, authority, delegate and name.
+const USER_SPOT_POSITIONS_OFFSET: usize = 8 + 32 + 32 + 32;
+const SPOT_POSITION_SIZE: usize = 40;
+/// `User.perp_positions`: 8 positions of 96 bytes right after the spot positions.
+const USER_PERP_POSITIONS_OFFSET: usize = USER_SPOT_POSITIONS_OFFSET + 8 * SPOT_POSITION_SIZE;
+const PERP_POSITION_SIZE: usize = 96;
+
+const SPOT_MARKET_LAST_ORACLE_PRICE_OFFSET: usize = 168;
+const SPOT_MARKET_CUMULATIVE_DEPOSIT_INTEREST_OFFSET: usize = 464;
+const SPOT_MARKET_CUMULATIVE_BORROW_INTEREST_OFFSET: usize = 480;
+const SPOT_MARKET_DECIMALS_OFFSET: usize = 680;
+/// `PerpMarket.amm.historical_oracle_data.last_oracle_price`
+const PERP_MARKET_LAST_ORACLE_PRICE_OFFSET: usize = 72;
+
+/// Drift `PRICE_PRECISION`
+pub const PRICE_PRECISION: i128 = 1_000_000;
+/// Drift `BASE_PRECISION`
+pub const BASE_PRECISION: i128 = 1_000_000_000;
+/// Drift scales spot balances to 19 digits, no spot market has more decimals.
+const SPOT_BALANCE_DECIMALS: u32 = 19;
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> anyhow::Result<[u8; N]> {
+    let bytes = data.get(offset..offset + N).ok_or(anyhow::anyhow!(
+        "Drift account too small to read offset {}",
+        offset
+    ))?;
+    Ok(bytes.try_into()?)
+}
+
+fn read_i64(data: &[u8], offset: usize) -> anyhow::Result<i64> {
+    Ok(i64::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> anyhow::Result<u64> {
+    Ok(u64::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_u128(data: &[u8], offset: usize) -> anyhow::Result<u128> {
+    Ok(u128::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> anyhow::Result<u16> {
+    Ok(u16::from_le_bytes(read_bytes(data, offset)?))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DriftSpotPosition {
+    pub market_index: u16,
+    pub scaled_balance: u64,
+    pub is_borrow: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DriftPerpPosition {
+    pub market_index: u16,
+    /// BASE_PRECISION
+    pub base_asset_amount: i64,
+    /// QUOTE_PRECISION
+    pub quote_asset_amount: i64,
+}
+
+/// Non-empty spot and perp positions of a Drift `User`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DriftUserPositions {
+    pub spot: Vec<DriftSpotPosition>,
+    pub perp: Vec<DriftPerpPosition>,
+}
+
+impl DriftUserPositions {
+    pub fn decode(data: &[u8]) -> anyhow::Result<Self> {
+        let mut positions = Self::default();
+        for i in 0..8 {
+            let offset = USER_SPOT_POSITIONS_OFFSET + i * SPOT_POSITION_SIZE;
+            let scaled_balance = read_u64(data, offset)?;
+            if scaled_balance == 0 {
+                continue;
+            }
+            positions.spot.push(DriftSpotPosition {
+                market_index: read_u16(data, offset + 32)?,
+                scaled_balance,
+                is_borrow: read_bytes::<1>(data, offset + 34)?[0] == 1,
+            });
+        }
+        for i in 0..8 {
+            let offset = USER_PERP_POSITIONS_OFFSET + i * PERP_POSITION_SIZE;
+            let base_asset_amount = read_i64(data, offset + 8)?;
+            let quote_asset_amount = read_i64(data, offset + 16)?;
+            if base_asset_amount == 0 && quote_asset_amount == 0 {
+                continue;
+            }
+            positions.perp.push(DriftPerpPosition {
+                market_index: read_u16(data, offset + 92)?,
+                base_asset_amount,
+                quote_asset_amount,
+            });
+        }
+        Ok(positions)
+    }
+
+    /// The markets Drift needs as remaining accounts to value these positions.
+    pub fn markets(&self) -> DriftMarkets {
+        DriftMarkets {
+            spot_markets: self.spot.iter().map(|p| p.market_index).collect(),
+            perp_markets: self.perp.iter().map(|p| p.market_index).collect(),
+        }
+    }
+}
+
+/// Fields of a Drift `SpotMarket` needed to turn scaled balances into token amounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpotMarketBalances {
+    pub decimals: u32,
+    pub cumulative_deposit_interest: u128,
+    pub cumulative_borrow_interest: u128,
+    /// PRICE_PRECISION, as last cached by Drift.
+    pub last_oracle_price: i64,
+}
+
+impl SpotMarketBalances {
+    pub fn decode(data: &[u8]) -> anyhow::Result<Self> {
+        Ok(Self {
+            decimals: u32::from_le_bytes(read_bytes(data, SPOT_MARKET_DECIMALS_OFFSET)?),
+            cumulative_deposit_interest: read_u128(
+                data,
+                SPOT_MARKET_CUMULATIVE_DEPOSIT_INTEREST_OFFSET,
+            )?,
+            cumulative_borrow_interest: read_u128(
+                data,
+                SPOT_MARKET_CUMULATIVE_BORROW_INTEREST_OFFSET,
+            )?,
+            last_oracle_price: read_i64(data, SPOT_MARKET_LAST_ORACLE_PRICE_OFFSET)?,
+        })
+    }
+
+    /// Fails for more decimals than Drift's balance precision, which the conversions below
+    /// would underflow or overflow on.
+    fn check_decimals(&self) -> anyhow::Result<()> {
+        if self.decimals > SPOT_BALANCE_DECIMALS {
+            return Err(anyhow::anyhow!(
+                "Spot market has {} decimals, more than Drift's {}",
+                self.decimals,
+                SPOT_BALANCE_DECIMALS
+            ));
+        }
+        Ok(())
+    }
+
+    /// Signed token amount (in the market's own decimals) of a scaled balance.
+    pub fn token_amount(&self, position: &DriftSpotPosition) -> anyhow::Result<i128> {
+        self.check_decimals()?;
+        let interest = if position.is_borrow {
+            self.cumulative_borrow_interest
+        } else {
+            self.cumulative_deposit_interest
+        };
+        let precision_decrease = 10_u128.pow(SPOT_BALANCE_DECIMALS - self.decimals);
+        let amount = (position.scaled_balance as u128 * interest / precision_decrease) as i128;
+        Ok(if position.is_borrow { -amount } else { amount })
+    }
+
+    /// Signed QUOTE_PRECISION value of a position at the cached oracle price.
+    pub fn value(&self, position: &DriftSpotPosition) -> anyhow::Result<i128> {
+        Ok(
+            self.token_amount(position)? * self.last_oracle_price as i128
+                / 10_i128.pow(self.decimals),
+        )
+    }
+}
+
+pub async fn fetch_user_positions(
+    client: &RpcClient,
+    user: &Pubkey,
+) -> anyhow::Result<DriftUserPositions> {
+    let account = get_account(client, user).await?;
+    DriftUserPositions::decode(&account.data)
+}
+
+/// Spot market balances and perp oracle prices, as last cached by Drift, used to value vault users.
+#[derive(Debug, Clone, Default)]
+pub struct DriftMarketPrices {
+    pub spot_markets: HashMap<u16, SpotMarketBalances>,
+    /// PRICE_PRECISION
+    pub perp_prices: HashMap<u16, i64>,
+}
+
+impl DriftMarketPrices {
+    /// Loads every market in `markets` with one batched `getMultipleAccounts`.
+    pub async fn fetch(client: &RpcClient, markets: &DriftMarkets) -> anyhow::Result<Self> {
+        let mut spot_indexes = markets.spot_markets.clone();
+        spot_indexes.sort_unstable();
+        spot_indexes.dedup();
+        let mut perp_indexes = markets.perp_markets.clone();
+        perp_indexes.sort_unstable();
+        perp_indexes.dedup();
+
+        let keys: Vec<Pubkey> = spot_indexes
+            .iter()
+            .map(|i| get_spot_market_address(*i).0)
+            .chain(perp_indexes.iter().map(|i| get_perp_market_address(*i).0))
+            .collect();
+        let accounts = get_multiple_accounts(client, &keys).await?;
+
+        let mut prices = Self::default();
+        for (i, account) in accounts.into_iter().enumerate() {
+            let account =
+                account.ok_or(anyhow::anyhow!("Drift market not found: {:?}", keys[i]))?;
+            if i < spot_indexes.len() {
+                prices
+                    .spot_markets
+                    .insert(spot_indexes[i], SpotMarketBalances::decode(&account.data)?);
+            } else {
+                prices.perp_prices.insert(
+                    perp_indexes[i - spot_indexes.len()],
+                    read_i64(&account.data, PERP_MARKET_LAST_ORACLE_PRICE_OFFSET)?,
+                );
+            }
+        }
+        Ok(prices)
+    }
+
+    /// Net value of `positions` in the token of `deposit_market_index`.
+    /// Unsettled funding is not included.
+    pub fn vault_equity(
+        &self,
+        positions: &DriftUserPositions,
+        deposit_market_index: u16,
+    ) -> anyhow::Result<u64> {
+        let mut net_usd_value: i128 = 0;
+        for position in &positions.spot {
+            let market = self
+                .spot_markets
+                .get(&position.market_index)
+                .ok_or(anyhow::anyhow!(
+                    "Spot market {} not loaded",
+                    position.market_index
+                ))?;
+            net_usd_value += market.value(position)?;
+        }
+        for position in &positions.perp {
+            let price = self
+                .perp_prices
+                .get(&position.market_index)
+                .ok_or(anyhow::anyhow!(
+                    "Perp market {} not loaded",
+                    position.market_index
+                ))?;
+            net_usd_value += position.base_asset_amount as i128 * *price as i128 / BASE_PRECISION
+                + position.quote_asset_amount as i128;
+        }
+        let deposit_market = self.deposit_market(deposit_market_index)?;
+        let equity = net_usd_value * 10_i128.pow(deposit_market.decimals)
+            / deposit_market.last_oracle_price as i128;
+        Ok(equity.max(0) as u64)
+    }
+
+    /// USD price of the token of `deposit_market_index`, to value [`Self::vault_equity`] with.
+    pub fn deposit_price(&self, deposit_market_index: u16) -> anyhow::Result<DepositPrice> {
+        let deposit_market = self.deposit_market(deposit_market_index)?;
+        Ok(DepositPrice {
+            decimals: deposit_market.decimals,
+            price: deposit_market.last_oracle_price as f64 / PRICE_PRECISION as f64,
+        })
+    }
+
+    fn deposit_market(&self, deposit_market_index: u16) -> anyhow::Result<&SpotMarketBalances> {
+        let deposit_market =
+            self.spot_markets
+                .get(&deposit_market_index)
+                .ok_or(anyhow::anyhow!(
+                    "Spot market {} not loaded",
+                    deposit_market_index
+                ))?;
+        deposit_market.check_decimals()?;
+        if deposit_market.last_oracle_price <= 0 {
+            return Err(anyhow::anyhow!(
+                "No oracle price for spot market {}",
+                deposit_market_index
+            ));
+        }
+        Ok(deposit_market)
+    }
+}
+
+/// The vault's Drift user positions and the prices of its markets and deposit market.
+pub async fn fetch_vault_prices(
+    client: &RpcClient,
+    vault: &Vault,
+) -> anyhow::Result<(DriftUserPositions, DriftMarketPrices)> {
+    let positions = fetch_user_positions(client, &vault.user).await?;
+    let mut markets = positions.markets();
+    markets.spot_markets.push(vault.spot_market_index);
+    let prices = DriftMarketPrices::fetch(client, &markets).await?;
+    Ok((positions, prices))
+}
+
+/// Net value of the vault's Drift user in its deposit token, marking spot balances and perp
+/// positions at the oracle prices Drift last cached.
+pub async fn fetch_vault_equity(client: &RpcClient, vault: &Vault) -> anyhow::Result<u64> {
+    let (positions, prices) = fetch_vault_prices(client, vault).await?;
+    prices.vault_equity(&positions, vault.spot_market_index)
+}
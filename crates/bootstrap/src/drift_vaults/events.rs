@@ -0,0 +1,232 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::nav::{ShareAction, ShareEvent};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SettlePnlExplanation {
+    #[default]
+    None,
+    ExpiredPosition,
+}
+
+/// Emitted by the Drift program each time a user's perp pnl is settled.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SettlePnlRecord {
+    pub ts: i64,
+    pub user: Pubkey,
+    pub market_index: u16,
+    /// QUOTE_PRECISION
+    pub pnl: i128,
+    pub base_asset_amount: i64,
+    pub quote_asset_amount_after: i64,
+    pub quote_entry_amount: i64,
+    pub settle_price: i64,
+    pub explanation: SettlePnlExplanation,
+}
+
+impl Discriminator for SettlePnlRecord {
+    const DISCRIMINATOR: [u8; 8] = [57, 68, 105, 26, 119, 198, 213, 89];
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderAction {
+    Place,
+    Cancel,
+    Fill,
+    Trigger,
+    Expire,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderActionExplanation {
+    None,
+    InsufficientFreeCollateral,
+    OraclePriceBreachedLimitPrice,
+    MarketOrderFilledToLimitPrice,
+    OrderExpired,
+    Liquidation,
+    OrderFilledWithAMM,
+    OrderFilledWithAMMJit,
+    OrderFilledWithMatch,
+    OrderFilledWithMatchJit,
+    MarketExpired,
+    RiskingIncreasingOrder,
+    ReduceOnlyOrderIncreasedPosition,
+    OrderFillWithSerum,
+    NoBorrowLiquidity,
+    OrderFillWithPhoenix,
+    OrderFilledWithAMMJitLPSplit,
+    OrderFilledWithLPJit,
+    DeriskLp,
+    OrderFilledWithOpenbookV2,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarketType {
+    Spot,
+    Perp,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PositionDirection {
+    Long,
+    Short,
+}
+
+/// Emitted by the Drift program when an order is placed, cancelled, filled, triggered or expired.
+/// Taker and maker are `User` accounts; an AMM fill has no maker.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OrderActionRecord {
+    pub ts: i64,
+    pub action: OrderAction,
+    pub action_explanation: OrderActionExplanation,
+    pub market_index: u16,
+    pub market_type: MarketType,
+    pub filler: Option<Pubkey>,
+    pub filler_reward: Option<u64>,
+    pub fill_record_id: Option<u64>,
+    /// BASE_PRECISION for perp markets, the mint's decimals for spot markets
+    pub base_asset_amount_filled: Option<u64>,
+    /// QUOTE_PRECISION
+    pub quote_asset_amount_filled: Option<u64>,
+    pub taker_fee: Option<u64>,
+    pub maker_fee: Option<i64>,
+    pub referrer_reward: Option<u32>,
+    pub quote_asset_amount_surplus: Option<i64>,
+    pub spot_fulfillment_method_fee: Option<u64>,
+    pub taker: Option<Pubkey>,
+    pub taker_order_id: Option<u32>,
+    pub taker_order_direction: Option<PositionDirection>,
+    pub taker_order_base_asset_amount: Option<u64>,
+    pub taker_order_cumulative_base_asset_amount_filled: Option<u64>,
+    pub taker_order_cumulative_quote_asset_amount_filled: Option<u64>,
+    pub maker: Option<Pubkey>,
+    pub maker_order_id: Option<u32>,
+    pub maker_order_direction: Option<PositionDirection>,
+    pub maker_order_base_asset_amount: Option<u64>,
+    pub maker_order_cumulative_base_asset_amount_filled: Option<u64>,
+    pub maker_order_cumulative_quote_asset_amount_filled: Option<u64>,
+    /// PRICE_PRECISION
+    pub oracle_price: i64,
+}
+
+impl Discriminator for OrderActionRecord {
+    const DISCRIMINATOR: [u8; 8] = [224, 52, 67, 71, 194, 237, 109, 1];
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VaultRecord {
+    pub ts: i64,
+    pub spot_market_index: u16,
+    pub vault_equity_before: u64,
+}
+
+impl Discriminator for VaultRecord {
+    const DISCRIMINATOR: [u8; 8] = [38, 129, 21, 139, 164, 170, 16, 134];
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VaultDepositorAction {
+    Deposit,
+    WithdrawRequest,
+    CancelWithdrawRequest,
+    Withdraw,
+    FeePayment,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VaultDepositorRecord {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub depositor_authority: Pubkey,
+    pub action: VaultDepositorAction,
+    pub amount: u64,
+    pub spot_market_index: u16,
+    pub vault_shares_before: u128,
+    pub vault_shares_after: u128,
+    pub vault_equity_before: u64,
+    pub user_vault_shares_before: u128,
+    pub total_vault_shares_before: u128,
+    pub user_vault_shares_after: u128,
+    pub total_vault_shares_after: u128,
+    pub profit_share: u64,
+    pub management_fee: i64,
+    pub management_fee_shares: i64,
+}
+
+impl Discriminator for VaultDepositorRecord {
+    const DISCRIMINATOR: [u8; 8] = [177, 172, 11, 74, 19, 216, 149, 11];
+}
+
+/// [`VaultDepositorRecord`] with the protocol fee split out, emitted by vaults with a `VaultProtocol`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VaultDepositorV1Record {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub depositor_authority: Pubkey,
+    pub action: VaultDepositorAction,
+    pub amount: u64,
+    pub spot_market_index: u16,
+    pub vault_shares_before: u128,
+    pub vault_shares_after: u128,
+    pub vault_equity_before: u64,
+    pub user_vault_shares_before: u128,
+    pub total_vault_shares_before: u128,
+    pub user_vault_shares_after: u128,
+    pub total_vault_shares_after: u128,
+    pub protocol_profit_share: u64,
+    pub protocol_fee: i64,
+    pub protocol_fee_shares: i64,
+    pub manager_profit_share: u64,
+    pub management_fee: i64,
+    pub management_fee_shares: i64,
+}
+
+impl Discriminator for VaultDepositorV1Record {
+    const DISCRIMINATOR: [u8; 8] = [36, 53, 222, 7, 105, 74, 6, 76];
+}
+
+impl From<VaultDepositorAction> for ShareAction {
+    fn from(action: VaultDepositorAction) -> Self {
+        match action {
+            VaultDepositorAction::Deposit => ShareAction::Deposit,
+            VaultDepositorAction::WithdrawRequest => ShareAction::WithdrawRequest,
+            VaultDepositorAction::CancelWithdrawRequest => ShareAction::CancelWithdrawRequest,
+            VaultDepositorAction::Withdraw => ShareAction::Withdraw,
+            VaultDepositorAction::FeePayment => ShareAction::FeePayment,
+        }
+    }
+}
+
+impl From<VaultDepositorRecord> for ShareEvent {
+    fn from(record: VaultDepositorRecord) -> Self {
+        Self {
+            ts: record.ts,
+            vault: record.vault,
+            authority: record.depositor_authority,
+            action: record.action.into(),
+            amount: record.amount,
+            vault_equity_before: record.vault_equity_before,
+            total_shares_before: record.total_vault_shares_before,
+            total_shares_after: record.total_vault_shares_after,
+            investor_shares_after: record.vault_shares_after,
+        }
+    }
+}
+
+impl From<VaultDepositorV1Record> for ShareEvent {
+    fn from(record: VaultDepositorV1Record) -> Self {
+        Self {
+            ts: record.ts,
+            vault: record.vault,
+            authority: record.depositor_authority,
+            action: record.action.into(),
+            amount: record.amount,
+            vault_equity_before: record.vault_equity_before,
+            total_shares_before: record.total_vault_shares_before,
+            total_shares_after: record.total_vault_shares_after,
+            investor_shares_after: record.vault_shares_after,
+        }
+    }
+}
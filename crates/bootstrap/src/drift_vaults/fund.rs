@@ -0,0 +1,230 @@
+use async_trait::async_trait;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+use crate::amount::TokenAmount;
+use crate::fund::{investors_by_vault, Fund, FundOverview, InvestorPosition, Venue};
+use crate::helpers::{decode_account, get_anchor_accounts, get_clock, get_multiple_accounts};
+use crate::nav::NavSnapshot;
+use crate::vault_update::{UpdateVaultConfig, VaultUpdatePlan};
+
+use super::*;
+
+impl From<(Pubkey, VaultDepositor)> for InvestorPosition {
+    fn from((pubkey, depositor): (Pubkey, VaultDepositor)) -> Self {
+        Self {
+            pubkey,
+            authority: depositor.authority,
+            shares: depositor.vault_shares,
+            net_deposits: depositor.net_deposits,
+            cumulative_profit_share_amount: depositor.cumulative_profit_share_amount,
+            pending_withdraw_shares: depositor.last_withdraw_request.shares,
+            pending_withdraw_value: depositor.last_withdraw_request.value,
+            pending_withdraw_ts: depositor.last_withdraw_request.ts,
+        }
+    }
+}
+
+/// A drift_vaults vault as a [`Fund`].
+pub struct DriftFund<'a> {
+    pub client: &'a RpcClient,
+    pub vault: Pubkey,
+}
+
+impl<'a> DriftFund<'a> {
+    pub fn new(client: &'a RpcClient, vault: Pubkey) -> Self {
+        Self { client, vault }
+    }
+}
+
+#[async_trait]
+impl<'a> Fund for DriftFund<'a> {
+    fn venue(&self) -> Venue {
+        Venue::Drift
+    }
+
+    fn vault(&self) -> Pubkey {
+        self.vault
+    }
+
+    async fn equity(&self) -> anyhow::Result<u64> {
+        let vault = fetch_vault(self.client, &self.vault).await?;
+        fetch_vault_equity(self.client, &vault).await
+    }
+
+    async fn investors(&self) -> anyhow::Result<Vec<InvestorPosition>> {
+        let depositors = fetch_vault_depositors(self.client, &self.vault).await?;
+        Ok(depositors.into_iter().map(InvestorPosition::from).collect())
+    }
+
+    async fn deposit(
+        &self,
+        investor: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature> {
+        deposit(self.client, investor, &self.vault, amount).await
+    }
+
+    async fn request_withdraw(
+        &self,
+        investor: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature> {
+        request_withdraw(self.client, investor, &self.vault, amount).await
+    }
+
+    async fn cancel_withdraw_request(
+        &self,
+        investor: &(dyn Signer + Sync),
+    ) -> anyhow::Result<Signature> {
+        cancel_request_withdraw(self.client, investor, &self.vault).await
+    }
+
+    async fn withdraw(&self, investor: &(dyn Signer + Sync)) -> anyhow::Result<Signature> {
+        withdraw(self.client, investor, &self.vault).await
+    }
+
+    async fn manager_deposit(
+        &self,
+        manager: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature> {
+        manager_deposit(self.client, manager, &self.vault, amount).await
+    }
+
+    async fn manager_request_withdraw(
+        &self,
+        manager: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature> {
+        manager_request_withdraw(self.client, manager, &self.vault, amount).await
+    }
+
+    async fn manager_cancel_withdraw_request(
+        &self,
+        manager: &(dyn Signer + Sync),
+    ) -> anyhow::Result<Signature> {
+        manager_cancel_withdraw_request(self.client, manager, &self.vault).await
+    }
+
+    async fn manager_withdraw(&self, manager: &(dyn Signer + Sync)) -> anyhow::Result<Signature> {
+        manager_withdraw(self.client, manager, &self.vault).await
+    }
+
+    async fn protocol_request_withdraw(
+        &self,
+        protocol: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature> {
+        protocol_request_withdraw(self.client, protocol, &self.vault, amount).await
+    }
+
+    async fn protocol_cancel_withdraw_request(
+        &self,
+        protocol: &(dyn Signer + Sync),
+    ) -> anyhow::Result<Signature> {
+        protocol_cancel_withdraw_request(self.client, protocol, &self.vault).await
+    }
+
+    async fn protocol_withdraw(&self, protocol: &(dyn Signer + Sync)) -> anyhow::Result<Signature> {
+        protocol_withdraw(self.client, protocol, &self.vault).await
+    }
+
+    async fn plan_update(&self, config: &UpdateVaultConfig) -> anyhow::Result<VaultUpdatePlan> {
+        plan_update_vault(self.client, &self.vault, config).await
+    }
+
+    async fn send_update(
+        &self,
+        manager: &(dyn Signer + Sync),
+        plan: &VaultUpdatePlan,
+    ) -> anyhow::Result<Option<Signature>> {
+        send_update(self.client, manager, &self.vault, plan).await
+    }
+
+    async fn overview(&self) -> anyhow::Result<FundOverview> {
+        let vault = fetch_vault(self.client, &self.vault).await?;
+        let vault_protocol = if vault.vault_protocol {
+            Some(fetch_vault_protocol(self.client, &self.vault).await?)
+        } else {
+            None
+        };
+        let investors = self.investors().await?;
+        let (positions, prices) = fetch_vault_prices(self.client, &vault).await?;
+        Ok(FundOverview::compute(
+            &vault.fund_summary(vault_protocol.as_ref()),
+            &investors,
+            prices.vault_equity(&positions, vault.spot_market_index)?,
+            prices.deposit_price(vault.spot_market_index)?,
+        ))
+    }
+
+    async fn nav(&self) -> anyhow::Result<NavSnapshot> {
+        let vault = fetch_vault(self.client, &self.vault).await?;
+        let equity = fetch_vault_equity(self.client, &vault).await?;
+        let clock = get_clock(self.client).await?;
+        Ok(NavSnapshot {
+            vault: self.vault,
+            ts: clock.unix_timestamp,
+            equity,
+            total_shares: vault.total_shares,
+        })
+    }
+}
+
+/// Overviews of every drift_vaults vault. Vaults and depositors come from one `getProgramAccounts`
+/// each, and Drift users, vault protocols and markets from batched `getMultipleAccounts`.
+pub async fn fetch_fund_overviews(client: &RpcClient) -> anyhow::Result<Vec<FundOverview>> {
+    let vaults = get_anchor_accounts::<Vault>(client, &DRIFT_VAULTS_PROGRAM_ID, vec![]).await?;
+    let depositors =
+        get_anchor_accounts::<VaultDepositor>(client, &DRIFT_VAULTS_PROGRAM_ID, vec![])
+            .await?
+            .into_iter()
+            .map(|(key, vd)| (vd.vault, InvestorPosition::from((key, vd))));
+    let mut investors = investors_by_vault(depositors);
+
+    let users: Vec<Pubkey> = vaults.iter().map(|(_, v)| v.user).collect();
+    let protocols: Vec<Pubkey> = vaults
+        .iter()
+        .map(|(key, _)| get_vault_protocol_address(key).0)
+        .collect();
+    let keys: Vec<Pubkey> = users.iter().chain(protocols.iter()).copied().collect();
+    let accounts = get_multiple_accounts(client, &keys).await?;
+    let (user_accounts, protocol_accounts) = accounts.split_at(users.len());
+
+    let mut positions = Vec::with_capacity(vaults.len());
+    let mut markets = DriftMarkets::default();
+    for ((_, vault), account) in vaults.iter().zip(user_accounts) {
+        let account = account
+            .as_ref()
+            .ok_or(anyhow::anyhow!("Drift user not found: {:?}", vault.user))?;
+        let user_positions = DriftUserPositions::decode(&account.data)?;
+        let user_markets = user_positions.markets();
+        markets.spot_markets.extend(user_markets.spot_markets);
+        markets.spot_markets.push(vault.spot_market_index);
+        markets.perp_markets.extend(user_markets.perp_markets);
+        positions.push(user_positions);
+    }
+    let prices = DriftMarketPrices::fetch(client, &markets).await?;
+
+    let mut overviews = vec![];
+    for (((key, vault), user_positions), protocol_account) in
+        vaults.iter().zip(positions).zip(protocol_accounts)
+    {
+        let vault_protocol = match (vault.vault_protocol, protocol_account) {
+            (true, Some(account)) => Some(decode_account::<VaultProtocol>(&account.data)?),
+            _ => None,
+        };
+        let equity = prices.vault_equity(&user_positions, vault.spot_market_index)?;
+        let vault_investors = investors.remove(key).unwrap_or_default();
+        overviews.push(FundOverview::compute(
+            &vault.fund_summary(vault_protocol.as_ref()),
+            &vault_investors,
+            equity,
+            prices.deposit_price(vault.spot_market_index)?,
+        ));
+    }
+    Ok(overviews)
+}
@@ -0,0 +1,576 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token;
+use solana_program::instruction::Instruction;
+
+use crate::helpers::instruction_data;
+use crate::vault_update::UpdateVaultConfig;
+
+use super::*;
+
+/// Fees and profit shares are in PERCENTAGE_PRECISION, token amounts in spot market atoms.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VaultParams {
+    pub name: [u8; 32],
+    pub redeem_period: i64,
+    pub max_tokens: u64,
+    pub management_fee: i64,
+    pub min_deposit_amount: u64,
+    pub profit_share: u32,
+    pub hurdle_rate: u32,
+    pub spot_market_index: u16,
+    pub permissioned: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UpdateVaultParams {
+    pub redeem_period: Option<i64>,
+    pub max_tokens: Option<u64>,
+    pub management_fee: Option<i64>,
+    pub min_deposit_amount: Option<u64>,
+    pub profit_share: Option<u32>,
+    pub hurdle_rate: Option<u32>,
+    pub permissioned: Option<bool>,
+}
+
+/// The delegate is not part of `updateVault`, it has its own instruction.
+impl From<UpdateVaultConfig> for UpdateVaultParams {
+    fn from(config: UpdateVaultConfig) -> Self {
+        Self {
+            redeem_period: config.redeem_period,
+            max_tokens: config.max_tokens,
+            management_fee: config.management_fee,
+            min_deposit_amount: config.min_deposit_amount,
+            profit_share: config.profit_share,
+            hurdle_rate: config.hurdle_rate,
+            permissioned: config.permissioned,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WithdrawUnit {
+    Shares,
+    Token,
+    SharesPercent,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+struct RequestWithdrawParams {
+    withdraw_amount: u64,
+    withdraw_unit: WithdrawUnit,
+}
+
+/// Creates the vault PDA, its token account, and the Drift `User` and `UserStats` it trades through.
+pub fn initialize_vault_ix(
+    manager: &Pubkey,
+    payer: &Pubkey,
+    spot_market_mint: &Pubkey,
+    params: VaultParams,
+) -> anyhow::Result<Instruction> {
+    let vault = get_vault_address(&params.name).0;
+    Ok(Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(vault, false),
+            AccountMeta::new(get_token_vault_address(&vault).0, false),
+            AccountMeta::new(get_user_stats_address(&vault).0, false),
+            AccountMeta::new(get_user_address(&vault, 0).0, false),
+            AccountMeta::new(get_state_address().0, false),
+            AccountMeta::new_readonly(get_spot_market_address(params.spot_market_index).0, false),
+            AccountMeta::new_readonly(*spot_market_mint, false),
+            AccountMeta::new_readonly(*manager, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(DRIFT_PROGRAM_ID, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: instruction_data([48, 191, 163, 44, 71, 129, 63, 164], &params)?,
+    })
+}
+
+pub fn update_vault_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    params: UpdateVaultParams,
+) -> anyhow::Result<Instruction> {
+    Ok(Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*vault, false),
+            AccountMeta::new_readonly(*manager, true),
+        ],
+        data: instruction_data([67, 229, 185, 188, 226, 11, 210, 60], &params)?,
+    })
+}
+
+/// Sets the delegate on both the vault and the vault's Drift `User`.
+pub fn update_delegate_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    delegate: &Pubkey,
+) -> anyhow::Result<Instruction> {
+    Ok(Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*vault, false),
+            AccountMeta::new_readonly(*manager, true),
+            AccountMeta::new(get_user_address(vault, 0).0, false),
+            AccountMeta::new_readonly(DRIFT_PROGRAM_ID, false),
+        ],
+        data: instruction_data([190, 202, 103, 138, 167, 197, 25, 9], delegate)?,
+    })
+}
+
+/// Restores the Drift `User` delegate to the vault's designated delegate.
+pub fn reset_delegate_ix(vault: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*vault, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(get_user_address(vault, 0).0, false),
+            AccountMeta::new_readonly(DRIFT_PROGRAM_ID, false),
+        ],
+        data: [204, 13, 61, 153, 97, 83, 146, 98].to_vec(),
+    }
+}
+
+pub fn update_margin_trading_enabled_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    enabled: bool,
+) -> anyhow::Result<Instruction> {
+    Ok(Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*vault, false),
+            AccountMeta::new_readonly(*manager, true),
+            AccountMeta::new(get_user_address(vault, 0).0, false),
+            AccountMeta::new_readonly(DRIFT_PROGRAM_ID, false),
+        ],
+        data: instruction_data([244, 34, 229, 140, 91, 65, 200, 67], &enabled)?,
+    })
+}
+
+/// Crystallises the manager and protocol profit share on a depositor's unrealized gains.
+pub fn apply_profit_share_ix(
+    vault: &Pubkey,
+    vault_depositor: &Pubkey,
+    manager: &Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*vault_depositor, false),
+        AccountMeta::new_readonly(*manager, true),
+        AccountMeta::new(get_user_stats_address(vault).0, false),
+        AccountMeta::new(get_user_address(vault, 0).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+        AccountMeta::new_readonly(get_signer_address().0, false),
+        AccountMeta::new_readonly(DRIFT_PROGRAM_ID, false),
+    ];
+    accounts.extend(remaining_accounts);
+    Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [112, 235, 54, 165, 178, 81, 25, 10].to_vec(),
+    }
+}
+
+/// Completes a depositor's matured withdraw request on their behalf, paying out to `user_token_account`.
+pub fn force_withdraw_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    vault_depositor: &Pubkey,
+    user_token_account: &Pubkey,
+    spot_market_index: u16,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*manager, true),
+        AccountMeta::new(*vault_depositor, false),
+        AccountMeta::new(get_token_vault_address(vault).0, false),
+        AccountMeta::new(get_user_stats_address(vault).0, false),
+        AccountMeta::new(get_user_address(vault, 0).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+        AccountMeta::new(get_spot_market_vault_address(spot_market_index).0, false),
+        AccountMeta::new_readonly(get_signer_address().0, false),
+        AccountMeta::new(*user_token_account, false),
+        AccountMeta::new_readonly(DRIFT_PROGRAM_ID, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend(remaining_accounts);
+    Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [106, 41, 34, 48, 17, 177, 59, 255].to_vec(),
+    }
+}
+
+/// Creates the vault's Drift `InsuranceFundStake` for `market_index`.
+pub fn initialize_insurance_fund_stake_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    payer: &Pubkey,
+    market_index: u16,
+) -> anyhow::Result<Instruction> {
+    let accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*manager, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(get_spot_market_address(market_index).0, false),
+        AccountMeta::new(
+            get_insurance_fund_stake_address(vault, market_index).0,
+            false,
+        ),
+        AccountMeta::new(get_user_stats_address(vault).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+        AccountMeta::new_readonly(DRIFT_PROGRAM_ID, false),
+    ];
+    Ok(Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([187, 179, 243, 70, 248, 90, 92, 147], &market_index)?,
+    })
+}
+
+/// Enrolls the vault's Drift `UserStats` in `competition`.
+pub fn initialize_competitor_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    payer: &Pubkey,
+    competition: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*manager, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new(get_competitor_address(competition, vault).0, false),
+        AccountMeta::new(*competition, false),
+        AccountMeta::new(get_user_stats_address(vault).0, false),
+        AccountMeta::new_readonly(DRIFT_COMPETITIONS_PROGRAM_ID, false),
+    ];
+    Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [82, 5, 7, 152, 20, 115, 34, 109].to_vec(),
+    }
+}
+
+pub fn initialize_vault_depositor_ix(
+    vault: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*vault, false),
+            AccountMeta::new(get_vault_depositor_address(vault, authority).0, false),
+            AccountMeta::new_readonly(*authority, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+        data: [112, 174, 162, 232, 89, 92, 205, 168].to_vec(),
+    }
+}
+
+pub fn deposit_ix(
+    vault: &Pubkey,
+    authority: &Pubkey,
+    user_token_account: &Pubkey,
+    spot_market_index: u16,
+    amount: u64,
+    remaining_accounts: Vec<AccountMeta>,
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(get_vault_depositor_address(vault, authority).0, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(get_token_vault_address(vault).0, false),
+        AccountMeta::new(get_user_stats_address(vault).0, false),
+        AccountMeta::new(get_user_address(vault, 0).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+        AccountMeta::new(get_spot_market_vault_address(spot_market_index).0, false),
+        AccountMeta::new(*user_token_account, false),
+        AccountMeta::new_readonly(DRIFT_PROGRAM_ID, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend(remaining_accounts);
+    Ok(Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([242, 35, 198, 137, 82, 225, 242, 182], &amount)?,
+    })
+}
+
+pub fn request_withdraw_ix(
+    vault: &Pubkey,
+    authority: &Pubkey,
+    withdraw_amount: u64,
+    withdraw_unit: WithdrawUnit,
+    remaining_accounts: Vec<AccountMeta>,
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(get_vault_depositor_address(vault, authority).0, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(get_user_stats_address(vault).0, false),
+        AccountMeta::new_readonly(get_user_address(vault, 0).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+    ];
+    accounts.extend(remaining_accounts);
+    let params = RequestWithdrawParams {
+        withdraw_amount,
+        withdraw_unit,
+    };
+    Ok(Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([137, 95, 187, 96, 250, 138, 31, 182], &params)?,
+    })
+}
+
+/// Cancels a pending withdraw request, re-pricing the requested shares at the current equity.
+pub fn cancel_request_withdraw_ix(
+    vault: &Pubkey,
+    authority: &Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(get_vault_depositor_address(vault, authority).0, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(get_user_stats_address(vault).0, false),
+        AccountMeta::new_readonly(get_user_address(vault, 0).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+    ];
+    accounts.extend(remaining_accounts);
+    Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [26, 109, 1, 81, 102, 15, 6, 106].to_vec(),
+    }
+}
+
+pub fn withdraw_ix(
+    vault: &Pubkey,
+    authority: &Pubkey,
+    user_token_account: &Pubkey,
+    spot_market_index: u16,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(get_vault_depositor_address(vault, authority).0, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(get_token_vault_address(vault).0, false),
+        AccountMeta::new(get_user_stats_address(vault).0, false),
+        AccountMeta::new(get_user_address(vault, 0).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+        AccountMeta::new(get_spot_market_vault_address(spot_market_index).0, false),
+        AccountMeta::new_readonly(get_signer_address().0, false),
+        AccountMeta::new(*user_token_account, false),
+        AccountMeta::new_readonly(DRIFT_PROGRAM_ID, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend(remaining_accounts);
+    Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [183, 18, 70, 156, 148, 109, 161, 34].to_vec(),
+    }
+}
+
+/// Requests a withdrawal of the manager's own shares, claimable after the redeem period.
+pub fn manager_request_withdraw_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    withdraw_amount: u64,
+    withdraw_unit: WithdrawUnit,
+    remaining_accounts: Vec<AccountMeta>,
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*manager, true),
+        AccountMeta::new_readonly(get_user_stats_address(vault).0, false),
+        AccountMeta::new_readonly(get_user_address(vault, 0).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+    ];
+    accounts.extend(remaining_accounts);
+    let params = RequestWithdrawParams {
+        withdraw_amount,
+        withdraw_unit,
+    };
+    Ok(Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([10, 238, 194, 232, 76, 55, 68, 4], &params)?,
+    })
+}
+
+pub fn manager_cancel_withdraw_request_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*manager, true),
+        AccountMeta::new_readonly(get_user_stats_address(vault).0, false),
+        AccountMeta::new_readonly(get_user_address(vault, 0).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+    ];
+    accounts.extend(remaining_accounts);
+    Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        // The program misspells it `mangerCancelWithdrawRequest`, which the discriminator hashes.
+        data: [235, 253, 32, 176, 145, 94, 162, 244].to_vec(),
+    }
+}
+
+/// Completes the manager's matured withdraw request into `user_token_account`.
+pub fn manager_withdraw_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    user_token_account: &Pubkey,
+    spot_market_index: u16,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*manager, true),
+        AccountMeta::new(get_token_vault_address(vault).0, false),
+        AccountMeta::new(get_user_stats_address(vault).0, false),
+        AccountMeta::new(get_user_address(vault, 0).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+        AccountMeta::new(get_spot_market_vault_address(spot_market_index).0, false),
+        AccountMeta::new_readonly(get_signer_address().0, false),
+        AccountMeta::new(*user_token_account, false),
+        AccountMeta::new_readonly(DRIFT_PROGRAM_ID, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend(remaining_accounts);
+    Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [201, 248, 190, 143, 86, 43, 183, 254].to_vec(),
+    }
+}
+
+/// Deposits `amount` spot market atoms from `user_token_account` as manager shares.
+pub fn manager_deposit_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    user_token_account: &Pubkey,
+    spot_market_index: u16,
+    amount: u64,
+    remaining_accounts: Vec<AccountMeta>,
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*manager, true),
+        AccountMeta::new(get_token_vault_address(vault).0, false),
+        AccountMeta::new(get_user_stats_address(vault).0, false),
+        AccountMeta::new(get_user_address(vault, 0).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+        AccountMeta::new(get_spot_market_vault_address(spot_market_index).0, false),
+        AccountMeta::new(*user_token_account, false),
+        AccountMeta::new_readonly(DRIFT_PROGRAM_ID, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend(remaining_accounts);
+    Ok(Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([73, 3, 16, 168, 143, 226, 201, 254], &amount)?,
+    })
+}
+
+/// Requests a withdrawal of the protocol's profit and fee shares. The `VaultProtocol` is a named
+/// account here, so `remaining_accounts` should only hold markets and oracles.
+pub fn protocol_request_withdraw_ix(
+    vault: &Pubkey,
+    protocol: &Pubkey,
+    withdraw_amount: u64,
+    withdraw_unit: WithdrawUnit,
+    remaining_accounts: Vec<AccountMeta>,
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(get_vault_protocol_address(vault).0, false),
+        AccountMeta::new_readonly(*protocol, true),
+        AccountMeta::new_readonly(get_user_stats_address(vault).0, false),
+        AccountMeta::new_readonly(get_user_address(vault, 0).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+    ];
+    accounts.extend(remaining_accounts);
+    let params = RequestWithdrawParams {
+        withdraw_amount,
+        withdraw_unit,
+    };
+    Ok(Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([189, 46, 14, 31, 7, 254, 150, 132], &params)?,
+    })
+}
+
+pub fn protocol_cancel_withdraw_request_ix(
+    vault: &Pubkey,
+    protocol: &Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(get_vault_protocol_address(vault).0, false),
+        AccountMeta::new_readonly(*protocol, true),
+        AccountMeta::new_readonly(get_user_stats_address(vault).0, false),
+        AccountMeta::new_readonly(get_user_address(vault, 0).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+    ];
+    accounts.extend(remaining_accounts);
+    Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [194, 217, 171, 94, 56, 253, 179, 242].to_vec(),
+    }
+}
+
+/// Completes the protocol's matured withdraw request into `user_token_account`.
+pub fn protocol_withdraw_ix(
+    vault: &Pubkey,
+    protocol: &Pubkey,
+    user_token_account: &Pubkey,
+    spot_market_index: u16,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(get_vault_protocol_address(vault).0, false),
+        AccountMeta::new_readonly(*protocol, true),
+        AccountMeta::new(get_token_vault_address(vault).0, false),
+        AccountMeta::new(get_user_stats_address(vault).0, false),
+        AccountMeta::new(get_user_address(vault, 0).0, false),
+        AccountMeta::new_readonly(get_state_address().0, false),
+        AccountMeta::new(get_spot_market_vault_address(spot_market_index).0, false),
+        AccountMeta::new_readonly(get_signer_address().0, false),
+        AccountMeta::new(*user_token_account, false),
+        AccountMeta::new_readonly(DRIFT_PROGRAM_ID, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend(remaining_accounts);
+    Instruction {
+        program_id: DRIFT_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [166, 24, 188, 209, 21, 251, 63, 199].to_vec(),
+    }
+}
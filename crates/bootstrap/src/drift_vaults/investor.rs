@@ -0,0 +1,144 @@
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::amount::TokenAmount;
+use crate::helpers::{decode_account, get_account, mint_atoms, send_and_confirm_tx, SignerRef};
+
+use super::*;
+
+pub async fn fetch_vault_depositor(
+    client: &RpcClient,
+    vault: &Pubkey,
+    authority: &Pubkey,
+) -> anyhow::Result<VaultDepositor> {
+    let account = get_account(client, &get_vault_depositor_address(vault, authority).0).await?;
+    decode_account::<VaultDepositor>(&account.data)
+}
+
+pub async fn fetch_vault_protocol(
+    client: &RpcClient,
+    vault: &Pubkey,
+) -> anyhow::Result<VaultProtocol> {
+    let account = get_account(client, &get_vault_protocol_address(vault).0).await?;
+    decode_account::<VaultProtocol>(&account.data)
+}
+
+/// Markets and oracles of the vault's Drift user, plus the `VaultProtocol` if the vault has one.
+pub async fn vault_remaining_accounts(
+    client: &RpcClient,
+    vault: &Vault,
+    writable_spot_market: Option<u16>,
+) -> anyhow::Result<Vec<AccountMeta>> {
+    let positions = fetch_user_positions(client, &vault.user).await?;
+    let mut metas = positions
+        .markets()
+        .remaining_accounts(client, writable_spot_market)
+        .await?;
+    if vault.vault_protocol {
+        metas.push(AccountMeta::new(
+            get_vault_protocol_address(&vault.pubkey).0,
+            false,
+        ));
+    }
+    Ok(metas)
+}
+
+/// Deposits `amount` of the vault's spot market token, creating the `VaultDepositor` first if needed.
+pub async fn deposit<S: Signer + ?Sized>(
+    client: &RpcClient,
+    authority: &S,
+    vault: &Pubkey,
+    amount: TokenAmount,
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let spot_market = fetch_spot_market_info(client, vault_acct.spot_market_index).await?;
+    let amount = mint_atoms(client, &spot_market.mint, amount).await?;
+    let mut ixs = vec![];
+    if fetch_vault_depositor(client, vault, &authority.pubkey())
+        .await
+        .is_err()
+    {
+        ixs.push(initialize_vault_depositor_ix(
+            vault,
+            &authority.pubkey(),
+            &authority.pubkey(),
+        ));
+    }
+    ixs.push(deposit_ix(
+        vault,
+        &authority.pubkey(),
+        &get_associated_token_address(&authority.pubkey(), &spot_market.mint),
+        vault_acct.spot_market_index,
+        amount,
+        vault_remaining_accounts(client, &vault_acct, Some(vault_acct.spot_market_index)).await?,
+    )?);
+    send_and_confirm_tx(client, authority, &ixs, &[&SignerRef(authority)]).await
+}
+
+/// Requests a withdrawal of `amount` spot market tokens, claimable after the redeem period.
+pub async fn request_withdraw<S: Signer + ?Sized>(
+    client: &RpcClient,
+    authority: &S,
+    vault: &Pubkey,
+    amount: TokenAmount,
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let spot_market = fetch_spot_market_info(client, vault_acct.spot_market_index).await?;
+    let amount = mint_atoms(client, &spot_market.mint, amount).await?;
+    let ix = request_withdraw_ix(
+        vault,
+        &authority.pubkey(),
+        amount,
+        WithdrawUnit::Token,
+        vault_remaining_accounts(client, &vault_acct, None).await?,
+    )?;
+    send_and_confirm_tx(client, authority, &[ix], &[&SignerRef(authority)]).await
+}
+
+/// Cancels the pending withdraw request so the shares keep earning.
+pub async fn cancel_request_withdraw<S: Signer + ?Sized>(
+    client: &RpcClient,
+    authority: &S,
+    vault: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let ix = cancel_request_withdraw_ix(
+        vault,
+        &authority.pubkey(),
+        vault_remaining_accounts(client, &vault_acct, None).await?,
+    );
+    send_and_confirm_tx(client, authority, &[ix], &[&SignerRef(authority)]).await
+}
+
+/// Completes a matured withdraw request into the authority's associated token account.
+pub async fn withdraw<S: Signer + ?Sized>(
+    client: &RpcClient,
+    authority: &S,
+    vault: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let spot_market = fetch_spot_market_info(client, vault_acct.spot_market_index).await?;
+    let ixs = vec![
+        create_associated_token_account_idempotent(
+            &authority.pubkey(),
+            &authority.pubkey(),
+            &spot_market.mint,
+            &spl_token::id(),
+        ),
+        withdraw_ix(
+            vault,
+            &authority.pubkey(),
+            &get_associated_token_address(&authority.pubkey(), &spot_market.mint),
+            vault_acct.spot_market_index,
+            vault_remaining_accounts(client, &vault_acct, Some(vault_acct.spot_market_index))
+                .await?,
+        ),
+    ];
+    send_and_confirm_tx(client, authority, &ixs, &[&SignerRef(authority)]).await
+}
@@ -0,0 +1,262 @@
+use anchor_spl::associated_token::get_associated_token_address;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_program::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+use crate::helpers::{
+    decode_account, get_anchor_accounts, get_clock, send_and_confirm_tx, sim_accounts, SignerRef,
+};
+
+use super::*;
+
+/// `VaultDepositor.vault` comes right after the discriminator.
+const VAULT_DEPOSITOR_VAULT_OFFSET: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeeperAction {
+    /// Fee (in spot market token) that `applyProfitShare` would crystallise.
+    ApplyProfitShare {
+        vault_depositor: Pubkey,
+        authority: Pubkey,
+        fee: u64,
+    },
+    /// Shares whose redeem period has elapsed and can be force withdrawn.
+    ForceWithdraw {
+        vault_depositor: Pubkey,
+        authority: Pubkey,
+        shares: u128,
+    },
+}
+
+impl std::fmt::Display for KeeperAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeeperAction::ApplyProfitShare {
+                vault_depositor,
+                fee,
+                ..
+            } => write!(f, "apply profit share on {}: fee {}", vault_depositor, fee),
+            KeeperAction::ForceWithdraw {
+                vault_depositor,
+                shares,
+                ..
+            } => write!(f, "force withdraw {}: {} shares", vault_depositor, shares),
+        }
+    }
+}
+
+/// What happened to a [`KeeperAction`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeeperOutcome {
+    /// Not sent, `dry_run` was set.
+    Planned,
+    Sent(Signature),
+    Failed(String),
+}
+
+/// Everything the keeper found to do for one vault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeeperPlan {
+    pub vault: Pubkey,
+    pub actions: Vec<KeeperAction>,
+    /// Depositors whose `applyProfitShare` failed to simulate, with the error.
+    pub skipped: Vec<(Pubkey, String)>,
+}
+
+/// The outcome of every planned action of one vault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeeperReport {
+    pub vault: Pubkey,
+    pub results: Vec<(KeeperAction, KeeperOutcome)>,
+    pub skipped: Vec<(Pubkey, String)>,
+    /// Why the vault could not be planned or its accounts fetched, in which case nothing was
+    /// sent for it.
+    pub error: Option<String>,
+}
+
+pub async fn fetch_vault_depositors(
+    client: &RpcClient,
+    vault: &Pubkey,
+) -> anyhow::Result<Vec<(Pubkey, VaultDepositor)>> {
+    get_anchor_accounts::<VaultDepositor>(
+        client,
+        &DRIFT_VAULTS_PROGRAM_ID,
+        vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            VAULT_DEPOSITOR_VAULT_OFFSET,
+            vault.to_bytes().to_vec(),
+        ))],
+    )
+    .await
+}
+
+/// Whether the depositor's pending withdraw request has outlived the vault's redeem period.
+pub fn is_withdraw_due(vault: &Vault, vault_depositor: &VaultDepositor, now: i64) -> bool {
+    let request = &vault_depositor.last_withdraw_request;
+    request.shares > 0 && now >= request.ts.saturating_add(vault.redeem_period)
+}
+
+/// Simulates `applyProfitShare` and returns the fee it would charge, if any.
+async fn simulate_profit_share<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    vault: &Pubkey,
+    vault_depositor: &Pubkey,
+    before: &VaultDepositor,
+    remaining_accounts: &[AccountMeta],
+) -> anyhow::Result<u64> {
+    let ix = apply_profit_share_ix(
+        vault,
+        vault_depositor,
+        &manager.pubkey(),
+        remaining_accounts.to_vec(),
+    );
+    let accounts = sim_accounts(
+        client,
+        manager,
+        &[ix],
+        &[&SignerRef(manager)],
+        &[*vault_depositor],
+    )
+    .await?;
+    let after = match accounts.into_iter().next().flatten() {
+        Some(account) => decode_account::<VaultDepositor>(&account.data)?,
+        None => return Ok(0),
+    };
+    Ok(after
+        .profit_share_fee_paid
+        .saturating_sub(before.profit_share_fee_paid))
+}
+
+/// Scans every depositor of `vault` for profit share that can be crystallised
+/// and withdraw requests past their redeem period.
+pub async fn plan_keeper_actions<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    vault: &Pubkey,
+) -> anyhow::Result<KeeperPlan> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let remaining_accounts = vault_remaining_accounts(client, &vault_acct, None).await?;
+    let now = get_clock(client).await?.unix_timestamp;
+    let mut plan = KeeperPlan {
+        vault: *vault,
+        actions: vec![],
+        skipped: vec![],
+    };
+    for (key, depositor) in fetch_vault_depositors(client, vault).await? {
+        if depositor.vault_shares == 0 {
+            continue;
+        }
+        match simulate_profit_share(
+            client,
+            manager,
+            vault,
+            &key,
+            &depositor,
+            &remaining_accounts,
+        )
+        .await
+        {
+            Ok(0) => {}
+            Ok(fee) => plan.actions.push(KeeperAction::ApplyProfitShare {
+                vault_depositor: key,
+                authority: depositor.authority,
+                fee,
+            }),
+            Err(e) => plan.skipped.push((key, format!("{:?}", e))),
+        }
+        if is_withdraw_due(&vault_acct, &depositor, now) {
+            plan.actions.push(KeeperAction::ForceWithdraw {
+                vault_depositor: key,
+                authority: depositor.authority,
+                shares: depositor.last_withdraw_request.shares,
+            });
+        }
+    }
+    Ok(plan)
+}
+
+/// Sends each planned action of every vault in `vaults`, or only plans them if `dry_run` is set.
+/// Vaults and actions that fail are recorded in their report and do not stop the rest.
+pub async fn run_keeper<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    vaults: &[Pubkey],
+    dry_run: bool,
+) -> Vec<KeeperReport> {
+    let mut reports = vec![];
+    for vault in vaults {
+        let report = match run_vault_keeper(client, manager, vault, dry_run).await {
+            Ok(report) => report,
+            Err(e) => KeeperReport {
+                vault: *vault,
+                results: vec![],
+                skipped: vec![],
+                error: Some(format!("{:?}", e)),
+            },
+        };
+        reports.push(report);
+    }
+    reports
+}
+
+async fn run_vault_keeper<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    vault: &Pubkey,
+    dry_run: bool,
+) -> anyhow::Result<KeeperReport> {
+    let plan = plan_keeper_actions(client, manager, vault).await?;
+    let mut report = KeeperReport {
+        vault: *vault,
+        results: vec![],
+        skipped: plan.skipped,
+        error: None,
+    };
+    if dry_run {
+        report.results = plan
+            .actions
+            .into_iter()
+            .map(|action| (action, KeeperOutcome::Planned))
+            .collect();
+        return Ok(report);
+    }
+    let vault_acct = fetch_vault(client, vault).await?;
+    let spot_market = fetch_spot_market_info(client, vault_acct.spot_market_index).await?;
+    let profit_share_accounts = vault_remaining_accounts(client, &vault_acct, None).await?;
+    let withdraw_accounts =
+        vault_remaining_accounts(client, &vault_acct, Some(vault_acct.spot_market_index)).await?;
+    for action in plan.actions {
+        let ix = match action {
+            KeeperAction::ApplyProfitShare {
+                vault_depositor, ..
+            } => apply_profit_share_ix(
+                vault,
+                &vault_depositor,
+                &manager.pubkey(),
+                profit_share_accounts.clone(),
+            ),
+            KeeperAction::ForceWithdraw {
+                vault_depositor,
+                authority,
+                ..
+            } => force_withdraw_ix(
+                vault,
+                &manager.pubkey(),
+                &vault_depositor,
+                &get_associated_token_address(&authority, &spot_market.mint),
+                vault_acct.spot_market_index,
+                withdraw_accounts.clone(),
+            ),
+        };
+        let outcome =
+            match send_and_confirm_tx(client, manager, &[ix], &[&SignerRef(manager)]).await {
+                Ok(sig) => KeeperOutcome::Sent(sig),
+                Err(e) => KeeperOutcome::Failed(format!("{:?}", e)),
+            };
+        report.results.push((action, outcome));
+    }
+    Ok(report)
+}
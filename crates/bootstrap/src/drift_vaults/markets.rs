@@ -0,0 +1,117 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::helpers::get_account;
+
+use super::*;
+
+/// Drift `SpotMarket` and `PerpMarket` both begin with the discriminator, their own pubkey and then the oracle.
+const MARKET_ORACLE_OFFSET: usize = 8 + 32;
+/// `SpotMarket` stores its mint right after the oracle.
+const SPOT_MARKET_MINT_OFFSET: usize = MARKET_ORACLE_OFFSET + 32;
+
+fn read_pubkey(data: &[u8], offset: usize, key: &Pubkey) -> anyhow::Result<Pubkey> {
+    let bytes = data
+        .get(offset..offset + 32)
+        .ok_or(anyhow::anyhow!("Drift market account too small: {:?}", key))?;
+    Ok(Pubkey::try_from(bytes)?)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpotMarketInfo {
+    pub market_index: u16,
+    pub pubkey: Pubkey,
+    pub oracle: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+}
+
+pub async fn fetch_spot_market_info(
+    client: &RpcClient,
+    market_index: u16,
+) -> anyhow::Result<SpotMarketInfo> {
+    let pubkey = get_spot_market_address(market_index).0;
+    let account = get_account(client, &pubkey).await?;
+    Ok(SpotMarketInfo {
+        market_index,
+        pubkey,
+        oracle: read_pubkey(&account.data, MARKET_ORACLE_OFFSET, &pubkey)?,
+        mint: read_pubkey(&account.data, SPOT_MARKET_MINT_OFFSET, &pubkey)?,
+        vault: get_spot_market_vault_address(market_index).0,
+    })
+}
+
+pub fn get_perp_market_address(market_index: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"perp_market", &market_index.to_le_bytes()],
+        &DRIFT_PROGRAM_ID,
+    )
+}
+
+pub async fn fetch_perp_market_oracle(
+    client: &RpcClient,
+    market_index: u16,
+) -> anyhow::Result<Pubkey> {
+    let pubkey = get_perp_market_address(market_index).0;
+    let account = get_account(client, &pubkey).await?;
+    read_pubkey(&account.data, MARKET_ORACLE_OFFSET, &pubkey)
+}
+
+/// The Drift markets a vault's user has positions in. Drift needs every one of them,
+/// and their oracles, to value the vault.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DriftMarkets {
+    pub spot_markets: Vec<u16>,
+    pub perp_markets: Vec<u16>,
+}
+
+impl DriftMarkets {
+    /// Remaining accounts in the order Drift expects: oracles, spot markets, then perp markets.
+    /// `writable_spot_market` is the market tokens move through, if any.
+    pub async fn remaining_accounts(
+        &self,
+        client: &RpcClient,
+        writable_spot_market: Option<u16>,
+    ) -> anyhow::Result<Vec<AccountMeta>> {
+        let mut oracles: Vec<Pubkey> = vec![];
+        let mut spot_metas = vec![];
+        let mut perp_metas = vec![];
+
+        let mut spot_markets = self.spot_markets.clone();
+        if let Some(index) = writable_spot_market {
+            if !spot_markets.contains(&index) {
+                spot_markets.push(index);
+            }
+        }
+        for index in spot_markets {
+            let info = fetch_spot_market_info(client, index).await?;
+            if !oracles.contains(&info.oracle) {
+                oracles.push(info.oracle);
+            }
+            if writable_spot_market == Some(index) {
+                spot_metas.push(AccountMeta::new(info.pubkey, false));
+            } else {
+                spot_metas.push(AccountMeta::new_readonly(info.pubkey, false));
+            }
+        }
+        for index in &self.perp_markets {
+            let oracle = fetch_perp_market_oracle(client, *index).await?;
+            if !oracles.contains(&oracle) {
+                oracles.push(oracle);
+            }
+            perp_metas.push(AccountMeta::new_readonly(
+                get_perp_market_address(*index).0,
+                false,
+            ));
+        }
+
+        let mut metas: Vec<AccountMeta> = oracles
+            .into_iter()
+            .map(|o| AccountMeta::new_readonly(o, false))
+            .collect();
+        metas.extend(spot_metas);
+        metas.extend(perp_metas);
+        Ok(metas)
+    }
+}
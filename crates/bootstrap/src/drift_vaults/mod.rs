@@ -0,0 +1,133 @@
+mod accounts;
+mod competitions;
+mod delegate;
+mod equity;
+mod events;
+mod fund;
+mod governance;
+mod instructions;
+mod insurance_fund;
+mod investor;
+mod keeper;
+mod manager;
+mod markets;
+mod protocol;
+
+pub use accounts::*;
+pub use competitions::*;
+pub use delegate::*;
+pub use equity::*;
+pub use events::*;
+pub use fund::*;
+pub use governance::*;
+pub use instructions::*;
+pub use insurance_fund::*;
+pub use investor::*;
+pub use keeper::*;
+pub use manager::*;
+pub use markets::*;
+pub use protocol::*;
+
+use solana_sdk::pubkey::Pubkey;
+
+pub const DRIFT_VAULTS_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("vAuLTsyrvSfZRuRB3XgvkPwNGgYSs9YRYymVebLKoxR");
+
+pub const DRIFT_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("dRiftyHA39MWEi3m9aunc5MzRF1JYuBsbn6VPcn33UH");
+
+pub const DRIFT_COMPETITIONS_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("DraWMeQX9LfzQQSYoeBwHAgM5JcqFkgrX7GbTfjzVMVL");
+
+/// Vault PDA derived from the 32 byte encoded name.
+pub fn get_vault_address(name: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", name], &DRIFT_VAULTS_PROGRAM_ID)
+}
+
+pub fn get_vault_depositor_address(vault: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"vault_depositor", vault.as_ref(), authority.as_ref()],
+        &DRIFT_VAULTS_PROGRAM_ID,
+    )
+}
+
+pub fn get_vault_protocol_address(vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"vault_protocol", vault.as_ref()],
+        &DRIFT_VAULTS_PROGRAM_ID,
+    )
+}
+
+pub fn get_token_vault_address(vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"vault_token_account", vault.as_ref()],
+        &DRIFT_VAULTS_PROGRAM_ID,
+    )
+}
+
+//
+// Drift program PDAs owned by the vault
+//
+
+/// The vault trades through sub account 0 of a Drift `User` it is the authority of.
+pub fn get_user_address(authority: &Pubkey, sub_account_id: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"user", authority.as_ref(), &sub_account_id.to_le_bytes()],
+        &DRIFT_PROGRAM_ID,
+    )
+}
+
+pub fn get_user_stats_address(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"user_stats", authority.as_ref()], &DRIFT_PROGRAM_ID)
+}
+
+pub fn get_state_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"drift_state"], &DRIFT_PROGRAM_ID)
+}
+
+pub fn get_signer_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"drift_signer"], &DRIFT_PROGRAM_ID)
+}
+
+pub fn get_spot_market_address(market_index: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"spot_market", &market_index.to_le_bytes()],
+        &DRIFT_PROGRAM_ID,
+    )
+}
+
+pub fn get_spot_market_vault_address(market_index: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"spot_market_vault", &market_index.to_le_bytes()],
+        &DRIFT_PROGRAM_ID,
+    )
+}
+
+pub fn get_insurance_fund_vault_address(market_index: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"insurance_fund_vault", &market_index.to_le_bytes()],
+        &DRIFT_PROGRAM_ID,
+    )
+}
+
+pub fn get_insurance_fund_stake_address(authority: &Pubkey, market_index: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"insurance_fund_stake",
+            authority.as_ref(),
+            &market_index.to_le_bytes(),
+        ],
+        &DRIFT_PROGRAM_ID,
+    )
+}
+
+//
+// Drift competitions PDAs
+//
+
+pub fn get_competitor_address(competition: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"competitor", competition.as_ref(), authority.as_ref()],
+        &DRIFT_COMPETITIONS_PROGRAM_ID,
+    )
+}
@@ -0,0 +1,97 @@
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::amount::TokenAmount;
+use crate::helpers::{mint_atoms, send_and_confirm_tx, SignerRef};
+
+use super::*;
+
+/// Fails for vaults without a `VaultProtocol`, which the program rejects protocol withdraws on.
+async fn fetch_protocol_vault(client: &RpcClient, vault: &Pubkey) -> anyhow::Result<Vault> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    if !vault_acct.vault_protocol {
+        return Err(anyhow::anyhow!("Vault {} has no protocol", vault));
+    }
+    Ok(vault_acct)
+}
+
+/// Markets and oracles of the vault's Drift user. Protocol instructions take the
+/// `VaultProtocol` as a named account, so unlike [`vault_remaining_accounts`] it isn't appended.
+async fn protocol_remaining_accounts(
+    client: &RpcClient,
+    vault: &Vault,
+    writable_spot_market: Option<u16>,
+) -> anyhow::Result<Vec<AccountMeta>> {
+    fetch_user_positions(client, &vault.user)
+        .await?
+        .markets()
+        .remaining_accounts(client, writable_spot_market)
+        .await
+}
+
+/// Requests a withdrawal of `amount` spot market tokens of the protocol's shares.
+pub async fn protocol_request_withdraw<S: Signer + ?Sized>(
+    client: &RpcClient,
+    protocol: &S,
+    vault: &Pubkey,
+    amount: TokenAmount,
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_protocol_vault(client, vault).await?;
+    let spot_market = fetch_spot_market_info(client, vault_acct.spot_market_index).await?;
+    let amount = mint_atoms(client, &spot_market.mint, amount).await?;
+    let ix = protocol_request_withdraw_ix(
+        vault,
+        &protocol.pubkey(),
+        amount,
+        WithdrawUnit::Token,
+        protocol_remaining_accounts(client, &vault_acct, None).await?,
+    )?;
+    send_and_confirm_tx(client, protocol, &[ix], &[&SignerRef(protocol)]).await
+}
+
+pub async fn protocol_cancel_withdraw_request<S: Signer + ?Sized>(
+    client: &RpcClient,
+    protocol: &S,
+    vault: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_protocol_vault(client, vault).await?;
+    let ix = protocol_cancel_withdraw_request_ix(
+        vault,
+        &protocol.pubkey(),
+        protocol_remaining_accounts(client, &vault_acct, None).await?,
+    );
+    send_and_confirm_tx(client, protocol, &[ix], &[&SignerRef(protocol)]).await
+}
+
+/// Completes the protocol's matured withdraw request into its associated token account.
+pub async fn protocol_withdraw<S: Signer + ?Sized>(
+    client: &RpcClient,
+    protocol: &S,
+    vault: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_protocol_vault(client, vault).await?;
+    let spot_market = fetch_spot_market_info(client, vault_acct.spot_market_index).await?;
+    let ixs = vec![
+        create_associated_token_account_idempotent(
+            &protocol.pubkey(),
+            &protocol.pubkey(),
+            &spot_market.mint,
+            &spl_token::id(),
+        ),
+        protocol_withdraw_ix(
+            vault,
+            &protocol.pubkey(),
+            &get_associated_token_address(&protocol.pubkey(), &spot_market.mint),
+            vault_acct.spot_market_index,
+            protocol_remaining_accounts(client, &vault_acct, Some(vault_acct.spot_market_index))
+                .await?,
+        ),
+    ];
+    send_and_confirm_tx(client, protocol, &ixs, &[&SignerRef(protocol)]).await
+}
@@ -0,0 +1,235 @@
+use std::str::FromStr;
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use base64::Engine;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiInnerInstructions, UiInstruction,
+    UiTransactionEncoding,
+};
+
+use crate::drift_vaults::{
+    self, OrderActionRecord, SettlePnlRecord, VaultDepositorRecord, VaultDepositorV1Record,
+    DRIFT_PROGRAM_ID, DRIFT_VAULTS_PROGRAM_ID,
+};
+use crate::nav::ShareEvent;
+use crate::phoenix_vaults::{self, InvestorRecord, PHOENIX_VAULTS_PROGRAM_ID};
+
+/// Prefix of the self CPI instruction data written by `emit_cpi!`.
+pub const EVENT_IX_TAG: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Drift logs its events base64 encoded with `msg!` rather than `sol_log_data`.
+const PROGRAM_LOG: &str = "Program log: ";
+
+/// An event emitted by one of the programs the vaults depend on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultEvent {
+    DriftVaultRecord(drift_vaults::VaultRecord),
+    VaultDepositorRecord(VaultDepositorRecord),
+    VaultDepositorV1Record(VaultDepositorV1Record),
+    SettlePnlRecord(SettlePnlRecord),
+    OrderActionRecord(OrderActionRecord),
+    PhoenixVaultRecord(phoenix_vaults::VaultRecord),
+    InvestorRecord(InvestorRecord),
+}
+
+impl VaultEvent {
+    /// The investor action, if this is a depositor or investor record.
+    pub fn share_event(&self) -> Option<ShareEvent> {
+        match self {
+            VaultEvent::VaultDepositorRecord(record) => Some((*record).into()),
+            VaultEvent::VaultDepositorV1Record(record) => Some((*record).into()),
+            VaultEvent::InvestorRecord(record) => Some((*record).into()),
+            _ => None,
+        }
+    }
+}
+
+fn decode<T: AnchorDeserialize>(data: &[u8]) -> anyhow::Result<T> {
+    T::deserialize(&mut &data[8..])
+        .map_err(|e| anyhow::anyhow!("Failed to deserialize event: {:?}", e))
+}
+
+/// Decodes discriminator prefixed event data emitted by `program_id`.
+/// Returns `None` for programs and events this crate doesn't model.
+pub fn decode_event(program_id: &Pubkey, data: &[u8]) -> anyhow::Result<Option<VaultEvent>> {
+    if data.len() < 8 {
+        return Ok(None);
+    }
+    let disc: [u8; 8] = data[..8].try_into()?;
+    let event = if *program_id == DRIFT_VAULTS_PROGRAM_ID {
+        match disc {
+            drift_vaults::VaultRecord::DISCRIMINATOR => VaultEvent::DriftVaultRecord(decode(data)?),
+            VaultDepositorRecord::DISCRIMINATOR => VaultEvent::VaultDepositorRecord(decode(data)?),
+            VaultDepositorV1Record::DISCRIMINATOR => {
+                VaultEvent::VaultDepositorV1Record(decode(data)?)
+            }
+            _ => return Ok(None),
+        }
+    } else if *program_id == DRIFT_PROGRAM_ID {
+        match disc {
+            SettlePnlRecord::DISCRIMINATOR => VaultEvent::SettlePnlRecord(decode(data)?),
+            OrderActionRecord::DISCRIMINATOR => VaultEvent::OrderActionRecord(decode(data)?),
+            _ => return Ok(None),
+        }
+    } else if *program_id == PHOENIX_VAULTS_PROGRAM_ID {
+        match disc {
+            phoenix_vaults::VaultRecord::DISCRIMINATOR => {
+                VaultEvent::PhoenixVaultRecord(decode(data)?)
+            }
+            InvestorRecord::DISCRIMINATOR => VaultEvent::InvestorRecord(decode(data)?),
+            _ => return Ok(None),
+        }
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(event))
+}
+
+/// Events in "Program data:" log lines, and in Drift's base64 "Program log:" lines, attributed to
+/// the program executing when each was logged.
+pub fn parse_logs(logs: &[String]) -> anyhow::Result<Vec<VaultEvent>> {
+    let mut stack: Vec<Pubkey> = vec![];
+    let mut events = vec![];
+    for log in logs {
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+            let program_id = match stack.last() {
+                Some(program_id) => program_id,
+                None => continue,
+            };
+            let data = base64::engine::general_purpose::STANDARD
+                .decode(data)
+                .map_err(|e| anyhow::anyhow!("Invalid program data: {:?}", e))?;
+            if let Some(event) = decode_event(program_id, &data)? {
+                events.push(event);
+            }
+            continue;
+        }
+        if let Some(message) = log.strip_prefix(PROGRAM_LOG) {
+            if stack.last() != Some(&DRIFT_PROGRAM_ID) {
+                continue;
+            }
+            // plain text messages aren't valid base64
+            if let Ok(data) = base64::engine::general_purpose::STANDARD.decode(message) {
+                if let Some(event) = decode_event(&DRIFT_PROGRAM_ID, &data)? {
+                    events.push(event);
+                }
+            }
+            continue;
+        }
+        let mut words = log.split_whitespace();
+        if let (Some("Program"), Some(program_id), Some(status)) =
+            (words.next(), words.next(), words.next())
+        {
+            match status {
+                "invoke" => stack.push(Pubkey::from_str(program_id)?),
+                "success" | "failed:" => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(events)
+}
+
+/// Events emitted through `emit_cpi!`, which appear as inner instructions a program sends to itself.
+pub fn parse_cpi_events(
+    account_keys: &[Pubkey],
+    inner_instructions: &[UiInnerInstructions],
+) -> anyhow::Result<Vec<VaultEvent>> {
+    let mut events = vec![];
+    for ix in inner_instructions
+        .iter()
+        .flat_map(|inner| &inner.instructions)
+    {
+        let ix = match ix {
+            UiInstruction::Compiled(ix) => ix,
+            UiInstruction::Parsed(_) => continue,
+        };
+        let program_id = account_keys
+            .get(ix.program_id_index as usize)
+            .ok_or(anyhow::anyhow!(
+                "Program index out of range: {}",
+                ix.program_id_index
+            ))?;
+        let data = bs58::decode(&ix.data)
+            .into_vec()
+            .map_err(|e| anyhow::anyhow!("Invalid instruction data: {:?}", e))?;
+        if let Some(data) = data.strip_prefix(&EVENT_IX_TAG) {
+            if let Some(event) = decode_event(program_id, data)? {
+                events.push(event);
+            }
+        }
+    }
+    Ok(events)
+}
+
+/// Log and CPI events of a transaction fetched with base64 encoding.
+pub fn parse_transaction_events(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+) -> anyhow::Result<Vec<VaultEvent>> {
+    let meta = match &tx.transaction.meta {
+        Some(meta) => meta,
+        None => return Ok(vec![]),
+    };
+    let mut events = match &meta.log_messages {
+        OptionSerializer::Some(logs) => parse_logs(logs)?,
+        _ => vec![],
+    };
+    if let OptionSerializer::Some(inner_instructions) = &meta.inner_instructions {
+        let account_keys = transaction_account_keys(tx)?;
+        events.extend(parse_cpi_events(&account_keys, inner_instructions)?);
+    }
+    Ok(events)
+}
+
+/// Static keys followed by keys loaded from lookup tables, the order instruction indexes refer to.
+pub fn transaction_account_keys(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+) -> anyhow::Result<Vec<Pubkey>> {
+    let transaction = tx
+        .transaction
+        .transaction
+        .decode()
+        .ok_or(anyhow::anyhow!("Failed to decode transaction"))?;
+    let mut account_keys = transaction.message.static_account_keys().to_vec();
+    if let Some(meta) = &tx.transaction.meta {
+        if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+            for key in loaded.writable.iter().chain(loaded.readonly.iter()) {
+                account_keys.push(Pubkey::from_str(key)?);
+            }
+        }
+    }
+    Ok(account_keys)
+}
+
+/// A confirmed transaction with base64 encoding, the form [`parse_transaction_events`] reads.
+pub async fn fetch_transaction(
+    client: &RpcClient,
+    signature: &Signature,
+) -> anyhow::Result<EncodedConfirmedTransactionWithStatusMeta> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    client
+        .get_transaction_with_config(signature, config)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to get transaction: {:?}", e))
+}
+
+pub async fn fetch_transaction_events(
+    client: &RpcClient,
+    signature: &Signature,
+) -> anyhow::Result<Vec<VaultEvent>> {
+    parse_transaction_events(&fetch_transaction(client, signature).await?)
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+use crate::amount::TokenAmount;
+use crate::helpers::{decode_name, serde_pubkey, serde_pubkey_map};
+use crate::nav::NavSnapshot;
+use crate::vault_update::{UpdateVaultConfig, VaultUpdatePlan};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Venue {
+    Drift,
+    Phoenix,
+}
+
+/// An investor's stake in a fund. Token amounts are in the fund's deposit token atoms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvestorPosition {
+    /// The `VaultDepositor` or `Investor` account.
+    pub pubkey: Pubkey,
+    pub authority: Pubkey,
+    pub shares: u128,
+    pub net_deposits: i64,
+    pub cumulative_profit_share_amount: i64,
+    pub pending_withdraw_shares: u128,
+    pub pending_withdraw_value: u64,
+    pub pending_withdraw_ts: i64,
+}
+
+/// The vault fields a [`FundOverview`] is computed from, common to every venue.
+/// Profit shares are in the fund's deposit token atoms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FundSummary {
+    pub vault: Pubkey,
+    pub manager: Pubkey,
+    pub venue: Venue,
+    pub name: [u8; 32],
+    pub init_ts: i64,
+    pub manager_total_profit_share: u64,
+    pub protocol_total_profit_share: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundOverview {
+    #[serde(with = "serde_pubkey")]
+    pub vault: Pubkey,
+    #[serde(with = "serde_pubkey")]
+    pub manager: Pubkey,
+    pub venue: Venue,
+    /// Investor address and profit
+    #[serde(with = "serde_pubkey_map")]
+    pub investors: HashMap<Pubkey, f64>,
+    pub investor_profit: f64,
+    pub manager_profit: f64,
+    pub protocol_profit: f64,
+    /// Profit before fees
+    pub profit: f64,
+    pub profit_after_fees: f64,
+    pub tvl: f64,
+    /// Unix timestamp the vault was initialized
+    pub birth: i64,
+    pub title: String,
+}
+
+/// USD price of a fund's deposit token, to value its atoms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepositPrice {
+    pub decimals: u32,
+    /// USD per whole token
+    pub price: f64,
+}
+
+impl DepositPrice {
+    pub const USDC: Self = Self {
+        decimals: 6,
+        price: 1.0,
+    };
+
+    pub fn usd(&self, atoms: f64) -> f64 {
+        atoms / 10_f64.powi(self.decimals as i32) * self.price
+    }
+}
+
+impl FundOverview {
+    /// `equity` and the profit shares are in deposit token atoms, `deposit` values them in USD so
+    /// funds of every deposit token compare.
+    pub fn compute(
+        summary: &FundSummary,
+        investors: &[InvestorPosition],
+        equity: u64,
+        deposit: DepositPrice,
+    ) -> Self {
+        let investors: HashMap<Pubkey, f64> = investors
+            .iter()
+            .map(|i| {
+                (
+                    i.pubkey,
+                    deposit.usd(i.cumulative_profit_share_amount as f64),
+                )
+            })
+            .collect();
+        let investor_profit = investors.values().sum::<f64>();
+        let manager_profit = deposit.usd(summary.manager_total_profit_share as f64);
+        let protocol_profit = deposit.usd(summary.protocol_total_profit_share as f64);
+        Self {
+            vault: summary.vault,
+            manager: summary.manager,
+            venue: summary.venue,
+            investors,
+            investor_profit,
+            manager_profit,
+            protocol_profit,
+            profit: investor_profit,
+            profit_after_fees: investor_profit - manager_profit - protocol_profit,
+            tvl: deposit.usd(equity as f64),
+            birth: summary.init_ts,
+            title: decode_name(&summary.name),
+        }
+    }
+}
+
+/// Groups investors by the vault they belong to.
+pub fn investors_by_vault(
+    investors: impl IntoIterator<Item = (Pubkey, InvestorPosition)>,
+) -> HashMap<Pubkey, Vec<InvestorPosition>> {
+    let mut by_vault: HashMap<Pubkey, Vec<InvestorPosition>> = HashMap::new();
+    for (vault, investor) in investors {
+        by_vault.entry(vault).or_default().push(investor);
+    }
+    by_vault
+}
+
+/// A vault on any venue. Amounts sent are in the vault's deposit token, and fail for another
+/// mint's decimals. Amounts read are in its atoms.
+#[async_trait]
+pub trait Fund: Send + Sync {
+    fn venue(&self) -> Venue;
+
+    fn vault(&self) -> Pubkey;
+
+    async fn equity(&self) -> anyhow::Result<u64>;
+
+    async fn investors(&self) -> anyhow::Result<Vec<InvestorPosition>>;
+
+    async fn deposit(
+        &self,
+        investor: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature>;
+
+    async fn request_withdraw(
+        &self,
+        investor: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature>;
+
+    async fn cancel_withdraw_request(
+        &self,
+        investor: &(dyn Signer + Sync),
+    ) -> anyhow::Result<Signature>;
+
+    async fn withdraw(&self, investor: &(dyn Signer + Sync)) -> anyhow::Result<Signature>;
+
+    async fn manager_deposit(
+        &self,
+        manager: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature>;
+
+    async fn manager_request_withdraw(
+        &self,
+        manager: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature>;
+
+    async fn manager_cancel_withdraw_request(
+        &self,
+        manager: &(dyn Signer + Sync),
+    ) -> anyhow::Result<Signature>;
+
+    async fn manager_withdraw(&self, manager: &(dyn Signer + Sync)) -> anyhow::Result<Signature>;
+
+    async fn protocol_request_withdraw(
+        &self,
+        protocol: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature>;
+
+    async fn protocol_cancel_withdraw_request(
+        &self,
+        protocol: &(dyn Signer + Sync),
+    ) -> anyhow::Result<Signature>;
+
+    async fn protocol_withdraw(&self, protocol: &(dyn Signer + Sync)) -> anyhow::Result<Signature>;
+
+    /// The minimal `updateVault` to reach `config`, diffed against the vault's current state.
+    async fn plan_update(&self, config: &UpdateVaultConfig) -> anyhow::Result<VaultUpdatePlan>;
+
+    /// Sends a plan from [`Fund::plan_update`]. Returns no signature if the plan is empty.
+    async fn send_update(
+        &self,
+        manager: &(dyn Signer + Sync),
+        plan: &VaultUpdatePlan,
+    ) -> anyhow::Result<Option<Signature>>;
+
+    async fn overview(&self) -> anyhow::Result<FundOverview>;
+
+    /// Equity and total shares at the current cluster time.
+    async fn nav(&self) -> anyhow::Result<NavSnapshot>;
+}
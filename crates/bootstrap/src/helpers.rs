@@ -0,0 +1,766 @@
+use std::str::FromStr;
+
+use anchor_lang::{AnchorDeserialize, AnchorSerialize, Discriminator};
+use anchor_spl::associated_token::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::state::{Account as TokenAccount, Mint};
+use base64::Engine;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcRequestAirdropConfig,
+    RpcSendTransactionConfig, RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_program::clock::Clock;
+use solana_program::instruction::Instruction;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::{keypair::Keypair, signers::Signers, Signer, SignerError};
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::amount::{TokenAmount, UiAmount, SOL_DECIMALS};
+use crate::token::{
+    is_token_program, mint_len, mint_rent_len, unpack_mint, unpack_token_account, MintExtension,
+};
+
+pub async fn get_account(client: &RpcClient, key: &Pubkey) -> anyhow::Result<Account> {
+    client
+        .get_account_with_commitment(key, CommitmentConfig::processed())
+        .await
+        .map_err(|e| anyhow::anyhow!("{:?}", e))?
+        .value
+        .ok_or(anyhow::anyhow!("Account not found: {:?}", key))
+}
+
+/// Deserializes an Anchor account after checking its 8 byte discriminator.
+pub fn decode_account<T: AnchorDeserialize + Discriminator>(data: &[u8]) -> anyhow::Result<T> {
+    if data.len() < 8 || data[..8] != T::DISCRIMINATOR {
+        return Err(anyhow::anyhow!("Account discriminator mismatch"));
+    }
+    T::deserialize(&mut &data[8..])
+        .map_err(|e| anyhow::anyhow!("Failed to deserialize account: {:?}", e))
+}
+
+/// Anchor instruction data: the 8 byte discriminator followed by the borsh encoded args.
+pub fn instruction_data<T: AnchorSerialize>(
+    discriminator: [u8; 8],
+    args: &T,
+) -> anyhow::Result<Vec<u8>> {
+    let mut data = discriminator.to_vec();
+    args.serialize(&mut data)
+        .map_err(|e| anyhow::anyhow!("Failed to serialize instruction args: {:?}", e))?;
+    Ok(data)
+}
+
+/// Pads or truncates a vault name to the fixed width stored on chain.
+pub fn encode_name(name: &str) -> [u8; 32] {
+    let mut encoded = [b' '; 32];
+    let bytes = name.as_bytes();
+    let len = bytes.len().min(32);
+    encoded[..len].copy_from_slice(&bytes[..len]);
+    encoded
+}
+
+pub fn decode_name(name: &[u8; 32]) -> String {
+    String::from_utf8_lossy(name).trim().to_string()
+}
+
+/// Fetches every account of type `T` owned by `program`, narrowed by any additional `filters`.
+pub async fn get_anchor_accounts<T: AnchorDeserialize + Discriminator>(
+    client: &RpcClient,
+    program: &Pubkey,
+    filters: Vec<RpcFilterType>,
+) -> anyhow::Result<Vec<(Pubkey, T)>> {
+    let mut all_filters = vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+        0,
+        T::DISCRIMINATOR.to_vec(),
+    ))];
+    all_filters.extend(filters);
+    let accounts = client
+        .get_program_accounts_with_config(
+            program,
+            RpcProgramAccountsConfig {
+                filters: Some(all_filters),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    commitment: Some(CommitmentConfig::processed()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    accounts
+        .into_iter()
+        .map(|(key, account)| Ok((key, decode_account::<T>(&account.data)?)))
+        .collect()
+}
+
+/// `getMultipleAccounts` in chunks of the RPC's 100 key limit. Results line up with `keys`.
+pub async fn get_multiple_accounts(
+    client: &RpcClient,
+    keys: &[Pubkey],
+) -> anyhow::Result<Vec<Option<Account>>> {
+    let mut accounts = Vec::with_capacity(keys.len());
+    for chunk in keys.chunks(100) {
+        let res = client
+            .get_multiple_accounts_with_commitment(chunk, CommitmentConfig::processed())
+            .await
+            .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+        accounts.extend(res.value);
+    }
+    Ok(accounts)
+}
+
+pub async fn get_clock(client: &RpcClient) -> anyhow::Result<Clock> {
+    let account = get_account(client, &solana_program::sysvar::clock::id()).await?;
+    solana_sdk::account::from_account::<Clock, _>(&account)
+        .ok_or(anyhow::anyhow!("Failed to deserialize clock sysvar"))
+}
+
+pub async fn get_token_account(
+    client: &RpcClient,
+    token_account: &Pubkey,
+) -> anyhow::Result<TokenAccount> {
+    let account = client
+        .get_account_with_commitment(token_account, CommitmentConfig::processed())
+        .await
+        .map_err(|e| anyhow::anyhow!("{:?}", e))?
+        .value
+        .ok_or(anyhow::anyhow!(
+            "Token account not found: {:?}",
+            token_account
+        ))?;
+    unpack_token_account(&account.data)
+}
+
+pub async fn get_mint(client: &RpcClient, mint: &Pubkey) -> anyhow::Result<Mint> {
+    let account = get_account(client, mint).await?;
+    unpack_mint(&account.data)
+}
+
+/// `amount` in atoms of `mint`, failing if it was made for other decimals.
+pub async fn mint_atoms(
+    client: &RpcClient,
+    mint: &Pubkey,
+    amount: TokenAmount,
+) -> anyhow::Result<u64> {
+    amount.to_atoms(get_mint(client, mint).await?.decimals)
+}
+
+/// The token program that owns `mint`, SPL Token or Token-2022.
+pub async fn get_token_program(client: &RpcClient, mint: &Pubkey) -> anyhow::Result<Pubkey> {
+    let account = get_account(client, mint).await?;
+    if !is_token_program(&account.owner) {
+        return Err(anyhow::anyhow!(
+            "Mint {} is owned by {}, not a token program",
+            mint,
+            account.owner
+        ));
+    }
+    Ok(account.owner)
+}
+
+pub async fn get_token_balance(client: &RpcClient, token_account: &Pubkey) -> anyhow::Result<u64> {
+    Ok(get_token_account(client, token_account).await?.amount)
+}
+
+pub async fn get_lamports(client: &RpcClient, key: &Pubkey) -> anyhow::Result<u64> {
+    Ok(client
+        .get_balance_with_commitment(key, CommitmentConfig::processed())
+        .await
+        .map_err(|e| anyhow::anyhow!("{:?}", e))?
+        .value)
+}
+
+/// Borrows any signer, including a `dyn Signer` from [`crate::signer::load_signer`], as a sized
+/// one so it can be listed in [`Signers`]. Unlike `&dyn Signer` it is `Send` when the signer is
+/// `Sync`, so futures holding it can run in `#[async_trait]` methods.
+pub struct SignerRef<'a, S: Signer + ?Sized>(pub &'a S);
+
+impl<S: Signer + ?Sized> Signer for SignerRef<'_, S> {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        self.0.try_pubkey()
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        self.0.try_sign_message(message)
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.0.is_interactive()
+    }
+}
+
+/// Two signers of any type, listed together without going through `&dyn Signer`
+/// so futures holding them stay `Send` when both signers are `Sync`.
+pub struct SignerPair<'a, A: Signer + ?Sized, B: Signer + ?Sized>(pub &'a A, pub &'a B);
+
+impl<A: Signer + ?Sized, B: Signer + ?Sized> Signers for SignerPair<'_, A, B> {
+    fn pubkeys(&self) -> Vec<Pubkey> {
+        vec![self.0.pubkey(), self.1.pubkey()]
+    }
+
+    fn try_pubkeys(&self) -> Result<Vec<Pubkey>, SignerError> {
+        Ok(vec![self.0.try_pubkey()?, self.1.try_pubkey()?])
+    }
+
+    fn sign_message(&self, message: &[u8]) -> Vec<Signature> {
+        vec![self.0.sign_message(message), self.1.sign_message(message)]
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Vec<Signature>, SignerError> {
+        Ok(vec![
+            self.0.try_sign_message(message)?,
+            self.1.try_sign_message(message)?,
+        ])
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.0.is_interactive() || self.1.is_interactive()
+    }
+}
+
+/// Builds and signs a transaction paid for by `payer`. `signers` can mix keypairs, presigners
+/// and remote signers, and must include the payer.
+pub fn sign_tx<S: Signer + ?Sized, T: Signers + ?Sized>(
+    payer: &S,
+    ixs: &[Instruction],
+    signers: &T,
+    blockhash: Hash,
+) -> anyhow::Result<Transaction> {
+    let mut tx = Transaction::new_with_payer(ixs, Some(&payer.pubkey()));
+    tx.try_sign(signers, blockhash)
+        .map_err(|e| anyhow::anyhow!("Failed to sign transaction: {:?}", e))?;
+    Ok(tx)
+}
+
+/// Simulates the transaction with inner instructions, for the caller to inspect or log.
+pub async fn sim_tx<S: Signer + ?Sized, T: Signers + ?Sized>(
+    client: &RpcClient,
+    payer: &S,
+    ixs: &[Instruction],
+    signers: &T,
+) -> anyhow::Result<RpcSimulateTransactionResult> {
+    let blockhash = client
+        .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
+        .await?
+        .0;
+    let tx = sign_tx(payer, ixs, signers, blockhash)?;
+
+    Ok(client
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: true,
+                replace_recent_blockhash: false,
+                commitment: Some(CommitmentConfig::processed()),
+                inner_instructions: true,
+                ..Default::default()
+            },
+        )
+        .await?
+        .value)
+}
+
+/// Simulates `ixs` and returns the post-simulation state of `accounts`, in the same order.
+pub async fn sim_accounts<S: Signer + ?Sized, T: Signers + ?Sized>(
+    client: &RpcClient,
+    payer: &S,
+    ixs: &[Instruction],
+    signers: &T,
+    accounts: &[Pubkey],
+) -> anyhow::Result<Vec<Option<Account>>> {
+    let blockhash = client
+        .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
+        .await?
+        .0;
+    let tx = sign_tx(payer, ixs, signers, blockhash)?;
+
+    let sim = client
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: true,
+                replace_recent_blockhash: false,
+                commitment: Some(CommitmentConfig::processed()),
+                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    addresses: accounts.iter().map(|k| k.to_string()).collect(),
+                }),
+                ..Default::default()
+            },
+        )
+        .await?
+        .value;
+    if let Some(err) = sim.err {
+        return Err(anyhow::anyhow!(
+            "Simulation failed: {:?}, logs: {:#?}",
+            err,
+            sim.logs
+        ));
+    }
+    Ok(sim
+        .accounts
+        .unwrap_or_default()
+        .into_iter()
+        .map(|a| a.and_then(|a| a.decode::<Account>()))
+        .collect())
+}
+
+pub async fn send_tx<S: Signer + ?Sized, T: Signers + ?Sized>(
+    client: &RpcClient,
+    payer: &S,
+    ixs: &[Instruction],
+    signers: &T,
+) -> anyhow::Result<Signature> {
+    let blockhash = client
+        .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
+        .await?
+        .0;
+    let tx = sign_tx(payer, ixs, signers, blockhash)?;
+    let sig = match client
+        .send_transaction_with_config(
+            &tx,
+            RpcSendTransactionConfig {
+                skip_preflight: true,
+                ..Default::default()
+            },
+        )
+        .await
+    {
+        Ok(sig) => Ok(sig),
+        Err(e) => Err(anyhow::anyhow!("Error sending transaction: {:#?}", e)),
+    }?;
+    Ok(sig)
+}
+
+pub async fn send_and_confirm_tx<S: Signer + ?Sized, T: Signers + ?Sized>(
+    client: &RpcClient,
+    payer: &S,
+    ixs: &[Instruction],
+    signers: &T,
+) -> anyhow::Result<Signature> {
+    let blockhash = client
+        .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
+        .await?
+        .0;
+    let tx = sign_tx(payer, ixs, signers, blockhash)?;
+
+    let sig = match client
+        .send_transaction_with_config(
+            &tx,
+            RpcSendTransactionConfig {
+                skip_preflight: true,
+                ..Default::default()
+            },
+        )
+        .await
+    {
+        Ok(sig) => Ok(sig),
+        Err(e) => Err(anyhow::anyhow!("Error sending transaction: {:#?}", e)),
+    }?;
+    client
+        .confirm_transaction_with_spinner(&sig, &blockhash, CommitmentConfig::processed())
+        .await?;
+    Ok(sig)
+}
+
+/// Airdrops `amount` SOL.
+pub async fn airdrop(
+    client: &RpcClient,
+    key: &Pubkey,
+    amount: UiAmount,
+) -> anyhow::Result<Signature> {
+    let lamports = amount.to_atoms(SOL_DECIMALS)?;
+    let blockhash = client
+        .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
+        .await?
+        .0;
+    let bh_str = solana_sdk::bs58::encode(blockhash).into_string();
+    let sig = client
+        .request_airdrop_with_config(
+            key,
+            lamports,
+            RpcRequestAirdropConfig {
+                recent_blockhash: Some(bh_str),
+                commitment: Some(CommitmentConfig::processed()),
+            },
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("{:?}", e))?;
+    client
+        .confirm_transaction_with_spinner(&sig, &blockhash, CommitmentConfig::processed())
+        .await?;
+    Ok(sig)
+}
+
+/// Transfers `amount` SOL.
+pub async fn transfer<S: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &S,
+    receiver: &Pubkey,
+    amount: UiAmount,
+) -> anyhow::Result<Signature> {
+    let ixs = vec![solana_program::system_instruction::transfer(
+        &payer.pubkey(),
+        receiver,
+        amount.to_atoms(SOL_DECIMALS)?,
+    )];
+    send_and_confirm_tx(client, payer, &ixs, &[&SignerRef(payer)]).await
+}
+
+pub fn clone_keypair(keypair: &Keypair) -> Keypair {
+    keypair.insecure_clone()
+}
+
+pub fn clone_pubkey(pubkey: &Pubkey) -> Pubkey {
+    Pubkey::from_str(&pubkey.to_string()).unwrap()
+}
+
+pub async fn create_associated_token_account<S: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &S,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> anyhow::Result<(Pubkey, Signature)> {
+    let ixs = vec![
+        spl_associated_token_account::instruction::create_associated_token_account(
+            &payer.pubkey(),
+            &payer.pubkey(),
+            token_mint,
+            token_program,
+        ),
+    ];
+    let sig = send_and_confirm_tx(client, payer, &ixs, &[&SignerRef(payer)]).await?;
+    Ok((
+        get_associated_token_address_with_program_id(&payer.pubkey(), token_mint, token_program),
+        sig,
+    ))
+}
+
+pub async fn create_mint<P: Signer + ?Sized, M: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &P,
+    authority: &Pubkey,
+    freeze_authority: Option<&Pubkey>,
+    decimals: u8,
+    mint: &M,
+) -> anyhow::Result<Signature> {
+    let lamports = mint_rent_exemption(client, &mint.pubkey(), &[]).await?;
+    let ixs = create_mint_ixs(
+        &spl_token::id(),
+        &payer.pubkey(),
+        authority,
+        freeze_authority,
+        decimals,
+        &mint.pubkey(),
+        &[],
+        lamports,
+    )?;
+    let sig = send_and_confirm_tx(client, payer, &ixs, &SignerPair(payer, mint)).await?;
+    Ok(sig)
+}
+
+/// Creates a Token-2022 mint, sized for and initialized with `extensions`.
+pub async fn create_token_2022_mint<P: Signer + ?Sized, M: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &P,
+    authority: &Pubkey,
+    freeze_authority: Option<&Pubkey>,
+    decimals: u8,
+    mint: &M,
+    extensions: &[MintExtension],
+) -> anyhow::Result<Signature> {
+    let lamports = mint_rent_exemption(client, &mint.pubkey(), extensions).await?;
+    let ixs = create_mint_ixs(
+        &spl_token_2022::id(),
+        &payer.pubkey(),
+        authority,
+        freeze_authority,
+        decimals,
+        &mint.pubkey(),
+        extensions,
+        lamports,
+    )?;
+    let sig = send_and_confirm_tx(client, payer, &ixs, &SignerPair(payer, mint)).await?;
+    Ok(sig)
+}
+
+/// Lamports that keep `mint` rent exempt, including any metadata it will store, see
+/// [`mint_rent_len`].
+pub async fn mint_rent_exemption(
+    client: &RpcClient,
+    mint: &Pubkey,
+    extensions: &[MintExtension],
+) -> anyhow::Result<u64> {
+    client
+        .get_minimum_balance_for_rent_exemption(mint_rent_len(mint, extensions)?)
+        .await
+        .map_err(|e| anyhow::anyhow!("{:?}", e))
+}
+
+/// Allocates and initializes `mint` under `token_program`, funded with `lamports`, see
+/// [`mint_rent_exemption`]. Extensions are only supported by Token-2022 and are initialized
+/// before the mint, as the program requires.
+#[allow(clippy::too_many_arguments)]
+pub fn create_mint_ixs(
+    token_program: &Pubkey,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    freeze_authority: Option<&Pubkey>,
+    decimals: u8,
+    mint: &Pubkey,
+    extensions: &[MintExtension],
+    lamports: u64,
+) -> anyhow::Result<Vec<Instruction>> {
+    if !is_token_program(token_program) {
+        return Err(anyhow::anyhow!("{} is not a token program", token_program));
+    }
+    if !extensions.is_empty() && *token_program != spl_token_2022::id() {
+        return Err(anyhow::anyhow!("Mint extensions require Token-2022"));
+    }
+    let space = mint_len(extensions)?;
+    let mut ixs = vec![solana_program::system_instruction::create_account(
+        payer,
+        mint,
+        lamports,
+        space as u64,
+        token_program,
+    )];
+    for extension in extensions {
+        ixs.push(extension.initialize_ix(mint)?);
+    }
+    ixs.push(spl_token_2022::instruction::initialize_mint(
+        token_program,
+        mint,
+        authority,
+        freeze_authority,
+        decimals,
+    )?);
+    Ok(ixs)
+}
+
+/// Mints `amount` with `mint_to_checked`, so the token program rejects an amount made for
+/// other decimals than the mint's.
+pub async fn mint_tokens<P: Signer + ?Sized, A: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &P,
+    authority: &A,
+    mint: &Pubkey,
+    account: &Pubkey,
+    amount: TokenAmount,
+    additional_signer: Option<&dyn Signer>,
+) -> anyhow::Result<Signature> {
+    let (payer_ref, authority_ref) = (SignerRef(payer), SignerRef(authority));
+    let mut signing_keypairs: Vec<&dyn Signer> = vec![&payer_ref, &authority_ref];
+    if let Some(signer) = additional_signer {
+        signing_keypairs.push(signer);
+    }
+
+    let token_program = get_token_program(client, mint).await?;
+    let ix = spl_token_2022::instruction::mint_to_checked(
+        &token_program,
+        mint,
+        account,
+        &authority.pubkey(),
+        &[],
+        amount.atoms,
+        amount.decimals,
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to build mint_to_checked: {:?}", e))?;
+
+    send_and_confirm_tx(client, payer, &[ix], &signing_keypairs).await
+}
+
+/// Wrapped SOL: the SPL Token mint whose balances are backed by the token account's lamports.
+pub fn native_mint() -> Pubkey {
+    spl_token::native_mint::id()
+}
+
+pub fn get_wsol_address(owner: &Pubkey) -> Pubkey {
+    get_associated_token_address(owner, &native_mint())
+}
+
+/// Moves `lamports` from `payer` into `owner`'s WSOL associated token account, creating it if
+/// needed, and syncs its token balance.
+pub fn wrap_sol_ixs(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    lamports: u64,
+) -> anyhow::Result<Vec<Instruction>> {
+    let wsol = get_wsol_address(owner);
+    Ok(vec![
+        create_associated_token_account_idempotent(payer, owner, &native_mint(), &spl_token::id()),
+        solana_program::system_instruction::transfer(payer, &wsol, lamports),
+        sync_native_ix(&wsol)?,
+    ])
+}
+
+/// Credits lamports sent straight to a WSOL account to its token balance.
+pub fn sync_native_ix(account: &Pubkey) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::sync_native(
+        &spl_token::id(),
+        account,
+    )?)
+}
+
+/// Closes a token account of `token_program` with a zero balance, or any WSOL account, sending
+/// its lamports to `destination`.
+pub fn close_account_ix(
+    token_program: &Pubkey,
+    account: &Pubkey,
+    destination: &Pubkey,
+    owner: &Pubkey,
+) -> anyhow::Result<Instruction> {
+    Ok(spl_token_2022::instruction::close_account(
+        token_program,
+        account,
+        destination,
+        owner,
+        &[],
+    )?)
+}
+
+/// Wraps `amount` SOL into the owner's WSOL associated token account. Returns the account.
+pub async fn wrap_sol<S: Signer + ?Sized>(
+    client: &RpcClient,
+    owner: &S,
+    amount: UiAmount,
+) -> anyhow::Result<(Pubkey, Signature)> {
+    let lamports = amount.to_atoms(SOL_DECIMALS)?;
+    let ixs = wrap_sol_ixs(&owner.pubkey(), &owner.pubkey(), lamports)?;
+    let sig = send_and_confirm_tx(client, owner, &ixs, &[&SignerRef(owner)]).await?;
+    Ok((get_wsol_address(&owner.pubkey()), sig))
+}
+
+/// Unwraps the owner's whole WSOL balance by closing its associated token account, which also
+/// refunds the account's rent. SPL Token has no partial unwrap.
+pub async fn unwrap_sol<S: Signer + ?Sized>(
+    client: &RpcClient,
+    owner: &S,
+) -> anyhow::Result<Signature> {
+    let ix = close_account_ix(
+        &spl_token::id(),
+        &get_wsol_address(&owner.pubkey()),
+        &owner.pubkey(),
+        &owner.pubkey(),
+    )?;
+    send_and_confirm_tx(client, owner, &[ix], &[&SignerRef(owner)]).await
+}
+
+pub async fn sync_native<S: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &S,
+    account: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let ix = sync_native_ix(account)?;
+    send_and_confirm_tx(client, payer, &[ix], &[&SignerRef(payer)]).await
+}
+
+/// Closes `account`, under whichever token program owns it, and sends its lamports to
+/// `destination`.
+pub async fn close_token_account<S: Signer + ?Sized>(
+    client: &RpcClient,
+    owner: &S,
+    account: &Pubkey,
+    destination: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let token_program = get_account(client, account).await?.owner;
+    if !is_token_program(&token_program) {
+        return Err(anyhow::anyhow!(
+            "{} is owned by {}, not a token program",
+            account,
+            token_program
+        ));
+    }
+    let ix = close_account_ix(&token_program, account, destination, &owner.pubkey())?;
+    send_and_confirm_tx(client, owner, &[ix], &[&SignerRef(owner)]).await
+}
+
+pub fn signature_link(client: &RpcClient, signature: &Signature) -> String {
+    let cluster_url = client.url();
+    let uri_encoded_cluster_url = urlencoding::encode(&cluster_url);
+    format!(
+        "https://explorer.solana.com/tx/{}?cluster=custom&customUrl={}",
+        signature, uri_encoded_cluster_url
+    )
+}
+
+pub async fn simulate_link<S: Signer + ?Sized, T: Signers + ?Sized>(
+    client: &RpcClient,
+    payer: &S,
+    ixs: &[Instruction],
+    signers: &T,
+) -> anyhow::Result<String> {
+    let blockhash = client
+        .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
+        .await?
+        .0;
+    let tx = sign_tx(payer, ixs, signers, blockhash)?;
+    let cluster_url = client.url();
+    let uri_encoded_cluster_url = urlencoding::encode(&cluster_url);
+    let serialized_message = tx.message.serialize();
+    let base64_message = base64::engine::general_purpose::STANDARD.encode(serialized_message);
+    let uri_encoded_message = urlencoding::encode(&base64_message);
+    Ok(format!(
+        "https://explorer.solana.com/tx/inspector?message={}&cluster=custom&customUrl={}",
+        uri_encoded_message, uri_encoded_cluster_url
+    ))
+}
+
+/// Serializes a `Pubkey` as its base58 string, e.g. `#[serde(with = "serde_pubkey")]`.
+pub mod serde_pubkey {
+    use std::str::FromStr;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(key: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&key.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Pubkey::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serializes a map keyed by `Pubkey` with base58 string keys, as JSON objects require.
+pub mod serde_pubkey_map {
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer, V: Serialize>(
+        map: &HashMap<Pubkey, V>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(map.iter().map(|(k, v)| (k.to_string(), v)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, V: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<Pubkey, V>, D::Error> {
+        let map = HashMap::<String, V>::deserialize(deserializer)?;
+        map.into_iter()
+            .map(|(k, v)| {
+                Pubkey::from_str(&k)
+                    .map(|k| (k, v))
+                    .map_err(serde::de::Error::custom)
+            })
+            .collect()
+    }
+}
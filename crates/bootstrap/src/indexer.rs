@@ -0,0 +1,680 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::str::FromStr;
+
+use phoenix::state::Side;
+use rusqlite::{params, Connection, OptionalExtension};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+
+use crate::drift_vaults::{
+    self, get_perp_market_address, get_spot_market_address, MarketType, OrderAction,
+    PositionDirection, VaultDepositor, DRIFT_VAULTS_PROGRAM_ID,
+};
+use crate::events::{
+    fetch_transaction, fetch_transaction_events, parse_transaction_events, VaultEvent,
+};
+use crate::fund::{FundSummary, InvestorPosition, Venue};
+use crate::helpers::{decode_name, get_anchor_accounts};
+use crate::market::PhoenixMarket;
+use crate::market_subscriber::{parse_trades, Trade};
+use crate::nav::{ShareAction, ShareEvent};
+use crate::phoenix_vaults::{self, Investor, PHOENIX_VAULTS_PROGRAM_ID};
+
+/// Largest page `getSignaturesForAddress` returns.
+const SIGNATURE_PAGE_SIZE: usize = 1_000;
+
+/// u128 amounts don't fit an SQLite INTEGER and are stored as decimal TEXT.
+/// Cursors are kept per vault program, Drift user and Phoenix market.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS cursors (
+    address TEXT PRIMARY KEY,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    program TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    events INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS vaults (
+    pubkey TEXT PRIMARY KEY,
+    venue TEXT NOT NULL,
+    name TEXT NOT NULL,
+    manager TEXT NOT NULL,
+    init_ts INTEGER NOT NULL,
+    manager_total_profit_share INTEGER NOT NULL,
+    total_shares TEXT NOT NULL,
+    slot INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS investors (
+    pubkey TEXT PRIMARY KEY,
+    vault TEXT NOT NULL,
+    authority TEXT NOT NULL,
+    shares TEXT NOT NULL,
+    net_deposits INTEGER NOT NULL,
+    cumulative_profit_share_amount INTEGER NOT NULL,
+    slot INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS deposits (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    ts INTEGER NOT NULL,
+    vault TEXT NOT NULL,
+    authority TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    vault_equity_before INTEGER NOT NULL,
+    total_shares_after TEXT NOT NULL,
+    investor_shares_after TEXT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS withdrawals (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    ts INTEGER NOT NULL,
+    vault TEXT NOT NULL,
+    authority TEXT NOT NULL,
+    action TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    vault_equity_before INTEGER NOT NULL,
+    total_shares_after TEXT NOT NULL,
+    investor_shares_after TEXT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS fees (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    ts INTEGER NOT NULL,
+    vault TEXT NOT NULL,
+    authority TEXT NOT NULL,
+    manager_profit_share INTEGER NOT NULL,
+    management_fee INTEGER NOT NULL,
+    protocol_profit_share INTEGER NOT NULL,
+    protocol_fee INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS trades (
+    signature TEXT NOT NULL,
+    venue TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    ts INTEGER NOT NULL,
+    vault TEXT NOT NULL,
+    market TEXT NOT NULL,
+    side TEXT NOT NULL,
+    maker INTEGER NOT NULL,
+    base_amount INTEGER NOT NULL,
+    quote_amount INTEGER NOT NULL,
+    PRIMARY KEY (signature, venue, event_index)
+);
+";
+
+/// A fill a vault was part of, from the vault's side of the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VaultTrade {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub venue: Venue,
+    /// The Phoenix market, or the Drift perp or spot market account.
+    pub market: Pubkey,
+    /// `Bid` when the vault bought, Drift longs included.
+    pub side: Side,
+    pub maker: bool,
+    /// Base atoms, BASE_PRECISION for Drift perps.
+    pub base_amount: u64,
+    /// Quote atoms.
+    pub quote_amount: u64,
+}
+
+impl VaultTrade {
+    /// Fills of `vault`'s Drift `user` among `events`, each with its event index.
+    pub fn from_drift_events(
+        events: &[VaultEvent],
+        vault: &Pubkey,
+        user: &Pubkey,
+    ) -> Vec<(usize, Self)> {
+        events
+            .iter()
+            .enumerate()
+            .filter_map(|(index, event)| {
+                let record = match event {
+                    VaultEvent::OrderActionRecord(r) if r.action == OrderAction::Fill => r,
+                    _ => return None,
+                };
+                let (direction, maker) = if record.taker == Some(*user) {
+                    (record.taker_order_direction?, false)
+                } else if record.maker == Some(*user) {
+                    (record.maker_order_direction?, true)
+                } else {
+                    return None;
+                };
+                let market = match record.market_type {
+                    MarketType::Perp => get_perp_market_address(record.market_index).0,
+                    MarketType::Spot => get_spot_market_address(record.market_index).0,
+                };
+                let side = match direction {
+                    PositionDirection::Long => Side::Bid,
+                    PositionDirection::Short => Side::Ask,
+                };
+                Some((
+                    index,
+                    Self {
+                        ts: record.ts,
+                        vault: *vault,
+                        venue: Venue::Drift,
+                        market,
+                        side,
+                        maker,
+                        base_amount: record.base_asset_amount_filled?,
+                        quote_amount: record.quote_asset_amount_filled?,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// Fills of any of `vaults` among `trades`, each with its index in `trades`.
+    pub fn from_phoenix_trades(trades: &[Trade], vaults: &HashSet<Pubkey>) -> Vec<(usize, Self)> {
+        trades
+            .iter()
+            .enumerate()
+            .filter_map(|(index, trade)| {
+                let (vault, side, maker) = if vaults.contains(&trade.taker) {
+                    (trade.taker, trade.taker_side, false)
+                } else if vaults.contains(&trade.maker) {
+                    (trade.maker, trade.taker_side.opposite(), true)
+                } else {
+                    return None;
+                };
+                Some((
+                    index,
+                    Self {
+                        ts: trade.timestamp,
+                        vault,
+                        venue: Venue::Phoenix,
+                        market: trade.market,
+                        side,
+                        maker,
+                        base_amount: trade.size.atoms,
+                        quote_amount: trade.quote_size.atoms,
+                    },
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Fees charged by a depositor or investor record.
+struct FeeRecord {
+    manager_profit_share: u64,
+    management_fee: i64,
+    protocol_profit_share: u64,
+    protocol_fee: i64,
+}
+
+impl FeeRecord {
+    fn from_event(event: &VaultEvent) -> Option<Self> {
+        let fees = match event {
+            VaultEvent::VaultDepositorRecord(r) => Self {
+                manager_profit_share: r.profit_share,
+                management_fee: r.management_fee,
+                protocol_profit_share: 0,
+                protocol_fee: 0,
+            },
+            VaultEvent::VaultDepositorV1Record(r) => Self {
+                manager_profit_share: r.manager_profit_share,
+                management_fee: r.management_fee,
+                protocol_profit_share: r.protocol_profit_share,
+                protocol_fee: r.protocol_fee,
+            },
+            VaultEvent::InvestorRecord(r) => Self {
+                manager_profit_share: r.manager_profit_share,
+                management_fee: r.management_fee,
+                protocol_profit_share: r.protocol_profit_share,
+                protocol_fee: r.protocol_fee,
+            },
+            _ => return None,
+        };
+        let charged = fees.manager_profit_share != 0
+            || fees.management_fee != 0
+            || fees.protocol_profit_share != 0
+            || fees.protocol_fee != 0;
+        charged.then_some(fees)
+    }
+}
+
+/// Vault history of drift_vaults and phoenix_vaults in a local SQLite database.
+pub struct Indexer {
+    pub conn: Connection,
+}
+
+impl Indexer {
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::new(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    fn new(conn: Connection) -> anyhow::Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// The newest signature of `address` already written.
+    pub fn cursor(&self, address: &Pubkey) -> anyhow::Result<Option<Signature>> {
+        let signature: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT signature FROM cursors WHERE address = ?1",
+                params![address.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        signature
+            .map(|s| Signature::from_str(&s).map_err(|e| anyhow::anyhow!("{:?}", e)))
+            .transpose()
+    }
+
+    /// Writes the events of one transaction and moves the cursor past it, atomically.
+    pub fn insert_transaction(
+        &mut self,
+        program: &Pubkey,
+        signature: &str,
+        slot: u64,
+        block_time: Option<i64>,
+        events: &[VaultEvent],
+    ) -> anyhow::Result<()> {
+        let tx = self.conn.transaction()?;
+        for (index, event) in events.iter().enumerate() {
+            if let Some(share_event) = event.share_event() {
+                insert_share_event(&tx, signature, index, slot, &share_event)?;
+                if let Some(fees) = FeeRecord::from_event(event) {
+                    tx.execute(
+                        "INSERT OR IGNORE INTO fees VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                        params![
+                            signature,
+                            index,
+                            slot,
+                            share_event.ts,
+                            share_event.vault.to_string(),
+                            share_event.authority.to_string(),
+                            fees.manager_profit_share,
+                            fees.management_fee,
+                            fees.protocol_profit_share,
+                            fees.protocol_fee,
+                        ],
+                    )?;
+                }
+            }
+        }
+        tx.execute(
+            "INSERT OR IGNORE INTO transactions VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                signature,
+                program.to_string(),
+                slot,
+                block_time,
+                events.len()
+            ],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO cursors VALUES (?1, ?2, ?3)",
+            params![program.to_string(), signature, slot],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Writes the vault fills of one transaction of `address` and moves its cursor past it, atomically.
+    pub fn insert_trades(
+        &mut self,
+        address: &Pubkey,
+        signature: &str,
+        slot: u64,
+        trades: &[(usize, VaultTrade)],
+    ) -> anyhow::Result<()> {
+        let tx = self.conn.transaction()?;
+        for (index, trade) in trades {
+            tx.execute(
+                "INSERT OR IGNORE INTO trades VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    signature,
+                    format!("{:?}", trade.venue),
+                    index,
+                    slot,
+                    trade.ts,
+                    trade.vault.to_string(),
+                    trade.market.to_string(),
+                    format!("{:?}", trade.side),
+                    trade.maker,
+                    trade.base_amount,
+                    trade.quote_amount,
+                ],
+            )?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO cursors VALUES (?1, ?2, ?3)",
+            params![address.to_string(), signature, slot],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn upsert_vault(
+        &self,
+        summary: &FundSummary,
+        total_shares: u128,
+        slot: u64,
+    ) -> anyhow::Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO vaults VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                summary.vault.to_string(),
+                format!("{:?}", summary.venue),
+                decode_name(&summary.name),
+                summary.manager.to_string(),
+                summary.init_ts,
+                summary.manager_total_profit_share,
+                total_shares.to_string(),
+                slot,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn upsert_investor(
+        &self,
+        vault: &Pubkey,
+        investor: &InvestorPosition,
+        slot: u64,
+    ) -> anyhow::Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO investors VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                investor.pubkey.to_string(),
+                vault.to_string(),
+                investor.authority.to_string(),
+                investor.shares.to_string(),
+                investor.net_deposits,
+                investor.cumulative_profit_share_amount,
+                slot,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Every signature of `address` newer than the cursor and not before `since`, oldest first.
+    async fn new_signatures(
+        &self,
+        client: &RpcClient,
+        address: &Pubkey,
+        since: Option<i64>,
+    ) -> anyhow::Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let until = self.cursor(address)?;
+        let mut before = None;
+        let mut signatures = vec![];
+        loop {
+            let page = client
+                .get_signatures_for_address_with_config(
+                    address,
+                    GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until,
+                        limit: Some(SIGNATURE_PAGE_SIZE),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                    },
+                )
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to get signatures: {:?}", e))?;
+            let last = match page.last() {
+                Some(last) => Signature::from_str(&last.signature)?,
+                None => break,
+            };
+            let full = page.len() == SIGNATURE_PAGE_SIZE;
+            // pages run newest to oldest, so everything after the first older signature is too
+            let older = page.iter().position(
+                |status| matches!((status.block_time, since), (Some(t), Some(since)) if t < since),
+            );
+            match older {
+                Some(position) => {
+                    signatures.extend(page.into_iter().take(position));
+                    break;
+                }
+                None => signatures.extend(page),
+            }
+            if !full {
+                break;
+            }
+            before = Some(last);
+        }
+        signatures.reverse();
+        Ok(signatures)
+    }
+
+    /// Indexes every transaction of `program` since the last run. Returns how many were processed.
+    pub async fn backfill(
+        &mut self,
+        client: &RpcClient,
+        program: &Pubkey,
+    ) -> anyhow::Result<usize> {
+        let signatures = self.new_signatures(client, program, None).await?;
+        for status in &signatures {
+            // failed transactions emit no events but still advance the cursor
+            let events = match status.err {
+                Some(_) => vec![],
+                None => {
+                    let signature = Signature::from_str(&status.signature)?;
+                    fetch_transaction_events(client, &signature).await?
+                }
+            };
+            self.insert_transaction(
+                program,
+                &status.signature,
+                status.slot,
+                status.block_time,
+                &events,
+            )?;
+        }
+        Ok(signatures.len())
+    }
+
+    /// Indexes the vault fills `fills` finds in each transaction of `address` since the last run,
+    /// skipping anything before `since`. Returns how many transactions were processed.
+    pub async fn backfill_trades<F>(
+        &mut self,
+        client: &RpcClient,
+        address: &Pubkey,
+        since: i64,
+        fills: F,
+    ) -> anyhow::Result<usize>
+    where
+        F: Fn(
+            &EncodedConfirmedTransactionWithStatusMeta,
+            &str,
+        ) -> anyhow::Result<Vec<(usize, VaultTrade)>>,
+    {
+        let signatures = self.new_signatures(client, address, Some(since)).await?;
+        for status in &signatures {
+            let trades = match status.err {
+                Some(_) => vec![],
+                None => {
+                    let signature = Signature::from_str(&status.signature)?;
+                    fills(
+                        &fetch_transaction(client, &signature).await?,
+                        &status.signature,
+                    )?
+                }
+            };
+            self.insert_trades(address, &status.signature, status.slot, &trades)?;
+        }
+        Ok(signatures.len())
+    }
+
+    /// Indexes the fills of every Drift vault's user and of every Phoenix vault on the markets it
+    /// holds a position in. Returns how many transactions of each user and market were processed.
+    pub async fn backfill_vault_trades(
+        &mut self,
+        client: &RpcClient,
+    ) -> anyhow::Result<Vec<(Pubkey, usize)>> {
+        let mut counts = vec![];
+        let vaults =
+            get_anchor_accounts::<drift_vaults::Vault>(client, &DRIFT_VAULTS_PROGRAM_ID, vec![])
+                .await?;
+        for (_, vault) in &vaults {
+            let count = self
+                .backfill_trades(client, &vault.user, vault.init_ts, |tx, _| {
+                    let events = parse_transaction_events(tx)?;
+                    Ok(VaultTrade::from_drift_events(
+                        &events,
+                        &vault.pubkey,
+                        &vault.user,
+                    ))
+                })
+                .await?;
+            counts.push((vault.user, count));
+        }
+
+        let vaults = get_anchor_accounts::<phoenix_vaults::Vault>(
+            client,
+            &PHOENIX_VAULTS_PROGRAM_ID,
+            vec![],
+        )
+        .await?;
+        // a market is scanned back to the first vault on it, maker fills don't mention the vault
+        let mut since: HashMap<Pubkey, i64> = HashMap::new();
+        for (_, vault) in &vaults {
+            for position in vault.positions.iter() {
+                if position.market != Pubkey::default() {
+                    let ts = since.entry(position.market).or_insert(vault.init_ts);
+                    *ts = (*ts).min(vault.init_ts);
+                }
+            }
+        }
+        let mut markets = HashMap::new();
+        for market in since.keys() {
+            markets.insert(*market, PhoenixMarket::fetch(client, market).await?);
+        }
+        let vaults: HashSet<Pubkey> = vaults.iter().map(|(key, _)| *key).collect();
+        for (market, since) in &since {
+            let count = self
+                .backfill_trades(client, market, *since, |tx, signature| {
+                    let trades = parse_trades(tx, signature, &markets)?;
+                    Ok(VaultTrade::from_phoenix_trades(&trades, &vaults))
+                })
+                .await?;
+            counts.push((*market, count));
+        }
+        Ok(counts)
+    }
+
+    /// Overwrites vaults and investors with the current state of every account.
+    pub async fn snapshot_accounts(&self, client: &RpcClient) -> anyhow::Result<()> {
+        let slot = client
+            .get_slot_with_commitment(CommitmentConfig::confirmed())
+            .await?;
+
+        let vaults =
+            get_anchor_accounts::<drift_vaults::Vault>(client, &DRIFT_VAULTS_PROGRAM_ID, vec![])
+                .await?;
+        for (_, vault) in &vaults {
+            self.upsert_vault(&vault.fund_summary(None), vault.total_shares, slot)?;
+        }
+        let depositors =
+            get_anchor_accounts::<VaultDepositor>(client, &DRIFT_VAULTS_PROGRAM_ID, vec![]).await?;
+        for (key, depositor) in depositors {
+            let vault = depositor.vault;
+            self.upsert_investor(&vault, &InvestorPosition::from((key, depositor)), slot)?;
+        }
+
+        let vaults = get_anchor_accounts::<phoenix_vaults::Vault>(
+            client,
+            &PHOENIX_VAULTS_PROGRAM_ID,
+            vec![],
+        )
+        .await?;
+        for (_, vault) in &vaults {
+            self.upsert_vault(&vault.fund_summary(), vault.total_shares, slot)?;
+        }
+        let investors =
+            get_anchor_accounts::<Investor>(client, &PHOENIX_VAULTS_PROGRAM_ID, vec![]).await?;
+        for (key, investor) in investors {
+            let vault = investor.vault;
+            self.upsert_investor(&vault, &InvestorPosition::from((key, investor)), slot)?;
+        }
+        Ok(())
+    }
+
+    /// Backfills both vault programs and the vaults' trades, then refreshes the account tables.
+    /// Returns how many transactions of each program, Drift user and Phoenix market were indexed.
+    pub async fn run(&mut self, client: &RpcClient) -> anyhow::Result<Vec<(Pubkey, usize)>> {
+        let mut counts = vec![];
+        for program in [DRIFT_VAULTS_PROGRAM_ID, PHOENIX_VAULTS_PROGRAM_ID] {
+            counts.push((program, self.backfill(client, &program).await?));
+        }
+        counts.extend(self.backfill_vault_trades(client).await?);
+        self.snapshot_accounts(client).await?;
+        Ok(counts)
+    }
+}
+
+fn insert_share_event(
+    conn: &Connection,
+    signature: &str,
+    index: usize,
+    slot: u64,
+    event: &ShareEvent,
+) -> anyhow::Result<()> {
+    match event.action {
+        ShareAction::Deposit => {
+            conn.execute(
+                "INSERT OR IGNORE INTO deposits VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    event.ts,
+                    event.vault.to_string(),
+                    event.authority.to_string(),
+                    event.amount,
+                    event.vault_equity_before,
+                    event.total_shares_after.to_string(),
+                    event.investor_shares_after.to_string(),
+                ],
+            )?;
+        }
+        ShareAction::WithdrawRequest
+        | ShareAction::CancelWithdrawRequest
+        | ShareAction::Withdraw => {
+            conn.execute(
+                "INSERT OR IGNORE INTO withdrawals VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    signature,
+                    index,
+                    slot,
+                    event.ts,
+                    event.vault.to_string(),
+                    event.authority.to_string(),
+                    format!("{:?}", event.action),
+                    event.amount,
+                    event.vault_equity_before,
+                    event.total_shares_after.to_string(),
+                    event.investor_shares_after.to_string(),
+                ],
+            )?;
+        }
+        ShareAction::FeePayment => {}
+    }
+    Ok(())
+}
@@ -1 +1,21 @@
-
+pub mod amount;
+pub mod drift_vaults;
+pub mod events;
+pub mod fund;
+pub mod helpers;
+pub mod indexer;
+pub mod market;
+pub mod market_bootstrap;
+pub mod market_subscriber;
+pub mod multisig;
+pub mod nav;
+pub mod nonce;
+pub mod offline;
+pub mod phoenix_vaults;
+pub mod pnl;
+pub mod pyth;
+pub mod signer;
+pub mod subscriber;
+pub mod token;
+pub mod token_metadata;
+pub mod vault_update;
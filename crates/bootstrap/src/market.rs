@@ -0,0 +1,235 @@
+use phoenix::program::{load_with_dispatch, MarketHeader};
+use phoenix::quantities::WrapperU64;
+use phoenix::state::markets::{FIFOOrderId, FIFORestingOrder, Ladder, Market};
+use phoenix::state::{OrderPacket, Side, TraderState};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::amount::{TokenAmount, UiAmount};
+use crate::helpers::get_account;
+
+type DynMarket<'a> = &'a dyn Market<Pubkey, FIFOOrderId, FIFORestingOrder, OrderPacket>;
+
+/// An order resting on the book that belongs to a single trader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenOrder {
+    pub side: Side,
+    pub price_in_ticks: u64,
+    pub order_sequence_number: u64,
+    pub base_lots: u64,
+}
+
+/// Raw Phoenix market account with the header decoded up front.
+/// The order book is loaded from `data` on demand since it borrows from the account bytes.
+#[derive(Clone)]
+pub struct PhoenixMarket {
+    pub key: Pubkey,
+    pub header: MarketHeader,
+    data: Vec<u8>,
+}
+
+impl PhoenixMarket {
+    pub fn new(key: Pubkey, data: Vec<u8>) -> anyhow::Result<Self> {
+        let header_len = std::mem::size_of::<MarketHeader>();
+        if data.len() < header_len {
+            return Err(anyhow::anyhow!("Market account too small: {:?}", key));
+        }
+        let header = *bytemuck::try_from_bytes::<MarketHeader>(&data[..header_len])
+            .map_err(|e| anyhow::anyhow!("Failed to deserialize market header: {:?}", e))?;
+        Ok(Self { key, header, data })
+    }
+
+    pub async fn fetch(client: &RpcClient, key: &Pubkey) -> anyhow::Result<Self> {
+        let account = get_account(client, key).await?;
+        Self::new(*key, account.data)
+    }
+
+    pub fn market(&self) -> anyhow::Result<DynMarket<'_>> {
+        let (_, market_bytes) = self.data.split_at(std::mem::size_of::<MarketHeader>());
+        Ok(
+            load_with_dispatch(&self.header.market_size_params, market_bytes)
+                .map_err(|e| anyhow::anyhow!("Failed to load market {:?}: {:?}", self.key, e))?
+                .inner,
+        )
+    }
+
+    pub fn base_mint(&self) -> Pubkey {
+        self.header.base_params.mint_key
+    }
+
+    pub fn quote_mint(&self) -> Pubkey {
+        self.header.quote_params.mint_key
+    }
+
+    pub fn base_decimals(&self) -> u32 {
+        self.header.base_params.decimals
+    }
+
+    pub fn quote_decimals(&self) -> u32 {
+        self.header.quote_params.decimals
+    }
+
+    /// Phoenix token account holding base tokens deposited by traders.
+    pub fn base_vault(&self) -> Pubkey {
+        self.header.base_params.vault_key
+    }
+
+    /// Phoenix token account holding quote tokens deposited by traders.
+    pub fn quote_vault(&self) -> Pubkey {
+        self.header.quote_params.vault_key
+    }
+
+    pub fn base_atoms_per_base_lot(&self) -> u64 {
+        self.header.get_base_lot_size().as_u64()
+    }
+
+    pub fn quote_atoms_per_quote_lot(&self) -> u64 {
+        self.header.get_quote_lot_size().as_u64()
+    }
+
+    pub fn tick_size_in_quote_atoms_per_base_unit(&self) -> u64 {
+        self.header
+            .get_tick_size_in_quote_atoms_per_base_unit()
+            .as_u64()
+    }
+
+    pub fn raw_base_units_per_base_unit(&self) -> u32 {
+        self.header.raw_base_units_per_base_unit.max(1)
+    }
+
+    //
+    // Conversions between UI units and market lots/ticks.
+    // Sizes are exact, anything that isn't a whole number of lots is rejected.
+    //
+
+    /// Fails rather than rounding if `price` is zero or not a whole number of ticks.
+    pub fn price_to_ticks(&self, price: UiAmount) -> anyhow::Result<u64> {
+        if price.is_zero() {
+            return Err(anyhow::anyhow!("Price must be positive"));
+        }
+        let quote_atoms_per_base_unit = price
+            .checked_mul(self.raw_base_units_per_base_unit() as u64)
+            .ok_or(anyhow::anyhow!("Price {} overflows", price))?
+            .to_atoms(u8::try_from(self.quote_decimals())?)
+            .map_err(|e| anyhow::anyhow!("Invalid price {}: {}", price, e))?;
+        let tick_size = self.tick_size_in_quote_atoms_per_base_unit();
+        if tick_size == 0 {
+            return Err(anyhow::anyhow!("Tick size must not be zero"));
+        }
+        if !quote_atoms_per_base_unit.is_multiple_of(tick_size) {
+            return Err(anyhow::anyhow!(
+                "Price {} is not a whole number of {} ticks",
+                price,
+                self.ticks_to_price(1)
+            ));
+        }
+        Ok(quote_atoms_per_base_unit / tick_size)
+    }
+
+    pub fn ticks_to_price(&self, price_in_ticks: u64) -> f64 {
+        // widened since ladder prices from the chain can be arbitrarily large
+        (price_in_ticks as u128 * self.tick_size_in_quote_atoms_per_base_unit() as u128) as f64
+            / 10_f64.powi(self.quote_decimals() as i32)
+            / self.raw_base_units_per_base_unit() as f64
+    }
+
+    /// Fails if `base_units` is more precise than the base mint or the base lot size.
+    pub fn base_units_to_lots(&self, base_units: UiAmount) -> anyhow::Result<u64> {
+        base_units
+            .with_decimals(u8::try_from(self.base_decimals())?)?
+            .to_lots(self.base_atoms_per_base_lot())
+    }
+
+    pub fn base_lots_to_amount(&self, base_lots: u64) -> anyhow::Result<TokenAmount> {
+        TokenAmount::from_lots(
+            base_lots,
+            self.base_atoms_per_base_lot(),
+            u8::try_from(self.base_decimals())?,
+        )
+    }
+
+    /// Fails if `quote_units` is more precise than the quote mint or the quote lot size.
+    pub fn quote_units_to_lots(&self, quote_units: UiAmount) -> anyhow::Result<u64> {
+        quote_units
+            .with_decimals(u8::try_from(self.quote_decimals())?)?
+            .to_lots(self.quote_atoms_per_quote_lot())
+    }
+
+    pub fn quote_lots_to_amount(&self, quote_lots: u64) -> anyhow::Result<TokenAmount> {
+        TokenAmount::from_lots(
+            quote_lots,
+            self.quote_atoms_per_quote_lot(),
+            u8::try_from(self.quote_decimals())?,
+        )
+    }
+
+    /// What `base_lots` cost at `price_in_ticks`, rounded down to a whole quote atom.
+    pub fn fill_quote_amount(
+        &self,
+        price_in_ticks: u64,
+        base_lots: u64,
+    ) -> anyhow::Result<TokenAmount> {
+        let base_atoms_per_base_unit = 10_u128
+            .checked_pow(self.base_decimals())
+            .and_then(|atoms| atoms.checked_mul(self.raw_base_units_per_base_unit() as u128))
+            .ok_or(anyhow::anyhow!("Base unit overflows"))?;
+        let quote_atoms = (price_in_ticks as u128)
+            .checked_mul(self.tick_size_in_quote_atoms_per_base_unit() as u128)
+            .and_then(|atoms| atoms.checked_mul(base_lots as u128))
+            .and_then(|atoms| atoms.checked_mul(self.base_atoms_per_base_lot() as u128))
+            .ok_or(anyhow::anyhow!("Fill of {} lots overflows", base_lots))?
+            / base_atoms_per_base_unit;
+        Ok(TokenAmount::new(
+            u64::try_from(quote_atoms)?,
+            u8::try_from(self.quote_decimals())?,
+        ))
+    }
+
+    //
+    // Book and trader state
+    //
+
+    pub fn ladder(&self, levels: u64) -> anyhow::Result<Ladder> {
+        Ok(self.market()?.get_ladder(levels))
+    }
+
+    /// Best bid and ask in UI price units, if either side of the book has liquidity.
+    pub fn best_bid_ask(&self) -> anyhow::Result<(Option<f64>, Option<f64>)> {
+        let ladder = self.ladder(1)?;
+        let bid = ladder
+            .bids
+            .first()
+            .map(|o| self.ticks_to_price(o.price_in_ticks));
+        let ask = ladder
+            .asks
+            .first()
+            .map(|o| self.ticks_to_price(o.price_in_ticks));
+        Ok((bid, ask))
+    }
+
+    pub fn trader_state(&self, trader: &Pubkey) -> anyhow::Result<Option<TraderState>> {
+        Ok(self.market()?.get_trader_state(trader).copied())
+    }
+
+    pub fn open_orders(&self, trader: &Pubkey) -> anyhow::Result<Vec<OpenOrder>> {
+        let market = self.market()?;
+        let trader_index = match market.get_trader_index(trader) {
+            Some(index) => index as u64,
+            None => return Ok(vec![]),
+        };
+        let mut orders = vec![];
+        for side in [Side::Bid, Side::Ask] {
+            for (order_id, order) in market.get_book(side).iter() {
+                if order.trader_index == trader_index {
+                    orders.push(OpenOrder {
+                        side,
+                        price_in_ticks: order_id.price_in_ticks.as_u64(),
+                        order_sequence_number: order_id.order_sequence_number,
+                        base_lots: order.num_base_lots.as_u64(),
+                    });
+                }
+            }
+        }
+        Ok(orders)
+    }
+}
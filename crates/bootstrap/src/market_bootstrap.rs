@@ -0,0 +1,612 @@
+use std::path::{Path, PathBuf};
+
+use anchor_spl::token::spl_token;
+use phoenix::program::status::MarketStatus;
+use phoenix::program::*;
+use phoenix_seat_manager::get_seat_manager_address;
+use phoenix_seat_manager::instruction_builders::create_claim_market_authority_instruction;
+use phoenix_seat_manager::seat_manager::SeatManager;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature};
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::amount::UiAmount;
+use crate::helpers::*;
+use crate::token_metadata::{
+    create_mint_metadata, get_metadata_address, MintMetadata, TOKEN_METADATA_PROGRAM_ID,
+};
+
+pub const BOOK_SIZE: usize = 4096;
+pub const NUM_SEATS: usize = 8321;
+
+pub const MOCK_MARKET_AUTHORITY_KEYPAIR: [u8; 64] = [
+    66, 123, 76, 224, 250, 46, 45, 185, 92, 44, 26, 59, 177, 162, 57, 152, 152, 168, 214, 27, 185,
+    110, 97, 62, 226, 94, 214, 190, 206, 253, 83, 234, 143, 207, 63, 171, 250, 160, 85, 171, 204,
+    57, 11, 146, 117, 118, 22, 155, 104, 251, 84, 131, 255, 168, 226, 187, 237, 120, 54, 43, 103,
+    65, 121, 161,
+];
+
+pub const MOCK_USDC_MINT: [u8; 64] = [
+    87, 198, 89, 198, 67, 63, 51, 219, 219, 205, 135, 80, 234, 56, 140, 16, 89, 50, 81, 229, 158,
+    31, 99, 65, 96, 2, 245, 44, 73, 148, 172, 223, 207, 221, 139, 122, 3, 190, 18, 238, 58, 168,
+    238, 122, 70, 81, 217, 218, 189, 29, 109, 94, 252, 95, 110, 157, 33, 107, 20, 14, 201, 83, 184,
+    122,
+];
+pub const MOCK_USDC_DECIMALS: u8 = 6;
+
+pub const MOCK_SOL_MINT: [u8; 64] = [
+    168, 35, 20, 1, 139, 84, 3, 188, 183, 74, 164, 142, 249, 104, 144, 203, 18, 74, 246, 121, 144,
+    17, 17, 220, 68, 183, 73, 72, 98, 138, 227, 243, 236, 2, 190, 43, 13, 5, 202, 115, 113, 27,
+    211, 68, 74, 123, 176, 95, 132, 166, 213, 212, 17, 228, 204, 134, 113, 149, 209, 227, 99, 7,
+    170, 237,
+];
+pub const MOCK_SOL_DECIMALS: u8 = 9;
+
+pub const MOCK_JUP_MINT: [u8; 64] = [
+    239, 37, 196, 242, 130, 217, 89, 30, 157, 246, 22, 44, 213, 30, 154, 9, 107, 91, 87, 56, 32,
+    44, 132, 214, 205, 160, 235, 21, 193, 82, 156, 27, 0, 52, 31, 170, 133, 18, 164, 125, 228, 81,
+    137, 2, 18, 235, 65, 106, 203, 192, 88, 222, 174, 198, 7, 131, 115, 181, 13, 17, 236, 173, 207,
+    77,
+];
+pub const MOCK_JUP_DECIMALS: u8 = 9;
+
+pub const MOCK_SOL_USDC_MARKET: [u8; 64] = [
+    93, 15, 240, 33, 150, 60, 211, 167, 231, 22, 41, 204, 200, 97, 206, 142, 26, 4, 165, 42, 10,
+    250, 122, 223, 206, 1, 229, 158, 165, 59, 223, 236, 43, 187, 177, 182, 105, 104, 42, 76, 105,
+    0, 63, 206, 168, 171, 153, 177, 92, 111, 205, 70, 213, 77, 79, 158, 212, 90, 50, 22, 37, 161,
+    233, 161,
+];
+
+pub const MOCK_JUP_SOL_MARKET: [u8; 64] = [
+    15, 151, 240, 120, 77, 168, 237, 143, 234, 212, 68, 61, 31, 86, 52, 247, 1, 94, 88, 16, 218,
+    194, 238, 146, 159, 57, 164, 139, 27, 8, 199, 208, 149, 224, 247, 248, 83, 62, 63, 218, 7, 175,
+    97, 67, 149, 214, 103, 186, 179, 0, 75, 42, 193, 199, 229, 89, 59, 238, 67, 228, 155, 206, 166,
+    232,
+];
+
+pub const MOCK_JUP_USDC_MARKET: [u8; 64] = [
+    136, 1, 116, 112, 92, 96, 18, 218, 159, 171, 129, 153, 142, 137, 45, 170, 71, 12, 207, 146, 4,
+    42, 43, 220, 224, 11, 240, 249, 154, 169, 93, 114, 97, 155, 77, 41, 195, 245, 43, 240, 189,
+    119, 112, 171, 181, 73, 151, 234, 158, 154, 244, 252, 42, 218, 124, 117, 43, 55, 204, 36, 167,
+    160, 42, 233,
+];
+
+/// A mint to create from its keypair if missing, or one that already exists such as
+/// [`native_mint`].
+#[derive(Clone, Copy)]
+pub enum BootstrapMint<'a> {
+    Keypair(&'a Keypair),
+    Existing(Pubkey),
+}
+
+impl BootstrapMint<'_> {
+    pub fn pubkey(&self) -> Pubkey {
+        match self {
+            BootstrapMint::Keypair(keypair) => keypair.pubkey(),
+            BootstrapMint::Existing(key) => *key,
+        }
+    }
+}
+
+pub struct BootstrapMarketConfig<'a> {
+    pub client: &'a RpcClient,
+    pub payer: &'a dyn Signer,
+    pub authority: &'a dyn Signer,
+    pub market: &'a dyn Signer,
+    pub quote_mint: BootstrapMint<'a>,
+    pub base_mint: BootstrapMint<'a>,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    pub num_quote_lots_per_quote_unit: Option<u64>,
+    pub num_base_lots_per_base_unit: Option<u64>,
+    pub tick_size_in_quote_lots_per_base_unit: Option<u64>,
+    pub fee_bps: Option<u16>,
+    pub raw_base_units_per_base_unit: Option<u32>,
+}
+
+/// What bootstrapping did about one account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BootstrapStatus {
+    Created(Signature),
+    /// Already on the cluster, left as is.
+    Existing,
+    /// Not attempted, for the given reason.
+    Skipped(String),
+}
+
+/// One account a bootstrap created or found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootstrapStep {
+    /// What the account is, e.g. `SOL/USDC market`.
+    pub name: String,
+    pub address: Pubkey,
+    pub status: BootstrapStatus,
+}
+
+impl BootstrapStep {
+    fn new(name: impl Into<String>, address: Pubkey, sig: Option<Signature>) -> Self {
+        Self {
+            name: name.into(),
+            address,
+            status: sig.map_or(BootstrapStatus::Existing, BootstrapStatus::Created),
+        }
+    }
+}
+
+/// Creates the mints and market if missing, and makes sure the seat manager has claimed the market.
+/// Returns the market and a step for each account, in order.
+pub async fn bootstrap_market(
+    cfg: BootstrapMarketConfig<'_>,
+) -> anyhow::Result<(BootstrappedMarket, Vec<BootstrapStep>)> {
+    let BootstrapMarketConfig {
+        client,
+        payer,
+        authority,
+        market,
+        quote_mint,
+        base_mint,
+        base_decimals,
+        quote_decimals,
+        num_quote_lots_per_quote_unit: _num_quote_lots_per_quote_unit,
+        num_base_lots_per_base_unit: _num_base_lots_per_base_unit,
+        tick_size_in_quote_lots_per_base_unit: _tick_size_in_quote_lots_per_base_unit,
+        fee_bps: _fee_bps,
+        raw_base_units_per_base_unit,
+    } = cfg;
+    let num_quote_lots_per_quote_unit = _num_quote_lots_per_quote_unit.unwrap_or(100_000);
+    let num_base_lots_per_base_unit = _num_base_lots_per_base_unit.unwrap_or(1_000);
+    let tick_size_in_quote_lots_per_base_unit =
+        _tick_size_in_quote_lots_per_base_unit.unwrap_or(1_000);
+    let fee_bps = _fee_bps.unwrap_or(1);
+
+    let params = MarketSizeParams {
+        bids_size: BOOK_SIZE as u64,
+        asks_size: BOOK_SIZE as u64,
+        num_seats: NUM_SEATS as u64,
+    };
+
+    let mut steps = vec![];
+
+    // create quote token mint
+    let quote_mint_acct = get_account(client, &quote_mint.pubkey()).await;
+    let sig = match (quote_mint_acct, quote_mint) {
+        (Err(_), BootstrapMint::Keypair(quote_mint)) => Some(
+            create_mint(
+                client,
+                payer,
+                &authority.pubkey(),
+                None,
+                quote_decimals,
+                quote_mint,
+            )
+            .await?,
+        ),
+        (Err(e), BootstrapMint::Existing(_)) => return Err(e),
+        (Ok(_), _) => None,
+    };
+    steps.push(BootstrapStep::new("quote mint", quote_mint.pubkey(), sig));
+
+    // create base token mint
+    let base_mint_acct = get_account(client, &base_mint.pubkey()).await;
+    let sig = match (base_mint_acct, base_mint) {
+        (Err(_), BootstrapMint::Keypair(base_mint)) => Some(
+            create_mint(
+                client,
+                payer,
+                &authority.pubkey(),
+                None,
+                base_decimals,
+                base_mint,
+            )
+            .await?,
+        ),
+        (Err(e), BootstrapMint::Existing(_)) => return Err(e),
+        (Ok(_), _) => None,
+    };
+    steps.push(BootstrapStep::new("base mint", base_mint.pubkey(), sig));
+
+    // create quote associated token account for payer
+    let quote_ata = get_associated_token_address(&payer.pubkey(), &quote_mint.pubkey());
+    let sig = if get_account(client, &quote_ata).await.is_err() {
+        let (_, sig) = create_associated_token_account(
+            client,
+            payer,
+            &quote_mint.pubkey(),
+            &anchor_spl::token::spl_token::id(),
+        )
+        .await?;
+        Some(sig)
+    } else {
+        None
+    };
+    steps.push(BootstrapStep::new("payer quote account", quote_ata, sig));
+
+    // create market
+    let seat_manager_key = get_seat_manager_address(&market.pubkey()).0;
+    let sig = if get_account(client, &market.pubkey()).await.is_err() {
+        let mut init_instructions = vec![];
+        init_instructions.extend(
+            create_initialize_market_instructions_default(
+                &market.pubkey(),
+                &base_mint.pubkey(),
+                &quote_mint.pubkey(),
+                &payer.pubkey(),
+                params,
+                num_quote_lots_per_quote_unit,
+                num_base_lots_per_base_unit,
+                tick_size_in_quote_lots_per_base_unit,
+                fee_bps,
+                raw_base_units_per_base_unit,
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to build market instructions: {:?}", e))?,
+        );
+
+        init_instructions.push(create_name_successor_instruction(
+            &payer.pubkey(),
+            &market.pubkey(),
+            &seat_manager_key,
+        ));
+
+        init_instructions.push(create_change_market_status_instruction(
+            &payer.pubkey(),
+            &market.pubkey(),
+            MarketStatus::Active,
+        ));
+
+        Some(send_and_confirm_tx(client, payer, &init_instructions, &[payer, market]).await?)
+    } else {
+        None
+    };
+    steps.push(BootstrapStep::new("market", market.pubkey(), sig));
+
+    //
+    // claim seat manager
+    //
+
+    let market_ai = client.get_account(&market.pubkey()).await?;
+    let market_bytes = market_ai.data;
+    let (header_bytes, _) = market_bytes.split_at(std::mem::size_of::<MarketHeader>());
+    let header = bytemuck::try_from_bytes::<MarketHeader>(header_bytes)
+        .map_err(|e| anyhow::anyhow!("failed to deserialize market header: {:?}", e))?;
+    if header.base_params.mint_key != base_mint.pubkey()
+        || header.quote_params.mint_key != quote_mint.pubkey()
+    {
+        return Err(anyhow::anyhow!(
+            "Market {} already exists with mints {}/{}",
+            market.pubkey(),
+            header.base_params.mint_key,
+            header.quote_params.mint_key
+        ));
+    }
+
+    let sig = if header.authority != seat_manager_key {
+        // this creates SeatManager: https://github.com/Ellipsis-Labs/phoenix-seat-manager-v1/blob/31ad32a186d7e0e5aa747dcaa9463b7e27089b47/src/processor/claim_market_authority.rs#L98
+        let claim_auth_ix =
+            create_claim_market_authority_instruction(&market.pubkey(), &payer.pubkey());
+
+        Some(send_and_confirm_tx(client, payer, &[claim_auth_ix], &[payer]).await?)
+    } else {
+        None
+    };
+    steps.push(BootstrapStep::new("seat manager", seat_manager_key, sig));
+
+    let seat_manager_data = client.get_account_data(&seat_manager_key).await?;
+    bytemuck::try_from_bytes::<SeatManager>(&seat_manager_data)
+        .map_err(|e| anyhow::anyhow!("failed to deserialize seat manager data: {:?}", e))?;
+
+    let bootstrapped = BootstrappedMarket {
+        market: market.pubkey(),
+        base_mint: base_mint.pubkey(),
+        quote_mint: quote_mint.pubkey(),
+        seat_manager: seat_manager_key,
+    };
+    Ok((bootstrapped, steps))
+}
+
+/// A keypair file path, relative to the manifest, or the 64 secret key bytes inline
+/// (the Solana CLI keypair file format).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeypairSource {
+    Bytes(Vec<u8>),
+    Path(PathBuf),
+}
+
+impl KeypairSource {
+    pub fn load(&self, base_dir: &Path) -> anyhow::Result<Keypair> {
+        match self {
+            KeypairSource::Bytes(bytes) => Keypair::from_bytes(bytes)
+                .map_err(|e| anyhow::anyhow!("Invalid keypair bytes: {:?}", e)),
+            KeypairSource::Path(path) => {
+                let path = base_dir.join(path);
+                read_keypair_file(&path).map_err(|e| {
+                    anyhow::anyhow!("Failed to read keypair {}: {:?}", path.display(), e)
+                })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MintManifest {
+    pub symbol: String,
+    pub keypair: KeypairSource,
+    pub decimals: u8,
+    /// Metaplex metadata to attach, so wallets and explorers name the mint.
+    #[serde(default)]
+    pub metadata: Option<MintMetadata>,
+    /// Use the real native mint (wrapped SOL) as on mainnet, rather than creating one from
+    /// `keypair`. Metadata is ignored, the native mint's is not ours to set.
+    #[serde(default)]
+    pub native: bool,
+}
+
+impl MintManifest {
+    pub fn bootstrap_mint<'a>(&self, keypair: &'a Keypair) -> anyhow::Result<BootstrapMint<'a>> {
+        if !self.native {
+            return Ok(BootstrapMint::Keypair(keypair));
+        }
+        if self.decimals != spl_token::native_mint::DECIMALS {
+            return Err(anyhow::anyhow!(
+                "Native mint {} must have {} decimals",
+                self.symbol,
+                spl_token::native_mint::DECIMALS
+            ));
+        }
+        Ok(BootstrapMint::Existing(native_mint()))
+    }
+}
+
+/// A market between two mints of the manifest. Omitted lot sizes and fees use the
+/// [`bootstrap_market`] defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketManifest {
+    pub keypair: KeypairSource,
+    /// Symbol of the base mint
+    pub base: String,
+    /// Symbol of the quote mint
+    pub quote: String,
+    #[serde(default)]
+    pub num_quote_lots_per_quote_unit: Option<u64>,
+    #[serde(default)]
+    pub num_base_lots_per_base_unit: Option<u64>,
+    #[serde(default)]
+    pub tick_size_in_quote_lots_per_base_unit: Option<u64>,
+    #[serde(default)]
+    pub fee_bps: Option<u16>,
+    #[serde(default)]
+    pub raw_base_units_per_base_unit: Option<u32>,
+}
+
+impl MarketManifest {
+    pub fn name(&self) -> String {
+        format!("{}/{}", self.base, self.quote)
+    }
+}
+
+/// The mints and markets to provision on a cluster.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootstrapManifest {
+    /// Mint authority. Defaults to the payer.
+    #[serde(default)]
+    pub authority: Option<KeypairSource>,
+    /// SOL to airdrop to the payer first, for local validators and devnet.
+    #[serde(default)]
+    pub airdrop: Option<UiAmount>,
+    pub mints: Vec<MintManifest>,
+    pub markets: Vec<MarketManifest>,
+    /// Directory keypair paths are resolved against.
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+}
+
+impl BootstrapManifest {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {:?}", path.display(), e))?;
+        let mut manifest: Self = serde_json::from_str(&data)
+            .map_err(|e| anyhow::anyhow!("Invalid manifest {}: {:?}", path.display(), e))?;
+        manifest.base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(manifest)
+    }
+
+    /// The mock SOL/USDC and JUP/SOL markets, owned by [`MOCK_MARKET_AUTHORITY_KEYPAIR`].
+    pub fn mock() -> Self {
+        let mint = |name: &str, symbol: &str, keypair: &[u8; 64], decimals: u8| MintManifest {
+            symbol: symbol.to_string(),
+            keypair: KeypairSource::Bytes(keypair.to_vec()),
+            decimals,
+            metadata: Some(MintMetadata::new(name, symbol)),
+            native: false,
+        };
+        let market = |keypair: &[u8; 64], base: &str, quote: &str| MarketManifest {
+            keypair: KeypairSource::Bytes(keypair.to_vec()),
+            base: base.to_string(),
+            quote: quote.to_string(),
+            num_quote_lots_per_quote_unit: None,
+            num_base_lots_per_base_unit: None,
+            tick_size_in_quote_lots_per_base_unit: None,
+            fee_bps: None,
+            raw_base_units_per_base_unit: None,
+        };
+        Self {
+            authority: Some(KeypairSource::Bytes(MOCK_MARKET_AUTHORITY_KEYPAIR.to_vec())),
+            airdrop: Some(UiAmount::from(20)),
+            mints: vec![
+                mint("Mock USD Coin", "USDC", &MOCK_USDC_MINT, MOCK_USDC_DECIMALS),
+                mint("Mock Solana", "SOL", &MOCK_SOL_MINT, MOCK_SOL_DECIMALS),
+                mint("Mock Jupiter", "JUP", &MOCK_JUP_MINT, MOCK_JUP_DECIMALS),
+            ],
+            markets: vec![
+                market(&MOCK_SOL_USDC_MARKET, "SOL", "USDC"),
+                market(&MOCK_JUP_SOL_MARKET, "JUP", "SOL"),
+            ],
+            base_dir: PathBuf::new(),
+        }
+    }
+
+    /// Bootstraps SOL markets against the native mint instead of the mock SOL mint, so they
+    /// behave like mainnet's. Traders then need WSOL, see [`wrap_sol`].
+    pub fn with_native_sol(mut self) -> Self {
+        for mint in &mut self.mints {
+            if mint.symbol == "SOL" {
+                mint.native = true;
+            }
+        }
+        self
+    }
+
+    pub fn mint(&self, symbol: &str) -> anyhow::Result<&MintManifest> {
+        self.mints
+            .iter()
+            .find(|m| m.symbol == symbol)
+            .ok_or(anyhow::anyhow!("Mint {} is not in the manifest", symbol))
+    }
+
+    /// The mint authority, or `None` if the payer is.
+    pub fn authority(&self) -> anyhow::Result<Option<Keypair>> {
+        self.authority
+            .as_ref()
+            .map(|source| source.load(&self.base_dir))
+            .transpose()
+    }
+
+    /// Addresses of every market, without touching the cluster.
+    pub fn market_addresses(&self) -> anyhow::Result<Vec<Pubkey>> {
+        self.markets
+            .iter()
+            .map(|m| Ok(m.keypair.load(&self.base_dir)?.pubkey()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootstrappedMarket {
+    #[serde(with = "serde_pubkey")]
+    pub market: Pubkey,
+    #[serde(with = "serde_pubkey")]
+    pub base_mint: Pubkey,
+    #[serde(with = "serde_pubkey")]
+    pub quote_mint: Pubkey,
+    #[serde(with = "serde_pubkey")]
+    pub seat_manager: Pubkey,
+}
+
+/// What [`bootstrap_manifest`] provisioned, for the caller to print.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BootstrapReport {
+    /// In manifest order.
+    pub markets: Vec<BootstrappedMarket>,
+    /// Every account created or found, in order.
+    pub steps: Vec<BootstrapStep>,
+    /// Rent and fees the payer spent, after any airdrop.
+    pub lamports_spent: u64,
+}
+
+impl BootstrapReport {
+    pub fn created(&self) -> impl Iterator<Item = &BootstrapStep> {
+        self.steps
+            .iter()
+            .filter(|step| matches!(step.status, BootstrapStatus::Created(_)))
+    }
+}
+
+/// Provisions every mint and market in `manifest`. Anything that already exists is left as is,
+/// so this can be rerun against the same cluster.
+pub async fn bootstrap_manifest<S: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &S,
+    manifest: &BootstrapManifest,
+) -> anyhow::Result<BootstrapReport> {
+    let payer_ref = SignerRef(payer);
+    let payer: &dyn Signer = &payer_ref;
+    let authority_keypair = manifest.authority()?;
+    let authority: &dyn Signer = match &authority_keypair {
+        Some(keypair) => keypair,
+        None => payer,
+    };
+    if let Some(amount) = manifest.airdrop {
+        airdrop(client, &payer.pubkey(), amount).await?;
+    }
+
+    let pre_balance = get_lamports(client, &payer.pubkey()).await?;
+    let mut report = BootstrapReport::default();
+    for market_manifest in &manifest.markets {
+        let name = market_manifest.name();
+        let base = manifest.mint(&market_manifest.base)?;
+        let quote = manifest.mint(&market_manifest.quote)?;
+        let market = market_manifest.keypair.load(&manifest.base_dir)?;
+        let base_keypair = base.keypair.load(&manifest.base_dir)?;
+        let quote_keypair = quote.keypair.load(&manifest.base_dir)?;
+
+        let (market, steps) = bootstrap_market(BootstrapMarketConfig {
+            client,
+            payer,
+            authority,
+            market: &market,
+            quote_mint: quote.bootstrap_mint(&quote_keypair)?,
+            quote_decimals: quote.decimals,
+            base_mint: base.bootstrap_mint(&base_keypair)?,
+            base_decimals: base.decimals,
+
+            num_quote_lots_per_quote_unit: market_manifest.num_quote_lots_per_quote_unit,
+            num_base_lots_per_base_unit: market_manifest.num_base_lots_per_base_unit,
+            tick_size_in_quote_lots_per_base_unit: market_manifest
+                .tick_size_in_quote_lots_per_base_unit,
+            fee_bps: market_manifest.fee_bps,
+            raw_base_units_per_base_unit: market_manifest.raw_base_units_per_base_unit,
+        })
+        .await?;
+        report.markets.push(market);
+        report
+            .steps
+            .extend(steps.into_iter().map(|step| BootstrapStep {
+                name: format!("{} {}", name, step.name),
+                ..step
+            }));
+    }
+
+    for mint_manifest in manifest.mints.iter().filter(|m| !m.native) {
+        if let Some(metadata) = &mint_manifest.metadata {
+            let mint = mint_manifest.keypair.load(&manifest.base_dir)?.pubkey();
+            let step = bootstrap_mint_metadata(client, payer, authority, &mint, metadata).await?;
+            report.steps.push(step);
+        }
+    }
+    let post_balance = get_lamports(client, &payer.pubkey()).await?;
+    report.lamports_spent = pre_balance.saturating_sub(post_balance);
+    Ok(report)
+}
+
+/// Attaches `metadata` to `mint` unless it already has some. Skipped on clusters without the
+/// metadata program, such as a `solana-test-validator` started without cloning it.
+pub async fn bootstrap_mint_metadata<P: Signer + ?Sized, A: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &P,
+    authority: &A,
+    mint: &Pubkey,
+    metadata: &MintMetadata,
+) -> anyhow::Result<BootstrapStep> {
+    let name = format!("{} metadata", metadata.symbol);
+    let address = get_metadata_address(mint).0;
+    let program = get_account(client, &TOKEN_METADATA_PROGRAM_ID).await;
+    if program.is_err() {
+        return Ok(BootstrapStep {
+            name,
+            address,
+            status: BootstrapStatus::Skipped("token metadata program not deployed".to_string()),
+        });
+    }
+    let sig = match get_account(client, &address).await {
+        Ok(_) => None,
+        Err(_) => Some(create_mint_metadata(client, payer, authority, mint, metadata).await?),
+    };
+    Ok(BootstrapStep::new(name, address, sig))
+}
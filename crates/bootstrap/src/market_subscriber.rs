@@ -0,0 +1,584 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use borsh_v09::BorshDeserialize;
+use futures::StreamExt;
+use phoenix::program::events::PhoenixMarketEvent;
+use phoenix::state::Side;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+};
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiInstruction, UiTransactionEncoding,
+};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::amount::TokenAmount;
+use crate::events::transaction_account_keys;
+use crate::market::PhoenixMarket;
+use crate::phoenix_vaults::PHOENIX_PROGRAM_ID;
+use crate::subscriber::SubscriberError;
+
+/// Tag of the `Log` instruction Phoenix sends to itself to record market events.
+const PHOENIX_LOG_INSTRUCTION: u8 = 15;
+
+/// One side of an L2 level change. A size of zero removes the level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelChange {
+    pub side: Side,
+    pub price: f64,
+    pub size: f64,
+}
+
+/// Aggregated book in UI units, best level first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct L2Book {
+    /// (price, size)
+    pub bids: Vec<(f64, f64)>,
+    /// (price, size)
+    pub asks: Vec<(f64, f64)>,
+}
+
+impl L2Book {
+    pub fn from_market(market: &PhoenixMarket, levels: u64) -> anyhow::Result<Self> {
+        let ladder = market.ladder(levels)?;
+        let to_level = |price_in_ticks: u64, size_in_base_lots: u64| {
+            Ok((
+                market.ticks_to_price(price_in_ticks),
+                market.base_lots_to_amount(size_in_base_lots)?.to_f64(),
+            ))
+        };
+        Ok(Self {
+            bids: ladder
+                .bids
+                .iter()
+                .map(|l| to_level(l.price_in_ticks, l.size_in_base_lots))
+                .collect::<anyhow::Result<_>>()?,
+            asks: ladder
+                .asks
+                .iter()
+                .map(|l| to_level(l.price_in_ticks, l.size_in_base_lots))
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+
+    pub fn best_bid(&self) -> Option<f64> {
+        self.bids.first().map(|(price, _)| *price)
+    }
+
+    pub fn best_ask(&self) -> Option<f64> {
+        self.asks.first().map(|(price, _)| *price)
+    }
+
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.best_bid()? + self.best_ask()?) / 2.0)
+    }
+
+    /// Level changes that turn `self` into `next`.
+    pub fn diff(&self, next: &L2Book) -> Vec<LevelChange> {
+        let mut changes = vec![];
+        for (side, old, new) in [
+            (Side::Bid, &self.bids, &next.bids),
+            (Side::Ask, &self.asks, &next.asks),
+        ] {
+            for (price, _) in old {
+                if !new.iter().any(|(p, _)| p == price) {
+                    changes.push(LevelChange {
+                        side,
+                        price: *price,
+                        size: 0.0,
+                    });
+                }
+            }
+            for (price, size) in new {
+                if !old.iter().any(|(p, s)| p == price && s == size) {
+                    changes.push(LevelChange {
+                        side,
+                        price: *price,
+                        size: *size,
+                    });
+                }
+            }
+        }
+        changes
+    }
+
+    pub fn apply(&mut self, changes: &[LevelChange]) {
+        for change in changes {
+            let levels = match change.side {
+                Side::Bid => &mut self.bids,
+                Side::Ask => &mut self.asks,
+            };
+            levels.retain(|(price, _)| *price != change.price);
+            if change.size > 0.0 {
+                // bids descend and asks ascend from the best price
+                let index = levels
+                    .iter()
+                    .position(|(price, _)| match change.side {
+                        Side::Bid => *price < change.price,
+                        Side::Ask => *price > change.price,
+                    })
+                    .unwrap_or(levels.len());
+                levels.insert(index, (change.price, change.size));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candle {
+    /// Unix timestamp the candle opens at, a multiple of the timeframe.
+    pub start: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+/// Mid price candles of one timeframe. Periods without a sample have no candle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandleSeries {
+    /// Seconds
+    pub timeframe: i64,
+    pub candles: Vec<Candle>,
+}
+
+impl CandleSeries {
+    pub fn new(timeframe: i64) -> Self {
+        Self {
+            timeframe,
+            candles: vec![],
+        }
+    }
+
+    /// Adds a price sample, returning the previous candle if this sample opened a new one.
+    /// Samples older than the current candle are ignored.
+    pub fn update(&mut self, ts: i64, price: f64) -> Option<Candle> {
+        let start = ts - ts.rem_euclid(self.timeframe);
+        match self.candles.last_mut() {
+            Some(candle) if candle.start == start => {
+                candle.high = candle.high.max(price);
+                candle.low = candle.low.min(price);
+                candle.close = price;
+                return None;
+            }
+            Some(candle) if candle.start > start => return None,
+            _ => {}
+        }
+        let closed = self.candles.last().copied();
+        self.candles.push(Candle {
+            start,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+        });
+        closed
+    }
+}
+
+/// A fill against a resting order, from the Phoenix event log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trade {
+    pub market: Pubkey,
+    pub signature: String,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub price: f64,
+    pub size: TokenAmount,
+    /// Quote paid for `size`.
+    pub quote_size: TokenAmount,
+    pub taker_side: Side,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+}
+
+/// Fills in the Phoenix `Log` instructions of `tx`, priced with the params of `markets`.
+/// Fills on markets not in `markets` are skipped.
+pub fn parse_trades(
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+    signature: &str,
+    markets: &HashMap<Pubkey, PhoenixMarket>,
+) -> anyhow::Result<Vec<Trade>> {
+    let inner_instructions = match tx.transaction.meta.as_ref().map(|m| &m.inner_instructions) {
+        Some(OptionSerializer::Some(inner)) => inner,
+        _ => return Ok(vec![]),
+    };
+    let account_keys = transaction_account_keys(tx)?;
+    let mut trades = vec![];
+    for ix in inner_instructions
+        .iter()
+        .flat_map(|inner| &inner.instructions)
+    {
+        let ix = match ix {
+            UiInstruction::Compiled(ix) => ix,
+            UiInstruction::Parsed(_) => continue,
+        };
+        if account_keys.get(ix.program_id_index as usize) != Some(&PHOENIX_PROGRAM_ID) {
+            continue;
+        }
+        let data = bs58::decode(&ix.data)
+            .into_vec()
+            .map_err(|e| anyhow::anyhow!("Invalid instruction data: {:?}", e))?;
+        let mut events = match data.split_first() {
+            Some((&PHOENIX_LOG_INSTRUCTION, events)) => events,
+            _ => continue,
+        };
+        let mut header = None;
+        while !events.is_empty() {
+            let event = PhoenixMarketEvent::deserialize(&mut events)
+                .map_err(|e| anyhow::anyhow!("Failed to deserialize Phoenix event: {:?}", e))?;
+            match event {
+                PhoenixMarketEvent::Header(h) => header = Some(h),
+                PhoenixMarketEvent::Fill(fill) => {
+                    let header = header.ok_or(anyhow::anyhow!("Phoenix fill before header"))?;
+                    let market = match markets.get(&header.market) {
+                        Some(market) => market,
+                        None => continue,
+                    };
+                    let taker_side =
+                        match Side::from_order_sequence_number(fill.order_sequence_number) {
+                            Side::Bid => Side::Ask,
+                            Side::Ask => Side::Bid,
+                        };
+                    trades.push(Trade {
+                        market: header.market,
+                        signature: signature.to_string(),
+                        slot: tx.slot,
+                        timestamp: header.timestamp,
+                        price: market.ticks_to_price(fill.price_in_ticks),
+                        size: market.base_lots_to_amount(fill.base_lots_filled)?,
+                        quote_size: market
+                            .fill_quote_amount(fill.price_in_ticks, fill.base_lots_filled)?,
+                        taker_side,
+                        maker: fill.maker_id,
+                        taker: header.signer,
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(trades)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarketEvent {
+    Book {
+        market: Pubkey,
+        slot: u64,
+        changes: Vec<LevelChange>,
+    },
+    Quote {
+        market: Pubkey,
+        slot: u64,
+        best_bid: Option<f64>,
+        best_ask: Option<f64>,
+    },
+    Trade(Trade),
+    /// A candle that has closed.
+    Candle {
+        market: Pubkey,
+        timeframe: i64,
+        candle: Candle,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct MarketSubscriberConfig {
+    pub rpc_url: String,
+    pub ws_url: String,
+    pub markets: Vec<Pubkey>,
+    /// Levels per side kept in each [`L2Book`].
+    pub levels: u64,
+    /// Candle timeframes in seconds.
+    pub timeframes: Vec<i64>,
+    pub commitment: CommitmentConfig,
+    pub reconnect_delay: Duration,
+    pub channel_capacity: usize,
+}
+
+impl MarketSubscriberConfig {
+    pub fn new(rpc_url: &str, ws_url: &str, markets: Vec<Pubkey>) -> Self {
+        Self {
+            rpc_url: rpc_url.to_string(),
+            ws_url: ws_url.to_string(),
+            markets,
+            levels: 20,
+            timeframes: vec![60, 5 * 60, 60 * 60],
+            commitment: CommitmentConfig::confirmed(),
+            reconnect_delay: Duration::from_secs(5),
+            channel_capacity: 1_024,
+        }
+    }
+}
+
+struct MarketState {
+    market: PhoenixMarket,
+    slot: u64,
+    book: L2Book,
+    candles: Vec<CandleSeries>,
+}
+
+/// Streams Phoenix markets: L2 book changes, best bid and ask, trades and mid price candles.
+pub struct PhoenixMarketSubscriber {
+    pub config: MarketSubscriberConfig,
+    state: Mutex<HashMap<Pubkey, MarketState>>,
+    sender: broadcast::Sender<MarketEvent>,
+    errors: broadcast::Sender<SubscriberError>,
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+impl PhoenixMarketSubscriber {
+    pub fn new(config: MarketSubscriberConfig) -> Self {
+        let (sender, _) = broadcast::channel(config.channel_capacity);
+        let (errors, _) = broadcast::channel(config.channel_capacity);
+        Self {
+            config,
+            state: Mutex::new(HashMap::new()),
+            sender,
+            errors,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<MarketEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Errors [`Self::run_market`] and [`Self::run_trades`] recovered from.
+    pub fn subscribe_errors(&self) -> broadcast::Receiver<SubscriberError> {
+        self.errors.subscribe()
+    }
+
+    fn report(&self, key: &Pubkey, error: anyhow::Error) {
+        let _ = self.errors.send(SubscriberError {
+            key: *key,
+            error: format!("{:?}", error),
+        });
+    }
+
+    pub fn book(&self, market: &Pubkey) -> Option<L2Book> {
+        self.state
+            .lock()
+            .unwrap()
+            .get(market)
+            .map(|s| s.book.clone())
+    }
+
+    pub fn candles(&self, market: &Pubkey, timeframe: i64) -> Option<CandleSeries> {
+        let state = self.state.lock().unwrap();
+        state
+            .get(market)?
+            .candles
+            .iter()
+            .find(|c| c.timeframe == timeframe)
+            .cloned()
+    }
+
+    /// Every market loaded so far, for pricing fills.
+    pub fn markets(&self) -> HashMap<Pubkey, PhoenixMarket> {
+        let state = self.state.lock().unwrap();
+        state
+            .iter()
+            .map(|(key, s)| (*key, s.market.clone()))
+            .collect()
+    }
+
+    /// Applies a market account read at `slot`, sampling the mid price at `ts`.
+    /// Updates at or before the last applied slot are ignored.
+    pub fn apply_market(&self, market: PhoenixMarket, slot: u64, ts: i64) -> anyhow::Result<()> {
+        let key = market.key;
+        let book = L2Book::from_market(&market, self.config.levels)?;
+        let mut events = vec![];
+        {
+            let mut markets = self.state.lock().unwrap();
+            let state = markets.entry(key).or_insert_with(|| MarketState {
+                market: market.clone(),
+                slot: 0,
+                book: L2Book::default(),
+                candles: self
+                    .config
+                    .timeframes
+                    .iter()
+                    .map(|t| CandleSeries::new(*t))
+                    .collect(),
+            });
+            if state.slot != 0 && slot <= state.slot {
+                return Ok(());
+            }
+            let changes = state.book.diff(&book);
+            if (state.book.best_bid(), state.book.best_ask()) != (book.best_bid(), book.best_ask())
+            {
+                events.push(MarketEvent::Quote {
+                    market: key,
+                    slot,
+                    best_bid: book.best_bid(),
+                    best_ask: book.best_ask(),
+                });
+            }
+            state.book.apply(&changes);
+            if !changes.is_empty() {
+                events.push(MarketEvent::Book {
+                    market: key,
+                    slot,
+                    changes,
+                });
+            }
+            if let Some(mid) = state.book.mid() {
+                for series in state.candles.iter_mut() {
+                    if let Some(candle) = series.update(ts, mid) {
+                        events.push(MarketEvent::Candle {
+                            market: key,
+                            timeframe: series.timeframe,
+                            candle,
+                        });
+                    }
+                }
+            }
+            state.market = market;
+            state.slot = slot;
+        }
+        for event in events {
+            // no receivers is not an error, events are simply dropped
+            let _ = self.sender.send(event);
+        }
+        Ok(())
+    }
+
+    /// Fetches and broadcasts the trades of a transaction that touched a subscribed market.
+    pub async fn apply_transaction(
+        &self,
+        client: &RpcClient,
+        signature: &Signature,
+    ) -> anyhow::Result<()> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(self.config.commitment),
+            max_supported_transaction_version: Some(0),
+        };
+        let tx = client
+            .get_transaction_with_config(signature, config)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get transaction: {:?}", e))?;
+        for trade in parse_trades(&tx, &signature.to_string(), &self.markets())? {
+            let _ = self.sender.send(MarketEvent::Trade(trade));
+        }
+        Ok(())
+    }
+
+    async fn stream_market(&self, client: &RpcClient, key: &Pubkey) -> anyhow::Result<()> {
+        let pubsub = PubsubClient::new(&self.config.ws_url)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to connect websocket: {:?}", e))?;
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(self.config.commitment),
+            ..Default::default()
+        };
+        let (mut stream, unsubscribe) = pubsub
+            .account_subscribe(key, Some(config))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to subscribe to {}: {:?}", key, e))?;
+
+        // catch up on anything missed while disconnected
+        let response = client
+            .get_account_with_commitment(key, self.config.commitment)
+            .await?;
+        if let Some(account) = response.value {
+            let market = PhoenixMarket::new(*key, account.data)?;
+            self.apply_market(market, response.context.slot, now())?;
+        }
+
+        while let Some(response) = stream.next().await {
+            if let Some(account) = response.value.decode::<Account>() {
+                let market = PhoenixMarket::new(*key, account.data)?;
+                self.apply_market(market, response.context.slot, now())?;
+            }
+        }
+        unsubscribe().await;
+        Err(anyhow::anyhow!("Subscription to {} closed", key))
+    }
+
+    async fn stream_trades(&self, client: &RpcClient, key: &Pubkey) -> anyhow::Result<()> {
+        let pubsub = PubsubClient::new(&self.config.ws_url)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to connect websocket: {:?}", e))?;
+        let (mut stream, unsubscribe) = pubsub
+            .logs_subscribe(
+                RpcTransactionLogsFilter::Mentions(vec![key.to_string()]),
+                RpcTransactionLogsConfig {
+                    commitment: Some(self.config.commitment),
+                },
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to subscribe to logs of {}: {:?}", key, e))?;
+        while let Some(response) = stream.next().await {
+            if response.value.err.is_some() {
+                continue;
+            }
+            let signature = Signature::from_str(&response.value.signature)?;
+            if let Err(e) = self.apply_transaction(client, &signature).await {
+                self.report(key, e);
+            }
+        }
+        unsubscribe().await;
+        Err(anyhow::anyhow!("Log subscription to {} closed", key))
+    }
+
+    /// Streams the book of `key` forever, reconnecting after `reconnect_delay`.
+    pub async fn run_market(&self, key: Pubkey) {
+        let client =
+            RpcClient::new_with_commitment(self.config.rpc_url.clone(), self.config.commitment);
+        loop {
+            if let Err(e) = self.stream_market(&client, &key).await {
+                self.report(&key, e);
+            }
+            tokio::time::sleep(self.config.reconnect_delay).await;
+        }
+    }
+
+    /// Streams the trades of `key` forever, reconnecting after `reconnect_delay`.
+    pub async fn run_trades(&self, key: Pubkey) {
+        let client =
+            RpcClient::new_with_commitment(self.config.rpc_url.clone(), self.config.commitment);
+        loop {
+            if let Err(e) = self.stream_trades(&client, &key).await {
+                self.report(&key, e);
+            }
+            tokio::time::sleep(self.config.reconnect_delay).await;
+        }
+    }
+
+    /// Spawns [`Self::run_market`] and [`Self::run_trades`] for each market.
+    pub fn spawn(self: &Arc<Self>) -> Vec<JoinHandle<()>> {
+        let mut handles = vec![];
+        for key in self.config.markets.clone() {
+            let subscriber = self.clone();
+            handles.push(tokio::spawn(
+                async move { subscriber.run_market(key).await },
+            ));
+            let subscriber = self.clone();
+            handles.push(tokio::spawn(
+                async move { subscriber.run_trades(key).await },
+            ));
+        }
+        handles
+    }
+}
@@ -0,0 +1,316 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+
+use crate::helpers::{
+    decode_account, get_account, instruction_data, send_and_confirm_tx, SignerPair, SignerRef,
+};
+
+/// The Serum/Coral multisig program. Owners propose arbitrary instructions, which run with the
+/// multisig's signer PDA as a signer once `threshold` owners have approved.
+pub const MULTISIG_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("msigmtwzgXJHj2ext4XJjCDmpbcMuufFb5cHuwg6Xdt");
+
+/// The PDA that signs executed proposals. Make this a vault's manager to govern it by multisig.
+pub fn get_multisig_signer_address(multisig: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[multisig.as_ref()], &MULTISIG_PROGRAM_ID)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Multisig {
+    pub owners: Vec<Pubkey>,
+    pub threshold: u64,
+    /// Bump of the signer PDA
+    pub nonce: u8,
+    /// Bumped whenever the owners change, invalidating pending proposals
+    pub owner_set_seqno: u32,
+}
+
+impl Discriminator for Multisig {
+    const DISCRIMINATOR: [u8; 8] = [224, 116, 121, 186, 68, 161, 79, 236];
+}
+
+impl Multisig {
+    /// Account size for up to `max_owners` owners.
+    pub fn space(max_owners: usize) -> usize {
+        8 + 4 + 32 * max_owners + 8 + 1 + 4
+    }
+
+    pub fn owner_index(&self, owner: &Pubkey) -> Option<usize> {
+        self.owners.iter().position(|o| o == owner)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransactionAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl From<&AccountMeta> for TransactionAccount {
+    fn from(meta: &AccountMeta) -> Self {
+        Self {
+            pubkey: meta.pubkey,
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+        }
+    }
+}
+
+impl From<&TransactionAccount> for AccountMeta {
+    fn from(account: &TransactionAccount) -> Self {
+        Self {
+            pubkey: account.pubkey,
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        }
+    }
+}
+
+/// A proposed instruction and the owners who have approved it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MultisigTransaction {
+    pub multisig: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: Vec<TransactionAccount>,
+    pub data: Vec<u8>,
+    /// `signers[i]` is true once `owners[i]` has approved
+    pub signers: Vec<bool>,
+    pub did_execute: bool,
+    pub owner_set_seqno: u32,
+}
+
+impl Discriminator for MultisigTransaction {
+    const DISCRIMINATOR: [u8; 8] = [11, 24, 174, 129, 203, 117, 242, 23];
+}
+
+impl MultisigTransaction {
+    /// Account size for proposing `ix` to a multisig with `owners` owners.
+    pub fn space(ix: &Instruction, owners: usize) -> usize {
+        8 + 32 + 32 + 4 + 34 * ix.accounts.len() + 4 + ix.data.len() + 4 + owners + 1 + 4
+    }
+
+    /// The proposed instruction, as the multisig will invoke it.
+    pub fn instruction(&self) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: self.accounts.iter().map(AccountMeta::from).collect(),
+            data: self.data.clone(),
+        }
+    }
+
+    pub fn approvals(&self) -> usize {
+        self.signers.iter().filter(|s| **s).count()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CreateMultisigArgs {
+    pub owners: Vec<Pubkey>,
+    pub threshold: u64,
+    pub nonce: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CreateTransactionArgs {
+    pub pid: Pubkey,
+    pub accs: Vec<TransactionAccount>,
+    pub data: Vec<u8>,
+}
+
+/// Initializes `multisig`, which must already be allocated to the multisig program.
+pub fn create_multisig_ix(
+    multisig: &Pubkey,
+    owners: Vec<Pubkey>,
+    threshold: u64,
+) -> anyhow::Result<Instruction> {
+    let args = CreateMultisigArgs {
+        owners,
+        threshold,
+        nonce: get_multisig_signer_address(multisig).1,
+    };
+    Ok(Instruction {
+        program_id: MULTISIG_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(*multisig, true)],
+        data: instruction_data([148, 146, 240, 10, 226, 215, 167, 174], &args)?,
+    })
+}
+
+/// Stores `ix` in `transaction`, which must already be allocated to the multisig program.
+/// The proposer's approval is recorded with it.
+pub fn create_transaction_ix(
+    multisig: &Pubkey,
+    transaction: &Pubkey,
+    proposer: &Pubkey,
+    ix: &Instruction,
+) -> anyhow::Result<Instruction> {
+    let args = CreateTransactionArgs {
+        pid: ix.program_id,
+        accs: ix.accounts.iter().map(TransactionAccount::from).collect(),
+        data: ix.data.clone(),
+    };
+    Ok(Instruction {
+        program_id: MULTISIG_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*multisig, false),
+            AccountMeta::new(*transaction, true),
+            AccountMeta::new_readonly(*proposer, true),
+        ],
+        data: instruction_data([227, 193, 53, 239, 55, 126, 112, 105], &args)?,
+    })
+}
+
+pub fn approve_ix(multisig: &Pubkey, transaction: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: MULTISIG_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*multisig, false),
+            AccountMeta::new(*transaction, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: [69, 74, 217, 36, 115, 117, 97, 76].to_vec(),
+    }
+}
+
+/// Invokes the proposal. Its accounts follow as remaining accounts, with the signer PDA
+/// demoted to a non-signer since the program signs for it, then the target program.
+pub fn execute_transaction_ix(
+    multisig: &Pubkey,
+    transaction: &Pubkey,
+    proposal: &MultisigTransaction,
+) -> Instruction {
+    let signer = get_multisig_signer_address(multisig).0;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*multisig, false),
+        AccountMeta::new_readonly(signer, false),
+        AccountMeta::new(*transaction, false),
+    ];
+    accounts.extend(proposal.accounts.iter().map(|a| AccountMeta {
+        pubkey: a.pubkey,
+        is_signer: a.is_signer && a.pubkey != signer,
+        is_writable: a.is_writable,
+    }));
+    accounts.push(AccountMeta::new_readonly(proposal.program_id, false));
+    Instruction {
+        program_id: MULTISIG_PROGRAM_ID,
+        accounts,
+        data: [231, 173, 49, 91, 235, 24, 68, 19].to_vec(),
+    }
+}
+
+pub async fn fetch_multisig(client: &RpcClient, multisig: &Pubkey) -> anyhow::Result<Multisig> {
+    let account = get_account(client, multisig).await?;
+    decode_account::<Multisig>(&account.data)
+}
+
+pub async fn fetch_multisig_transaction(
+    client: &RpcClient,
+    transaction: &Pubkey,
+) -> anyhow::Result<MultisigTransaction> {
+    let account = get_account(client, transaction).await?;
+    decode_account::<MultisigTransaction>(&account.data)
+}
+
+/// Creates a multisig at `multisig` with room for exactly `owners`. Returns its signer PDA.
+pub async fn create_multisig<P: Signer + ?Sized, M: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &P,
+    multisig: &M,
+    owners: Vec<Pubkey>,
+    threshold: u64,
+) -> anyhow::Result<(Pubkey, Signature)> {
+    if threshold == 0 || threshold as usize > owners.len() {
+        return Err(anyhow::anyhow!(
+            "Threshold {} must be between 1 and the {} owners",
+            threshold,
+            owners.len()
+        ));
+    }
+    let space = Multisig::space(owners.len());
+    let lamports = client.get_minimum_balance_for_rent_exemption(space).await?;
+    let ixs = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &multisig.pubkey(),
+            lamports,
+            space as u64,
+            &MULTISIG_PROGRAM_ID,
+        ),
+        create_multisig_ix(&multisig.pubkey(), owners, threshold)?,
+    ];
+    let sig = send_and_confirm_tx(client, payer, &ixs, &SignerPair(payer, multisig)).await?;
+    Ok((get_multisig_signer_address(&multisig.pubkey()).0, sig))
+}
+
+/// Proposes `ix` to `multisig`, counting as the proposer's approval.
+/// Returns the new proposal account.
+pub async fn propose<S: Signer + ?Sized>(
+    client: &RpcClient,
+    proposer: &S,
+    multisig: &Pubkey,
+    ix: &Instruction,
+) -> anyhow::Result<(Pubkey, Signature)> {
+    let multisig_acct = fetch_multisig(client, multisig).await?;
+    if multisig_acct.owner_index(&proposer.pubkey()).is_none() {
+        return Err(anyhow::anyhow!(
+            "{} is not an owner of multisig {}",
+            proposer.pubkey(),
+            multisig
+        ));
+    }
+    let transaction = Keypair::new();
+    let space = MultisigTransaction::space(ix, multisig_acct.owners.len());
+    let lamports = client.get_minimum_balance_for_rent_exemption(space).await?;
+    let ixs = [
+        system_instruction::create_account(
+            &proposer.pubkey(),
+            &transaction.pubkey(),
+            lamports,
+            space as u64,
+            &MULTISIG_PROGRAM_ID,
+        ),
+        create_transaction_ix(multisig, &transaction.pubkey(), &proposer.pubkey(), ix)?,
+    ];
+    let sig =
+        send_and_confirm_tx(client, proposer, &ixs, &SignerPair(proposer, &transaction)).await?;
+    Ok((transaction.pubkey(), sig))
+}
+
+pub async fn approve<S: Signer + ?Sized>(
+    client: &RpcClient,
+    owner: &S,
+    multisig: &Pubkey,
+    transaction: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let ix = approve_ix(multisig, transaction, &owner.pubkey());
+    send_and_confirm_tx(client, owner, &[ix], &[&SignerRef(owner)]).await
+}
+
+/// Runs an approved proposal. Anyone can pay for this once the threshold is met.
+pub async fn execute<S: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &S,
+    multisig: &Pubkey,
+    transaction: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let multisig_acct = fetch_multisig(client, multisig).await?;
+    let proposal = fetch_multisig_transaction(client, transaction).await?;
+    if proposal.did_execute {
+        return Err(anyhow::anyhow!("Proposal {} already executed", transaction));
+    }
+    if (proposal.approvals() as u64) < multisig_acct.threshold {
+        return Err(anyhow::anyhow!(
+            "Proposal {} has {} of {} approvals",
+            transaction,
+            proposal.approvals(),
+            multisig_acct.threshold
+        ));
+    }
+    let ix = execute_transaction_ix(multisig, transaction, &proposal);
+    send_and_confirm_tx(client, payer, &[ix], &[&SignerRef(payer)]).await
+}
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::fund::Fund;
+use crate::helpers::serde_pubkey;
+use crate::pnl::{EquitySnapshot, VaultPnl, SECONDS_PER_YEAR};
+
+/// Vault equity and shares at a unix timestamp. Equity is in deposit token atoms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NavSnapshot {
+    #[serde(with = "serde_pubkey")]
+    pub vault: Pubkey,
+    pub ts: i64,
+    pub equity: u64,
+    pub total_shares: u128,
+}
+
+impl NavSnapshot {
+    /// Equity per share. Shares are minted 1:1 with the first deposit, so this starts at 1.0.
+    pub fn nav_per_share(&self) -> Option<f64> {
+        if self.total_shares == 0 {
+            return None;
+        }
+        Some(self.equity as f64 / self.total_shares as f64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareAction {
+    Deposit,
+    WithdrawRequest,
+    CancelWithdrawRequest,
+    Withdraw,
+    FeePayment,
+}
+
+/// An investor's `VaultDepositorRecord` or `InvestorRecord`, common to every venue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShareEvent {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub action: ShareAction,
+    /// Deposit token atoms
+    pub amount: u64,
+    pub vault_equity_before: u64,
+    pub total_shares_before: u128,
+    pub total_shares_after: u128,
+    /// The investor's own shares after the action
+    pub investor_shares_after: u128,
+}
+
+impl ShareEvent {
+    /// The vault right after the action. Deposits and withdrawals move equity by `amount`.
+    pub fn nav_snapshot(&self) -> NavSnapshot {
+        let equity = match self.action {
+            ShareAction::Deposit => self.vault_equity_before + self.amount,
+            ShareAction::Withdraw => self.vault_equity_before.saturating_sub(self.amount),
+            _ => self.vault_equity_before,
+        };
+        NavSnapshot {
+            vault: self.vault,
+            ts: self.ts,
+            equity,
+            total_shares: self.total_shares_after,
+        }
+    }
+}
+
+/// An investor's cash flow. Deposits are negative and withdrawals positive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CashFlow {
+    pub ts: i64,
+    pub amount: f64,
+}
+
+/// NAV history of each vault, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct NavHistory {
+    snapshots: HashMap<Pubkey, Vec<NavSnapshot>>,
+}
+
+impl NavHistory {
+    /// Inserts in time order, replacing a snapshot of the same vault at the same `ts`.
+    pub fn record(&mut self, snapshot: NavSnapshot) {
+        let series = self.snapshots.entry(snapshot.vault).or_default();
+        match series.binary_search_by_key(&snapshot.ts, |s| s.ts) {
+            Ok(i) => series[i] = snapshot,
+            Err(i) => series.insert(i, snapshot),
+        }
+    }
+
+    /// Records the vault state after each event.
+    pub fn record_events(&mut self, events: &[ShareEvent]) {
+        for event in events {
+            self.record(event.nav_snapshot());
+        }
+    }
+
+    /// Snapshots `fund` at the current cluster time.
+    pub async fn record_fund(&mut self, fund: &dyn Fund) -> anyhow::Result<NavSnapshot> {
+        let snapshot = fund.nav().await?;
+        self.record(snapshot);
+        Ok(snapshot)
+    }
+
+    pub fn series(&self, vault: &Pubkey) -> &[NavSnapshot] {
+        self.snapshots.get(vault).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// NAV per share of the latest snapshot at or before `ts`.
+    pub fn nav_at(&self, vault: &Pubkey, ts: i64) -> Option<f64> {
+        self.series(vault)
+            .iter()
+            .rev()
+            .find(|s| s.ts <= ts)?
+            .nav_per_share()
+    }
+
+    /// Growth of one share from `from_ts` to `to_ts`. Deposits and withdrawals mint and burn shares
+    /// at NAV, so this is the time weighted return.
+    pub fn time_weighted_return(&self, vault: &Pubkey, from_ts: i64, to_ts: i64) -> Option<f64> {
+        let start = self.nav_at(vault, from_ts)?;
+        let end = self.nav_at(vault, to_ts)?;
+        if start <= 0.0 {
+            return None;
+        }
+        Some(end / start - 1.0)
+    }
+
+    pub fn annualized_time_weighted_return(
+        &self,
+        vault: &Pubkey,
+        from_ts: i64,
+        to_ts: i64,
+    ) -> Option<f64> {
+        let years = (to_ts - from_ts) as f64 / SECONDS_PER_YEAR;
+        if years <= 0.0 {
+            return None;
+        }
+        let twr = self.time_weighted_return(vault, from_ts, to_ts)?;
+        Some((1.0 + twr).powf(1.0 / years) - 1.0)
+    }
+
+    /// NAV per share as an equity curve, so [`VaultPnl`] risk metrics ignore deposits and withdrawals.
+    pub fn nav_pnl(&self, vault: &Pubkey) -> VaultPnl {
+        VaultPnl::from_equity_snapshots(
+            self.series(vault)
+                .iter()
+                .filter_map(|s| {
+                    Some(EquitySnapshot {
+                        ts: s.ts,
+                        equity: s.nav_per_share()?,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Annualised money weighted return of `authority` in `vault`, valuing their shares at `ts`.
+    pub fn money_weighted_return(
+        &self,
+        events: &[ShareEvent],
+        vault: &Pubkey,
+        authority: &Pubkey,
+        ts: i64,
+    ) -> Option<f64> {
+        let mut events: Vec<&ShareEvent> = events
+            .iter()
+            .filter(|e| e.vault == *vault && e.authority == *authority && e.ts <= ts)
+            .collect();
+        events.sort_by_key(|e| e.ts);
+        let shares = events.last()?.investor_shares_after;
+        let final_value = shares as f64 * self.nav_at(vault, ts)?;
+        let flows = investor_cash_flows(events.into_iter());
+        money_weighted_return(&flows, final_value, ts)
+    }
+}
+
+/// NAV snapshots of every vault in a JSON lines file, appended one per line so history survives
+/// restarts.
+#[derive(Debug, Clone)]
+pub struct NavStore {
+    path: PathBuf,
+}
+
+impl NavStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every snapshot appended so far, grouped by vault. A missing file is an empty history.
+    pub fn load(&self) -> anyhow::Result<NavHistory> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(NavHistory::default()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut history = NavHistory::default();
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let snapshot = serde_json::from_str(&line)
+                .map_err(|e| anyhow::anyhow!("{}:{}: {}", self.path.display(), i + 1, e))?;
+            history.record(snapshot);
+        }
+        Ok(history)
+    }
+
+    pub fn append(&self, snapshot: &NavSnapshot) -> anyhow::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(snapshot)?)?;
+        Ok(())
+    }
+
+    /// Snapshots each fund into `history` and appends it here. A fund that fails is returned with
+    /// its error and skipped, so one unreachable vault doesn't stop the others being recorded.
+    pub async fn record_funds(
+        &self,
+        history: &mut NavHistory,
+        funds: &[Box<dyn Fund + '_>],
+    ) -> Vec<(Pubkey, anyhow::Result<NavSnapshot>)> {
+        let mut results = Vec::with_capacity(funds.len());
+        for fund in funds {
+            let result = match history.record_fund(fund.as_ref()).await {
+                Ok(snapshot) => self.append(&snapshot).map(|_| snapshot),
+                Err(e) => Err(e),
+            };
+            results.push((fund.vault(), result));
+        }
+        results
+    }
+}
+
+/// Deposits and withdrawals in `events`, in deposit token atoms.
+pub fn investor_cash_flows<'a>(events: impl Iterator<Item = &'a ShareEvent>) -> Vec<CashFlow> {
+    events
+        .filter_map(|e| match e.action {
+            ShareAction::Deposit => Some(CashFlow {
+                ts: e.ts,
+                amount: -(e.amount as f64),
+            }),
+            ShareAction::Withdraw => Some(CashFlow {
+                ts: e.ts,
+                amount: e.amount as f64,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Annualised rate that discounts `flows` plus `final_value` received at `final_ts` to zero.
+/// `None` if the flows never change sign or span no time.
+pub fn money_weighted_return(flows: &[CashFlow], final_value: f64, final_ts: i64) -> Option<f64> {
+    let start = flows.iter().map(|f| f.ts).min()?;
+    if final_ts <= start {
+        return None;
+    }
+    let npv = |rate: f64| {
+        let discount = |ts: i64| (1.0 + rate).powf(-((ts - start) as f64 / SECONDS_PER_YEAR));
+        flows.iter().map(|f| f.amount * discount(f.ts)).sum::<f64>()
+            + final_value * discount(final_ts)
+    };
+    let (mut low, mut high) = (-0.9999, 1_000.0);
+    let (npv_low, npv_high) = (npv(low), npv(high));
+    if npv_low.signum() == npv_high.signum() {
+        return None;
+    }
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        if npv(mid).signum() == npv_low.signum() {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some((low + high) / 2.0)
+}
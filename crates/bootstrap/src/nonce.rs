@@ -0,0 +1,73 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::nonce_utils::nonblocking::{data_from_account, get_account_with_commitment};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::nonce::state::State;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+
+use crate::helpers::{send_and_confirm_tx, SignerPair};
+
+/// The current durable nonce and the key allowed to advance it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceInfo {
+    pub nonce: Pubkey,
+    pub authority: Pubkey,
+    pub blockhash: Hash,
+}
+
+pub async fn fetch_nonce(client: &RpcClient, nonce: &Pubkey) -> anyhow::Result<NonceInfo> {
+    let account = get_account_with_commitment(client, nonce, CommitmentConfig::processed())
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch nonce account {}: {:?}", nonce, e))?;
+    let data = data_from_account(&account)
+        .map_err(|e| anyhow::anyhow!("Invalid nonce account {}: {:?}", nonce, e))?;
+    Ok(NonceInfo {
+        nonce: *nonce,
+        authority: data.authority,
+        blockhash: data.blockhash(),
+    })
+}
+
+/// Creates and initializes `nonce` as a rent exempt durable nonce account owned by `authority`.
+pub async fn create_nonce_account<P: Signer + ?Sized, N: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &P,
+    nonce: &N,
+    authority: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let lamports = client
+        .get_minimum_balance_for_rent_exemption(State::size())
+        .await?;
+    let ixs = system_instruction::create_nonce_account(
+        &payer.pubkey(),
+        &nonce.pubkey(),
+        authority,
+        lamports,
+    );
+    send_and_confirm_tx(client, payer, &ixs, &SignerPair(payer, nonce)).await
+}
+
+/// Moves the nonce on, invalidating anything signed against the previous value.
+pub async fn advance_nonce_account<P: Signer + ?Sized, A: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &P,
+    authority: &A,
+    nonce: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let ix = system_instruction::advance_nonce_account(nonce, &authority.pubkey());
+    send_and_confirm_tx(client, payer, &[ix], &SignerPair(payer, authority)).await
+}
+
+pub async fn authorize_nonce_account<P: Signer + ?Sized, A: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &P,
+    authority: &A,
+    nonce: &Pubkey,
+    new_authority: &Pubkey,
+) -> anyhow::Result<Signature> {
+    let ix = system_instruction::authorize_nonce_account(nonce, &authority.pubkey(), new_authority);
+    send_and_confirm_tx(client, payer, &[ix], &SignerPair(payer, authority)).await
+}
@@ -0,0 +1,229 @@
+use std::fmt;
+use std::str::FromStr;
+
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_program::instruction::Instruction;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::hash::Hash;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+
+use crate::nonce::{fetch_nonce, NonceInfo};
+
+/// A signature made away from the transaction, written `PUBKEY=SIGNATURE` as the Solana CLI's
+/// `--signer` flag expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetachedSignature {
+    pub signer: Pubkey,
+    pub signature: Signature,
+}
+
+impl fmt::Display for DetachedSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}={}", self.signer, self.signature)
+    }
+}
+
+impl FromStr for DetachedSignature {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (signer, signature) = s
+            .trim()
+            .split_once('=')
+            .ok_or(anyhow::anyhow!("Expected PUBKEY=SIGNATURE, got {}", s))?;
+        Ok(DetachedSignature {
+            signer: Pubkey::from_str(signer)
+                .map_err(|e| anyhow::anyhow!("Invalid signer {}: {:?}", signer, e))?,
+            signature: Signature::from_str(signature)
+                .map_err(|e| anyhow::anyhow!("Invalid signature {}: {:?}", signature, e))?,
+        })
+    }
+}
+
+impl Serialize for DetachedSignature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for DetachedSignature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        DetachedSignature::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A transaction built against a durable nonce, so it stays valid while it travels to cold
+/// signers and back. Exported as JSON with the base64 message every signer signs, which is
+/// also what the explorer's inspector and most hardware wallets accept.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineTransaction {
+    pub message: String,
+    #[serde(default)]
+    pub signatures: Vec<DetachedSignature>,
+}
+
+impl OfflineTransaction {
+    /// Prepends the nonce advance to `ixs` and uses the nonce in place of a recent blockhash.
+    pub fn new(ixs: &[Instruction], payer: &Pubkey, nonce: &NonceInfo) -> Self {
+        let mut message =
+            Message::new_with_nonce(ixs.to_vec(), Some(payer), &nonce.nonce, &nonce.authority);
+        message.recent_blockhash = nonce.blockhash;
+        Self::from_message(&message)
+    }
+
+    /// [`OfflineTransaction::new`] against the nonce's current value.
+    pub async fn build(
+        client: &RpcClient,
+        ixs: &[Instruction],
+        payer: &Pubkey,
+        nonce: &Pubkey,
+    ) -> anyhow::Result<Self> {
+        let nonce = fetch_nonce(client, nonce).await?;
+        Ok(Self::new(ixs, payer, &nonce))
+    }
+
+    pub fn from_message(message: &Message) -> Self {
+        Self {
+            message: base64::engine::general_purpose::STANDARD.encode(message.serialize()),
+            signatures: vec![],
+        }
+    }
+
+    pub fn load(path: &std::path::Path) -> anyhow::Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {:?}", path.display(), e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| anyhow::anyhow!("Invalid offline transaction {}: {:?}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {:?}", path.display(), e))
+    }
+
+    /// The exact bytes signers sign.
+    pub fn message_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        base64::engine::general_purpose::STANDARD
+            .decode(&self.message)
+            .map_err(|e| anyhow::anyhow!("Invalid message encoding: {:?}", e))
+    }
+
+    pub fn decode_message(&self) -> anyhow::Result<Message> {
+        bincode::deserialize(&self.message_bytes()?)
+            .map_err(|e| anyhow::anyhow!("Invalid message: {:?}", e))
+    }
+
+    /// The nonce value the message was built against.
+    pub fn blockhash(&self) -> anyhow::Result<Hash> {
+        Ok(self.decode_message()?.recent_blockhash)
+    }
+
+    /// Every key that has to sign, the fee payer first.
+    pub fn signers(&self) -> anyhow::Result<Vec<Pubkey>> {
+        Ok(self
+            .decode_message()?
+            .signer_keys()
+            .into_iter()
+            .copied()
+            .collect())
+    }
+
+    pub fn missing_signers(&self) -> anyhow::Result<Vec<Pubkey>> {
+        Ok(self
+            .signers()?
+            .into_iter()
+            .filter(|key| !self.signatures.iter().any(|s| s.signer == *key))
+            .collect())
+    }
+
+    /// Signs with a key that is at hand, returning the signature to hand back.
+    pub fn sign<S: Signer + ?Sized>(&mut self, signer: &S) -> anyhow::Result<DetachedSignature> {
+        let signature = signer
+            .try_sign_message(&self.message_bytes()?)
+            .map_err(|e| anyhow::anyhow!("Failed to sign message: {:?}", e))?;
+        let detached = DetachedSignature {
+            signer: signer.pubkey(),
+            signature,
+        };
+        self.add_signature(detached)?;
+        Ok(detached)
+    }
+
+    /// Accepts a signature made elsewhere, after checking it is a required signer's and
+    /// verifies against the message.
+    pub fn add_signature(&mut self, detached: DetachedSignature) -> anyhow::Result<()> {
+        if !self.signers()?.contains(&detached.signer) {
+            return Err(anyhow::anyhow!(
+                "{} is not a signer of this transaction",
+                detached.signer
+            ));
+        }
+        if !detached
+            .signature
+            .verify(detached.signer.as_ref(), &self.message_bytes()?)
+        {
+            return Err(anyhow::anyhow!(
+                "Signature from {} does not match the message",
+                detached.signer
+            ));
+        }
+        self.signatures.retain(|s| s.signer != detached.signer);
+        self.signatures.push(detached);
+        Ok(())
+    }
+
+    /// The fully signed transaction, once every signer has signed.
+    pub fn assemble(&self) -> anyhow::Result<Transaction> {
+        let missing = self.missing_signers()?;
+        if !missing.is_empty() {
+            return Err(anyhow::anyhow!("Missing signatures from {:?}", missing));
+        }
+        let mut tx = Transaction::new_unsigned(self.decode_message()?);
+        let signatures: Vec<(Pubkey, Signature)> = self
+            .signatures
+            .iter()
+            .map(|s| (s.signer, s.signature))
+            .collect();
+        tx.replace_signatures(&signatures)
+            .map_err(|e| anyhow::anyhow!("Failed to assemble transaction: {:?}", e))?;
+        tx.verify()
+            .map_err(|e| anyhow::anyhow!("Assembled transaction does not verify: {:?}", e))?;
+        Ok(tx)
+    }
+
+    /// Assembles and sends the transaction, then waits for it to land. The nonce stands in
+    /// for the blockhash, so this does not expire the way [`crate::helpers::send_and_confirm_tx`]
+    /// transactions do. Preflight stays on so a spent nonce fails here rather than timing out.
+    pub async fn submit(&self, client: &RpcClient) -> anyhow::Result<Signature> {
+        let tx = self.assemble()?;
+        let sig = client
+            .send_transaction_with_config(
+                &tx,
+                RpcSendTransactionConfig {
+                    preflight_commitment: Some(CommitmentLevel::Processed),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Error sending transaction: {:#?}", e))?;
+        client
+            .poll_for_signature_with_commitment(&sig, CommitmentConfig::processed())
+            .await?;
+        if let Some(Err(e)) = client
+            .get_signature_status_with_commitment(&sig, CommitmentConfig::processed())
+            .await?
+        {
+            return Err(anyhow::anyhow!("Transaction {} failed: {:?}", sig, e));
+        }
+        Ok(sig)
+    }
+}
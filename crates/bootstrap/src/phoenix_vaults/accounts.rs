@@ -0,0 +1,214 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::fund::{FundSummary, Venue};
+use crate::vault_update::VaultSettings;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WithdrawRequest {
+    /// request shares of vault withdraw
+    pub shares: u128,
+    /// requested value in USDC of shares for withdraw
+    pub value: u64,
+    /// request ts of vault withdraw
+    pub ts: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MarketPosition {
+    pub market: Pubkey,
+    pub quote_lots_locked: u64,
+    pub quote_lots_free: u64,
+    pub base_lots_locked: u64,
+    pub base_lots_free: u64,
+}
+
+impl MarketPosition {
+    pub fn is_available(&self) -> bool {
+        self.quote_lots_locked == 0
+            && self.quote_lots_free == 0
+            && self.base_lots_locked == 0
+            && self.base_lots_free == 0
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Vault {
+    /// The name of the vault. Vault pubkey is derived from this name.
+    pub name: [u8; 32],
+    /// The vault's pubkey. It is a PDA also used as the authority token accounts
+    pub pubkey: Pubkey,
+    /// The manager of the vault who has ability to update vault config,
+    /// and earns a profit share or management fee.
+    pub manager: Pubkey,
+    /// The Phoenix USDC mint.
+    pub usdc_mint: Pubkey,
+    /// The Phoenix (wrapped) SOL mint.
+    pub sol_mint: Pubkey,
+    /// The USDC token account investor transfer with,
+    /// and the vault transfer to Phoenix markets with.
+    pub usdc_token_account: Pubkey,
+    /// The SOL token account investor transfer with,
+    /// and the vault transfer to Phoenix markets with.
+    pub sol_token_account: Pubkey,
+    /// The delegate (investor) handling liquidation for an investor to withdraw their funds.
+    pub liquidator: Pubkey,
+    /// The delegate is the "portfolio manager", "trader", or "bot" that trades the vault assets.
+    /// This is the manager by default.
+    pub delegate: Pubkey,
+    /// The sum of all shares held by the investors
+    pub investor_shares: u128,
+    /// The sum of all shares: investor deposits, manager deposits, manager profit/fee, and protocol profit/fee.
+    pub total_shares: u128,
+    /// Last fee update unix timestamp
+    pub last_fee_update_ts: i64,
+    /// When the liquidation starts
+    pub liquidation_start_ts: i64,
+    /// The period (in seconds) that an investor must wait after requesting a withdrawal to transfer funds.
+    /// This is only updatable to lesser values.
+    pub redeem_period: i64,
+    /// The sum of all outstanding withdraw requests
+    pub total_withdraw_requested: u64,
+    /// Max token capacity, once hit/passed vault will reject new deposits.
+    /// This is only updatable to lesser values.
+    pub max_tokens: u64,
+    /// The annual fee charged on deposits by the manager.
+    /// This is only updatable to lesser values.
+    pub management_fee: i64,
+    /// Timestamp vault initialized
+    pub init_ts: i64,
+    /// The net deposits for the vault
+    pub net_deposits: i64,
+    /// The net deposits for the manager
+    pub manager_net_deposits: i64,
+    /// Total deposits
+    pub total_deposits: u64,
+    /// Total withdraws
+    pub total_withdraws: u64,
+    /// Total deposits for the manager
+    pub manager_total_deposits: u64,
+    /// Total withdraws for the manager
+    pub manager_total_withdraws: u64,
+    /// Total management fee accrued by the manager
+    pub manager_total_fee: i64,
+    /// Total profit share accrued by the manager
+    pub manager_total_profit_share: u64,
+    /// The minimum deposit amount.
+    /// This is only updatable to lesser values.
+    pub min_deposit_amount: u64,
+    pub last_manager_withdraw_request: WithdrawRequest,
+    /// The base 10 exponent of the shares
+    pub shares_base: u32,
+    /// Percentage the manager charges on all profits realized by depositors (multiplied by PERCENTAGE_PRECISION).
+    /// This is only updatable to lesser values.
+    pub profit_share: u32,
+    /// Vault manager only collect incentive fees during periods when returns are higher than this amount.
+    pub hurdle_rate: u32,
+    /// Percentage the protocol charges on all profits realized by depositors: PERCENTAGE_PRECISION
+    pub protocol_profit_share: u32,
+    /// The protocol, company, or entity that services the product using this vault.
+    pub protocol: Pubkey,
+    /// The shares from profit share and annual fee unclaimed by the protocol.
+    pub protocol_profit_and_fee_shares: u128,
+    /// The annual fee charged on deposits by the protocol.
+    pub protocol_fee: u64,
+    /// Total withdraws for the protocol
+    pub protocol_total_withdraws: u64,
+    /// Total fee charged by the protocol (annual management fee + profit share).
+    pub protocol_total_fee: u64,
+    /// Total profit share charged by the protocol
+    pub protocol_total_profit_share: u64,
+    pub last_protocol_withdraw_request: WithdrawRequest,
+    pub positions: [MarketPosition; 8],
+    /// Whether anyone can be an investor
+    pub permissioned: bool,
+    /// The bump for the vault PDA
+    pub bump: u8,
+    pub padding: [u8; 6],
+}
+
+impl Discriminator for Vault {
+    const DISCRIMINATOR: [u8; 8] = [211, 8, 232, 43, 2, 152, 117, 119];
+}
+
+impl Vault {
+    /// `deposit_decimals` are the decimals of the mint the vault takes deposits in.
+    pub fn settings(&self, deposit_decimals: u8) -> VaultSettings {
+        VaultSettings {
+            redeem_period: self.redeem_period,
+            max_tokens: self.max_tokens,
+            management_fee: self.management_fee,
+            min_deposit_amount: self.min_deposit_amount,
+            profit_share: self.profit_share,
+            hurdle_rate: self.hurdle_rate,
+            permissioned: self.permissioned,
+            delegate: self.delegate,
+            total_withdraw_requested: self.total_withdraw_requested,
+            deposit_decimals,
+        }
+    }
+
+    pub fn fund_summary(&self) -> FundSummary {
+        FundSummary {
+            vault: self.pubkey,
+            manager: self.manager,
+            venue: Venue::Phoenix,
+            name: self.name,
+            init_ts: self.init_ts,
+            manager_total_profit_share: self.manager_total_profit_share,
+            protocol_total_profit_share: self.protocol_total_profit_share,
+        }
+    }
+
+    pub fn position(&self, market: &Pubkey) -> Option<&MarketPosition> {
+        self.positions.iter().find(|p| p.market == *market)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Investor {
+    /// The vault deposited into
+    pub vault: Pubkey,
+    /// The vault depositor account's pubkey. It is a pda of vault and authority
+    pub pubkey: Pubkey,
+    /// The authority is the address w permission to deposit/withdraw
+    pub authority: Pubkey,
+    /// share of vault owned by this depositor. vault_shares / vault.total_shares is depositor's ownership of vault_equity
+    pub vault_shares: u128,
+    /// last withdraw request
+    pub last_withdraw_request: WithdrawRequest,
+    /// creation ts of vault depositor
+    pub last_valid_ts: i64,
+    /// lifetime net deposits of vault depositor for the vault
+    pub net_deposits: i64,
+    /// lifetime total deposits
+    pub total_deposits: u64,
+    /// lifetime total withdraws
+    pub total_withdraws: u64,
+    /// the token amount of gains the vault depositor has paid performance fees on
+    pub cumulative_profit_share_amount: i64,
+    /// the token amount profit share paid out to the manager and protocol
+    pub profit_share_fee_paid: u64,
+    /// the exponent for vault_shares decimal places
+    pub vault_shares_base: u32,
+    pub padding1: u32,
+    pub padding: [u64; 8],
+}
+
+impl Discriminator for Investor {
+    const DISCRIMINATOR: [u8; 8] = [174, 129, 17, 83, 36, 116, 26, 196];
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct MarketRegistry {
+    /// Authority over this account. This is a program admin-level keypair.
+    pub authority: Pubkey,
+    /// Phoenix SOL/USDC market
+    pub sol_usdc_market: Pubkey,
+    pub usdc_mint: Pubkey,
+    pub sol_mint: Pubkey,
+}
+
+impl Discriminator for MarketRegistry {
+    const DISCRIMINATOR: [u8; 8] = [200, 118, 217, 126, 179, 169, 172, 104];
+}
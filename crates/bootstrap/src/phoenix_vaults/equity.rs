@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::helpers::get_multiple_accounts;
+use crate::market::PhoenixMarket;
+use crate::token::unpack_token_account;
+
+use super::*;
+
+/// USDC and SOL held by the vault plus every open `MarketPosition` marked at the best bid, in USDC atoms.
+/// `markets` must contain every market the vault has a position in, and the SOL/USDC market to price SOL.
+pub fn vault_equity(
+    vault: &Vault,
+    usdc_balance: u64,
+    sol_balance: u64,
+    markets: &HashMap<Pubkey, PhoenixMarket>,
+) -> anyhow::Result<u64> {
+    Ok(value_vault(
+        vault,
+        usdc_balance,
+        sol_balance,
+        markets,
+        &BestBid,
+        DEFAULT_MAX_SLIPPAGE,
+    )?
+    .total_atoms())
+}
+
+/// Loads `markets` with one batched `getMultipleAccounts`.
+pub async fn fetch_markets(
+    client: &RpcClient,
+    markets: &[Pubkey],
+) -> anyhow::Result<HashMap<Pubkey, PhoenixMarket>> {
+    let accounts = get_multiple_accounts(client, markets).await?;
+    let mut loaded = HashMap::new();
+    for (key, account) in markets.iter().zip(accounts) {
+        let account = account.ok_or(anyhow::anyhow!("Market not found: {:?}", key))?;
+        loaded.insert(*key, PhoenixMarket::new(*key, account.data)?);
+    }
+    Ok(loaded)
+}
+
+fn token_amount(account: Option<&Account>) -> anyhow::Result<u64> {
+    match account {
+        Some(account) => Ok(unpack_token_account(&account.data)?.amount),
+        None => Ok(0),
+    }
+}
+
+/// Balances of `token_accounts` in atoms, zero for any that don't exist yet.
+pub async fn fetch_token_balances(
+    client: &RpcClient,
+    token_accounts: &[Pubkey],
+) -> anyhow::Result<Vec<u64>> {
+    get_multiple_accounts(client, token_accounts)
+        .await?
+        .iter()
+        .map(|account| token_amount(account.as_ref()))
+        .collect()
+}
+
+/// Marks the vault against `price_source`, loading its markets, the registry's SOL/USDC market
+/// and its USDC and SOL balances in one batch.
+pub async fn fetch_vault_valuation(
+    client: &RpcClient,
+    vault: &Vault,
+    price_source: &dyn PriceSource,
+    max_slippage: f64,
+) -> anyhow::Result<VaultValuation> {
+    let registry = fetch_market_registry(client).await?;
+    let mut keys = vec![vault.usdc_token_account, vault.sol_token_account];
+    let mut market_keys = vec![registry.sol_usdc_market];
+    for position in vault.positions.iter().filter(|p| !p.is_available()) {
+        if !market_keys.contains(&position.market) {
+            market_keys.push(position.market);
+        }
+    }
+    keys.extend(market_keys);
+    let accounts = get_multiple_accounts(client, &keys).await?;
+    let usdc_balance = token_amount(accounts[0].as_ref())?;
+    let sol_balance = token_amount(accounts[1].as_ref())?;
+    let mut markets = HashMap::new();
+    for (key, account) in keys[2..].iter().zip(&accounts[2..]) {
+        let account = account
+            .as_ref()
+            .ok_or(anyhow::anyhow!("Market not found: {:?}", key))?;
+        markets.insert(*key, PhoenixMarket::new(*key, account.data.clone())?);
+    }
+    value_vault(
+        vault,
+        usdc_balance,
+        sol_balance,
+        &markets,
+        price_source,
+        max_slippage,
+    )
+}
+
+pub async fn fetch_vault_equity(client: &RpcClient, vault: &Vault) -> anyhow::Result<u64> {
+    Ok(
+        fetch_vault_valuation(client, vault, &BestBid, DEFAULT_MAX_SLIPPAGE)
+            .await?
+            .total_atoms(),
+    )
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::nav::{ShareAction, ShareEvent};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VaultRecord {
+    pub ts: i64,
+    pub spot_market_index: u16,
+    pub vault_equity_before: u64,
+}
+
+impl Discriminator for VaultRecord {
+    const DISCRIMINATOR: [u8; 8] = [38, 129, 21, 139, 164, 170, 16, 134];
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvestorAction {
+    Deposit,
+    WithdrawRequest,
+    CancelWithdrawRequest,
+    Withdraw,
+    FeePayment,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvestorRecord {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub depositor_authority: Pubkey,
+    pub action: InvestorAction,
+    pub amount: u64,
+    pub usdc_mint: Pubkey,
+    pub sol_mint: Pubkey,
+    pub vault_shares_before: u128,
+    pub vault_shares_after: u128,
+    pub vault_equity_before: u64,
+    pub user_vault_shares_before: u128,
+    pub total_vault_shares_before: u128,
+    pub protocol_shares_before: u128,
+    pub user_vault_shares_after: u128,
+    pub total_vault_shares_after: u128,
+    pub protocol_shares_after: u128,
+    pub protocol_profit_share: u64,
+    pub protocol_fee: i64,
+    pub protocol_fee_shares: i64,
+    pub manager_profit_share: u64,
+    pub management_fee: i64,
+    pub management_fee_shares: i64,
+}
+
+impl Discriminator for InvestorRecord {
+    const DISCRIMINATOR: [u8; 8] = [194, 184, 92, 14, 230, 69, 201, 184];
+}
+
+impl From<InvestorAction> for ShareAction {
+    fn from(action: InvestorAction) -> Self {
+        match action {
+            InvestorAction::Deposit => ShareAction::Deposit,
+            InvestorAction::WithdrawRequest => ShareAction::WithdrawRequest,
+            InvestorAction::CancelWithdrawRequest => ShareAction::CancelWithdrawRequest,
+            InvestorAction::Withdraw => ShareAction::Withdraw,
+            InvestorAction::FeePayment => ShareAction::FeePayment,
+        }
+    }
+}
+
+impl From<InvestorRecord> for ShareEvent {
+    fn from(record: InvestorRecord) -> Self {
+        Self {
+            ts: record.ts,
+            vault: record.vault,
+            authority: record.depositor_authority,
+            action: record.action.into(),
+            amount: record.amount,
+            vault_equity_before: record.vault_equity_before,
+            total_shares_before: record.total_vault_shares_before,
+            total_shares_after: record.total_vault_shares_after,
+            investor_shares_after: record.vault_shares_after,
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use async_trait::async_trait;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+use crate::amount::TokenAmount;
+use crate::fund::{investors_by_vault, DepositPrice, Fund, FundOverview, InvestorPosition, Venue};
+use crate::helpers::{get_anchor_accounts, get_clock};
+use crate::nav::NavSnapshot;
+use crate::vault_update::{UpdateVaultConfig, VaultUpdatePlan};
+
+use super::*;
+
+impl From<(Pubkey, Investor)> for InvestorPosition {
+    fn from((pubkey, investor): (Pubkey, Investor)) -> Self {
+        Self {
+            pubkey,
+            authority: investor.authority,
+            shares: investor.vault_shares,
+            net_deposits: investor.net_deposits,
+            cumulative_profit_share_amount: investor.cumulative_profit_share_amount,
+            pending_withdraw_shares: investor.last_withdraw_request.shares,
+            pending_withdraw_value: investor.last_withdraw_request.value,
+            pending_withdraw_ts: investor.last_withdraw_request.ts,
+        }
+    }
+}
+
+/// A phoenix_vaults vault as a [`Fund`].
+pub struct PhoenixFund<'a> {
+    pub client: &'a RpcClient,
+    pub vault: Pubkey,
+    /// Every market in the `MarketRegistry`, passed as remaining accounts.
+    pub markets: Vec<Pubkey>,
+}
+
+impl<'a> PhoenixFund<'a> {
+    pub fn new(client: &'a RpcClient, vault: Pubkey, markets: Vec<Pubkey>) -> Self {
+        Self {
+            client,
+            vault,
+            markets,
+        }
+    }
+}
+
+#[async_trait]
+impl<'a> Fund for PhoenixFund<'a> {
+    fn venue(&self) -> Venue {
+        Venue::Phoenix
+    }
+
+    fn vault(&self) -> Pubkey {
+        self.vault
+    }
+
+    async fn equity(&self) -> anyhow::Result<u64> {
+        let vault = fetch_vault(self.client, &self.vault).await?;
+        fetch_vault_equity(self.client, &vault).await
+    }
+
+    async fn investors(&self) -> anyhow::Result<Vec<InvestorPosition>> {
+        let investors = fetch_investors(self.client, &self.vault).await?;
+        Ok(investors.into_iter().map(InvestorPosition::from).collect())
+    }
+
+    async fn deposit(
+        &self,
+        investor: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature> {
+        investor_deposit(self.client, investor, &self.vault, amount, &self.markets).await
+    }
+
+    async fn request_withdraw(
+        &self,
+        investor: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature> {
+        investor_request_withdraw(self.client, investor, &self.vault, amount, &self.markets).await
+    }
+
+    async fn cancel_withdraw_request(
+        &self,
+        investor: &(dyn Signer + Sync),
+    ) -> anyhow::Result<Signature> {
+        investor_cancel_withdraw_request(self.client, investor, &self.vault, &self.markets).await
+    }
+
+    async fn withdraw(&self, investor: &(dyn Signer + Sync)) -> anyhow::Result<Signature> {
+        investor_withdraw(self.client, investor, &self.vault, &self.markets).await
+    }
+
+    async fn manager_deposit(
+        &self,
+        manager: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature> {
+        manager_deposit(self.client, manager, &self.vault, amount, &self.markets).await
+    }
+
+    async fn manager_request_withdraw(
+        &self,
+        manager: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature> {
+        manager_request_withdraw(self.client, manager, &self.vault, amount, &self.markets).await
+    }
+
+    async fn manager_cancel_withdraw_request(
+        &self,
+        manager: &(dyn Signer + Sync),
+    ) -> anyhow::Result<Signature> {
+        manager_cancel_withdraw_request(self.client, manager, &self.vault, &self.markets).await
+    }
+
+    async fn manager_withdraw(&self, manager: &(dyn Signer + Sync)) -> anyhow::Result<Signature> {
+        manager_withdraw(self.client, manager, &self.vault, &self.markets).await
+    }
+
+    async fn protocol_request_withdraw(
+        &self,
+        protocol: &(dyn Signer + Sync),
+        amount: TokenAmount,
+    ) -> anyhow::Result<Signature> {
+        protocol_request_withdraw(self.client, protocol, &self.vault, amount, &self.markets).await
+    }
+
+    async fn protocol_cancel_withdraw_request(
+        &self,
+        protocol: &(dyn Signer + Sync),
+    ) -> anyhow::Result<Signature> {
+        protocol_cancel_withdraw_request(self.client, protocol, &self.vault, &self.markets).await
+    }
+
+    async fn protocol_withdraw(&self, protocol: &(dyn Signer + Sync)) -> anyhow::Result<Signature> {
+        protocol_withdraw(self.client, protocol, &self.vault, &self.markets).await
+    }
+
+    async fn plan_update(&self, config: &UpdateVaultConfig) -> anyhow::Result<VaultUpdatePlan> {
+        plan_update_vault(self.client, &self.vault, config).await
+    }
+
+    async fn send_update(
+        &self,
+        manager: &(dyn Signer + Sync),
+        plan: &VaultUpdatePlan,
+    ) -> anyhow::Result<Option<Signature>> {
+        send_update(self.client, manager, &self.vault, plan).await
+    }
+
+    async fn overview(&self) -> anyhow::Result<FundOverview> {
+        let vault = fetch_vault(self.client, &self.vault).await?;
+        let investors = self.investors().await?;
+        let equity = fetch_vault_equity(self.client, &vault).await?;
+        Ok(FundOverview::compute(
+            &vault.fund_summary(),
+            &investors,
+            equity,
+            DepositPrice::USDC,
+        ))
+    }
+
+    async fn nav(&self) -> anyhow::Result<NavSnapshot> {
+        let vault = fetch_vault(self.client, &self.vault).await?;
+        let equity = fetch_vault_equity(self.client, &vault).await?;
+        let clock = get_clock(self.client).await?;
+        Ok(NavSnapshot {
+            vault: self.vault,
+            ts: clock.unix_timestamp,
+            equity,
+            total_shares: vault.total_shares,
+        })
+    }
+}
+
+/// Overviews of every phoenix_vaults vault. Vaults and investors come from one `getProgramAccounts`
+/// each, and markets and USDC and SOL balances from batched `getMultipleAccounts`.
+pub async fn fetch_fund_overviews(
+    client: &RpcClient,
+    markets: &[Pubkey],
+) -> anyhow::Result<Vec<FundOverview>> {
+    let vaults = get_anchor_accounts::<Vault>(client, &PHOENIX_VAULTS_PROGRAM_ID, vec![]).await?;
+    let investors = get_anchor_accounts::<Investor>(client, &PHOENIX_VAULTS_PROGRAM_ID, vec![])
+        .await?
+        .into_iter()
+        .map(|(key, investor)| (investor.vault, InvestorPosition::from((key, investor))));
+    let mut investors = investors_by_vault(investors);
+
+    // `markets` prices SOL and non-USDC quotes, the vaults' own positions are loaded regardless
+    let mut market_keys = markets.to_vec();
+    for (_, vault) in &vaults {
+        for position in vault.positions.iter().filter(|p| !p.is_available()) {
+            if !market_keys.contains(&position.market) {
+                market_keys.push(position.market);
+            }
+        }
+    }
+    let loaded_markets = fetch_markets(client, &market_keys).await?;
+    let token_accounts: Vec<Pubkey> = vaults
+        .iter()
+        .flat_map(|(_, v)| [v.usdc_token_account, v.sol_token_account])
+        .collect();
+    let balances = fetch_token_balances(client, &token_accounts).await?;
+
+    let mut overviews = vec![];
+    for ((key, vault), balances) in vaults.iter().zip(balances.chunks(2)) {
+        let equity = vault_equity(vault, balances[0], balances[1], &loaded_markets)?;
+        let vault_investors = investors.remove(key).unwrap_or_default();
+        overviews.push(FundOverview::compute(
+            &vault.fund_summary(),
+            &vault_investors,
+            equity,
+            DepositPrice::USDC,
+        ));
+    }
+    Ok(overviews)
+}
@@ -0,0 +1,119 @@
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::amount::TokenAmount;
+use crate::helpers::{mint_atoms, send_and_confirm_tx, SignerRef};
+use crate::market::PhoenixMarket;
+use crate::multisig::{get_multisig_signer_address, propose};
+use crate::vault_update::VaultUpdatePlan;
+
+use super::*;
+
+// Admin for vaults whose manager is a multisig's signer PDA, see `drift_vaults::governance`.
+// `markets` must be every market in the `MarketRegistry`, as for the investor instructions.
+
+async fn fetch_governed_vault(
+    client: &RpcClient,
+    multisig: &Pubkey,
+    vault: &Pubkey,
+) -> anyhow::Result<(Vault, Pubkey)> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let signer = get_multisig_signer_address(multisig).0;
+    if vault_acct.manager != signer {
+        return Err(anyhow::anyhow!(
+            "Vault {} is managed by {}, not multisig {}",
+            vault,
+            vault_acct.manager,
+            multisig
+        ));
+    }
+    Ok((vault_acct, signer))
+}
+
+/// Proposes the `updateVault` of a plan from [`plan_update_vault`], including its delegate, once
+/// the caller has shown it. Returns no proposal for an empty plan.
+pub async fn propose_update_vault<S: Signer + ?Sized>(
+    client: &RpcClient,
+    proposer: &S,
+    multisig: &Pubkey,
+    vault: &Pubkey,
+    plan: &VaultUpdatePlan,
+) -> anyhow::Result<Option<(Pubkey, Signature)>> {
+    if plan.is_empty() {
+        return Ok(None);
+    }
+    let signer = fetch_governed_vault(client, multisig, vault).await?.1;
+    let ix = update_vault_ix(vault, &signer, plan.update.into())?;
+    let proposal = propose(client, proposer, multisig, &ix).await?;
+    Ok(Some(proposal))
+}
+
+/// Proposes withdrawing `amount` USDC of the manager's shares.
+pub async fn propose_manager_request_withdraw<S: Signer + ?Sized>(
+    client: &RpcClient,
+    proposer: &S,
+    multisig: &Pubkey,
+    vault: &Pubkey,
+    amount: TokenAmount,
+    markets: &[Pubkey],
+) -> anyhow::Result<(Pubkey, Signature)> {
+    let (vault_acct, signer) = fetch_governed_vault(client, multisig, vault).await?;
+    let amount = mint_atoms(client, &vault_acct.usdc_mint, amount).await?;
+    let ix = manager_request_withdraw_ix(
+        vault,
+        &signer,
+        &vault_acct.usdc_mint,
+        amount,
+        WithdrawUnit::Token,
+        markets,
+    )?;
+    propose(client, proposer, multisig, &ix).await
+}
+
+pub async fn propose_manager_cancel_withdraw_request<S: Signer + ?Sized>(
+    client: &RpcClient,
+    proposer: &S,
+    multisig: &Pubkey,
+    vault: &Pubkey,
+    markets: &[Pubkey],
+) -> anyhow::Result<(Pubkey, Signature)> {
+    let (vault_acct, signer) = fetch_governed_vault(client, multisig, vault).await?;
+    let ix = manager_cancel_withdraw_request_ix(vault, &signer, &vault_acct.usdc_mint, markets);
+    propose(client, proposer, multisig, &ix).await
+}
+
+/// Proposes completing the manager's matured withdraw request into the signer PDA's USDC
+/// associated token account, which the proposer creates first if needed. Liquidates from the
+/// SOL/USDC market like `investor_withdraw`.
+pub async fn propose_manager_withdraw<S: Signer + ?Sized>(
+    client: &RpcClient,
+    proposer: &S,
+    multisig: &Pubkey,
+    vault: &Pubkey,
+    markets: &[Pubkey],
+) -> anyhow::Result<(Pubkey, Signature)> {
+    let signer = fetch_governed_vault(client, multisig, vault).await?.1;
+    let registry = fetch_market_registry(client).await?;
+    let market = PhoenixMarket::fetch(client, &registry.sol_usdc_market).await?;
+    let create_ata_ix = create_associated_token_account_idempotent(
+        &proposer.pubkey(),
+        &signer,
+        &market.quote_mint(),
+        &spl_token::id(),
+    );
+    send_and_confirm_tx(client, proposer, &[create_ata_ix], &[&SignerRef(proposer)]).await?;
+
+    let ix = manager_withdraw_ix(
+        vault,
+        &signer,
+        &get_associated_token_address(&signer, &market.quote_mint()),
+        &market,
+        markets,
+    );
+    propose(client, proposer, multisig, &ix).await
+}
@@ -0,0 +1,584 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use phoenix::program::get_seat_address;
+use solana_program::instruction::Instruction;
+
+use crate::helpers::instruction_data;
+use crate::market::PhoenixMarket;
+use crate::vault_update::UpdateVaultConfig;
+
+use super::{
+    get_investor_address, get_market_registry_address, get_vault_address, PHOENIX_PROGRAM_ID,
+    PHOENIX_VAULTS_PROGRAM_ID,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+impl From<phoenix::state::Side> for Side {
+    fn from(side: phoenix::state::Side) -> Self {
+        match side {
+            phoenix::state::Side::Bid => Side::Bid,
+            phoenix::state::Side::Ask => Side::Ask,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PlaceOrderParams {
+    /// Phoenix instruction data (discriminator and serialized `OrderPacket`) forwarded via CPI.
+    pub order: Vec<u8>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CancelOrderParams {
+    pub side: Side,
+    pub price_in_ticks: u64,
+    pub order_sequence_number: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CancelMultipleOrdersParams {
+    pub orders: Vec<CancelOrderParams>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MarketTransferParams {
+    pub quote_lots: u64,
+    pub base_lots: u64,
+}
+
+/// Fees and profit shares are in PERCENTAGE_PRECISION, token amounts in USDC atoms.
+/// The protocol fields are fixed at creation and can't be changed by `updateVault`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VaultParams {
+    pub name: [u8; 32],
+    pub redeem_period: i64,
+    pub max_tokens: u64,
+    pub management_fee: i64,
+    pub min_deposit_amount: u64,
+    pub profit_share: u32,
+    pub hurdle_rate: u32,
+    pub permissioned: bool,
+    pub protocol: Pubkey,
+    pub protocol_fee: u64,
+    pub protocol_profit_share: u32,
+}
+
+/// Every market in the registry must be passed as a read-only remaining account
+/// so the program can validate the vault's positions.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UpdateVaultParams {
+    pub redeem_period: Option<i64>,
+    pub max_tokens: Option<u64>,
+    pub management_fee: Option<i64>,
+    pub min_deposit_amount: Option<u64>,
+    pub profit_share: Option<u32>,
+    pub hurdle_rate: Option<u32>,
+    pub permissioned: Option<bool>,
+    pub delegate: Option<Pubkey>,
+}
+
+impl From<UpdateVaultConfig> for UpdateVaultParams {
+    fn from(config: UpdateVaultConfig) -> Self {
+        Self {
+            redeem_period: config.redeem_period,
+            max_tokens: config.max_tokens,
+            management_fee: config.management_fee,
+            min_deposit_amount: config.min_deposit_amount,
+            profit_share: config.profit_share,
+            hurdle_rate: config.hurdle_rate,
+            permissioned: config.permissioned,
+            delegate: config.delegate,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WithdrawUnit {
+    Shares,
+    Token,
+    SharesPercent,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+struct RequestWithdrawParams {
+    withdraw_amount: u64,
+    withdraw_unit: WithdrawUnit,
+}
+
+fn market_metas(markets: &[Pubkey]) -> Vec<AccountMeta> {
+    markets
+        .iter()
+        .map(|m| AccountMeta::new_readonly(*m, false))
+        .collect()
+}
+
+/// Accounts shared by every delegate instruction that moves tokens between the vault and a market.
+fn market_token_accounts(vault: &Pubkey, market: &PhoenixMarket) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(market.base_mint(), false),
+        AccountMeta::new_readonly(market.quote_mint(), false),
+        AccountMeta::new(
+            get_associated_token_address(vault, &market.base_mint()),
+            false,
+        ),
+        AccountMeta::new(
+            get_associated_token_address(vault, &market.quote_mint()),
+            false,
+        ),
+        AccountMeta::new(market.base_vault(), false),
+        AccountMeta::new(market.quote_vault(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ]
+}
+
+pub fn place_limit_order_ix(
+    vault: &Pubkey,
+    delegate: &Pubkey,
+    market: &PhoenixMarket,
+    params: PlaceOrderParams,
+    markets: &[Pubkey],
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*delegate, true),
+        AccountMeta::new_readonly(PHOENIX_PROGRAM_ID, false),
+        AccountMeta::new_readonly(phoenix::phoenix_log_authority::id(), false),
+        AccountMeta::new(market.key, false),
+        AccountMeta::new_readonly(get_seat_address(&market.key, vault).0, false),
+    ];
+    accounts.extend(market_token_accounts(vault, market));
+    accounts.extend(market_metas(markets));
+    Ok(Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([108, 176, 33, 186, 146, 229, 1, 197], &params)?,
+    })
+}
+
+pub fn cancel_all_orders_ix(
+    vault: &Pubkey,
+    delegate: &Pubkey,
+    market: &Pubkey,
+    markets: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*delegate, true),
+        AccountMeta::new_readonly(PHOENIX_PROGRAM_ID, false),
+        AccountMeta::new_readonly(phoenix::phoenix_log_authority::id(), false),
+        AccountMeta::new(*market, false),
+    ];
+    accounts.extend(market_metas(markets));
+    Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [196, 83, 243, 171, 17, 100, 160, 143].to_vec(),
+    }
+}
+
+pub fn cancel_multiple_orders_by_id_ix(
+    vault: &Pubkey,
+    delegate: &Pubkey,
+    market: &Pubkey,
+    params: CancelMultipleOrdersParams,
+    markets: &[Pubkey],
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*delegate, true),
+        AccountMeta::new_readonly(PHOENIX_PROGRAM_ID, false),
+        AccountMeta::new_readonly(phoenix::phoenix_log_authority::id(), false),
+        AccountMeta::new(*market, false),
+    ];
+    accounts.extend(market_metas(markets));
+    Ok(Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([240, 145, 18, 248, 67, 112, 10, 149], &params)?,
+    })
+}
+
+pub fn market_deposit_ix(
+    vault: &Pubkey,
+    delegate: &Pubkey,
+    market: &PhoenixMarket,
+    params: MarketTransferParams,
+    markets: &[Pubkey],
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*delegate, true),
+        AccountMeta::new_readonly(PHOENIX_PROGRAM_ID, false),
+        AccountMeta::new_readonly(phoenix::phoenix_log_authority::id(), false),
+        AccountMeta::new(market.key, false),
+        AccountMeta::new_readonly(get_seat_address(&market.key, vault).0, false),
+    ];
+    accounts.extend(market_token_accounts(vault, market));
+    accounts.extend(market_metas(markets));
+    Ok(Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([231, 55, 143, 187, 144, 8, 25, 94], &params)?,
+    })
+}
+
+pub fn market_withdraw_ix(
+    vault: &Pubkey,
+    delegate: &Pubkey,
+    market: &PhoenixMarket,
+    params: MarketTransferParams,
+    markets: &[Pubkey],
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*delegate, true),
+        AccountMeta::new_readonly(PHOENIX_PROGRAM_ID, false),
+        AccountMeta::new_readonly(phoenix::phoenix_log_authority::id(), false),
+        AccountMeta::new(market.key, false),
+    ];
+    accounts.extend(market_token_accounts(vault, market));
+    accounts.extend(market_metas(markets));
+    Ok(Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([193, 55, 177, 97, 238, 123, 115, 121], &params)?,
+    })
+}
+
+/// Creates the vault PDA and its USDC and SOL associated token accounts.
+pub fn initialize_vault_ix(
+    manager: &Pubkey,
+    payer: &Pubkey,
+    usdc_mint: &Pubkey,
+    sol_mint: &Pubkey,
+    params: VaultParams,
+) -> anyhow::Result<Instruction> {
+    let vault = get_vault_address(&params.name).0;
+    Ok(Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(*manager, true),
+            AccountMeta::new(get_associated_token_address(&vault, usdc_mint), false),
+            AccountMeta::new_readonly(*usdc_mint, false),
+            AccountMeta::new(get_associated_token_address(&vault, sol_mint), false),
+            AccountMeta::new_readonly(*sol_mint, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(anchor_spl::associated_token::ID, false),
+        ],
+        data: instruction_data([48, 191, 163, 44, 71, 129, 63, 164], &params)?,
+    })
+}
+
+pub fn update_vault_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    params: UpdateVaultParams,
+) -> anyhow::Result<Instruction> {
+    Ok(Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*vault, false),
+            AccountMeta::new_readonly(*manager, true),
+        ],
+        data: instruction_data([67, 229, 185, 188, 226, 11, 210, 60], &params)?,
+    })
+}
+
+pub fn initialize_investor_ix(vault: &Pubkey, authority: &Pubkey, payer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*vault, false),
+            AccountMeta::new(get_investor_address(vault, authority).0, false),
+            AccountMeta::new_readonly(*authority, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+        data: [12, 105, 129, 28, 138, 149, 223, 135].to_vec(),
+    }
+}
+
+pub fn investor_deposit_ix(
+    vault: &Pubkey,
+    authority: &Pubkey,
+    usdc_mint: &Pubkey,
+    amount: u64,
+    markets: &[Pubkey],
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(get_investor_address(vault, authority).0, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(get_market_registry_address().0, false),
+        AccountMeta::new(get_associated_token_address(authority, usdc_mint), false),
+        AccountMeta::new(get_associated_token_address(vault, usdc_mint), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend(market_metas(markets));
+    Ok(Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([22, 198, 16, 186, 136, 14, 116, 4], &amount)?,
+    })
+}
+
+pub fn investor_request_withdraw_ix(
+    vault: &Pubkey,
+    authority: &Pubkey,
+    usdc_mint: &Pubkey,
+    withdraw_amount: u64,
+    withdraw_unit: WithdrawUnit,
+    markets: &[Pubkey],
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(get_investor_address(vault, authority).0, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(get_market_registry_address().0, false),
+        AccountMeta::new(get_associated_token_address(vault, usdc_mint), false),
+    ];
+    accounts.extend(market_metas(markets));
+    let params = RequestWithdrawParams {
+        withdraw_amount,
+        withdraw_unit,
+    };
+    Ok(Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([231, 8, 6, 235, 143, 37, 130, 110], &params)?,
+    })
+}
+
+pub fn investor_cancel_withdraw_request_ix(
+    vault: &Pubkey,
+    authority: &Pubkey,
+    usdc_mint: &Pubkey,
+    markets: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(get_investor_address(vault, authority).0, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(get_market_registry_address().0, false),
+        AccountMeta::new(get_associated_token_address(vault, usdc_mint), false),
+    ];
+    accounts.extend(market_metas(markets));
+    Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [167, 100, 110, 128, 113, 154, 224, 77].to_vec(),
+    }
+}
+
+/// Withdraws a matured request, liquidating from `market` if the vault's USDC doesn't cover it.
+pub fn investor_withdraw_ix(
+    vault: &Pubkey,
+    authority: &Pubkey,
+    market: &PhoenixMarket,
+    markets: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(get_investor_address(vault, authority).0, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(get_market_registry_address().0, false),
+        AccountMeta::new(
+            get_associated_token_address(authority, &market.quote_mint()),
+            false,
+        ),
+        AccountMeta::new_readonly(PHOENIX_PROGRAM_ID, false),
+        AccountMeta::new_readonly(phoenix::phoenix_log_authority::id(), false),
+        AccountMeta::new(market.key, false),
+        AccountMeta::new_readonly(get_seat_address(&market.key, vault).0, false),
+    ];
+    accounts.extend(market_token_accounts(vault, market));
+    accounts.extend(market_metas(markets));
+    Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [245, 32, 238, 154, 178, 216, 9, 151].to_vec(),
+    }
+}
+
+pub fn manager_request_withdraw_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    usdc_mint: &Pubkey,
+    withdraw_amount: u64,
+    withdraw_unit: WithdrawUnit,
+    markets: &[Pubkey],
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*manager, true),
+        AccountMeta::new_readonly(get_market_registry_address().0, false),
+        AccountMeta::new(get_associated_token_address(vault, usdc_mint), false),
+    ];
+    accounts.extend(market_metas(markets));
+    let params = RequestWithdrawParams {
+        withdraw_amount,
+        withdraw_unit,
+    };
+    Ok(Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([10, 238, 194, 232, 76, 55, 68, 4], &params)?,
+    })
+}
+
+pub fn manager_cancel_withdraw_request_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    usdc_mint: &Pubkey,
+    markets: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*manager, true),
+        AccountMeta::new_readonly(get_market_registry_address().0, false),
+        AccountMeta::new(get_associated_token_address(vault, usdc_mint), false),
+    ];
+    accounts.extend(market_metas(markets));
+    Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [187, 21, 195, 44, 87, 97, 230, 151].to_vec(),
+    }
+}
+
+/// Withdraws the manager's matured request into `manager_token_account`, liquidating from
+/// `market` like [`investor_withdraw_ix`].
+pub fn manager_withdraw_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    manager_token_account: &Pubkey,
+    market: &PhoenixMarket,
+    markets: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*manager, true),
+        AccountMeta::new_readonly(get_market_registry_address().0, false),
+        AccountMeta::new(*manager_token_account, false),
+        AccountMeta::new_readonly(PHOENIX_PROGRAM_ID, false),
+        AccountMeta::new_readonly(phoenix::phoenix_log_authority::id(), false),
+        AccountMeta::new(market.key, false),
+        AccountMeta::new_readonly(get_seat_address(&market.key, vault).0, false),
+    ];
+    accounts.extend(market_token_accounts(vault, market));
+    accounts.extend(market_metas(markets));
+    Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [201, 248, 190, 143, 86, 43, 183, 254].to_vec(),
+    }
+}
+
+/// Deposits `amount` USDC atoms from the manager's associated token account as manager shares.
+pub fn manager_deposit_ix(
+    vault: &Pubkey,
+    manager: &Pubkey,
+    usdc_mint: &Pubkey,
+    amount: u64,
+    markets: &[Pubkey],
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*manager, true),
+        AccountMeta::new_readonly(get_market_registry_address().0, false),
+        AccountMeta::new(get_associated_token_address(manager, usdc_mint), false),
+        AccountMeta::new(get_associated_token_address(vault, usdc_mint), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend(market_metas(markets));
+    Ok(Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([73, 3, 16, 168, 143, 226, 201, 254], &amount)?,
+    })
+}
+
+pub fn protocol_request_withdraw_ix(
+    vault: &Pubkey,
+    protocol: &Pubkey,
+    usdc_mint: &Pubkey,
+    withdraw_amount: u64,
+    withdraw_unit: WithdrawUnit,
+    markets: &[Pubkey],
+) -> anyhow::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*protocol, true),
+        AccountMeta::new_readonly(get_market_registry_address().0, false),
+        AccountMeta::new(get_associated_token_address(vault, usdc_mint), false),
+    ];
+    accounts.extend(market_metas(markets));
+    let params = RequestWithdrawParams {
+        withdraw_amount,
+        withdraw_unit,
+    };
+    Ok(Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: instruction_data([189, 46, 14, 31, 7, 254, 150, 132], &params)?,
+    })
+}
+
+pub fn protocol_cancel_withdraw_request_ix(
+    vault: &Pubkey,
+    protocol: &Pubkey,
+    usdc_mint: &Pubkey,
+    markets: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*protocol, true),
+        AccountMeta::new_readonly(get_market_registry_address().0, false),
+        AccountMeta::new(get_associated_token_address(vault, usdc_mint), false),
+    ];
+    accounts.extend(market_metas(markets));
+    Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [194, 217, 171, 94, 56, 253, 179, 242].to_vec(),
+    }
+}
+
+/// Withdraws the protocol's matured request into `protocol_token_account`, liquidating from
+/// `market` like [`investor_withdraw_ix`].
+pub fn protocol_withdraw_ix(
+    vault: &Pubkey,
+    protocol: &Pubkey,
+    protocol_token_account: &Pubkey,
+    market: &PhoenixMarket,
+    markets: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*vault, false),
+        AccountMeta::new_readonly(*protocol, true),
+        AccountMeta::new_readonly(get_market_registry_address().0, false),
+        AccountMeta::new(*protocol_token_account, false),
+        AccountMeta::new_readonly(PHOENIX_PROGRAM_ID, false),
+        AccountMeta::new_readonly(phoenix::phoenix_log_authority::id(), false),
+        AccountMeta::new(market.key, false),
+        AccountMeta::new_readonly(get_seat_address(&market.key, vault).0, false),
+    ];
+    accounts.extend(market_token_accounts(vault, market));
+    accounts.extend(market_metas(markets));
+    Instruction {
+        program_id: PHOENIX_VAULTS_PROGRAM_ID,
+        accounts,
+        data: [166, 24, 188, 209, 21, 251, 63, 199].to_vec(),
+    }
+}
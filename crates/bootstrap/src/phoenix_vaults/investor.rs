@@ -0,0 +1,133 @@
+use anchor_spl::token::spl_token;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::amount::TokenAmount;
+use crate::helpers::{
+    decode_account, get_account, get_anchor_accounts, mint_atoms, send_and_confirm_tx, SignerRef,
+};
+use crate::market::PhoenixMarket;
+
+use super::*;
+
+/// `Investor.vault` comes right after the discriminator.
+const INVESTOR_VAULT_OFFSET: usize = 8;
+
+pub async fn fetch_investor(
+    client: &RpcClient,
+    vault: &Pubkey,
+    authority: &Pubkey,
+) -> anyhow::Result<Investor> {
+    let account = get_account(client, &get_investor_address(vault, authority).0).await?;
+    decode_account::<Investor>(&account.data)
+}
+
+pub async fn fetch_investors(
+    client: &RpcClient,
+    vault: &Pubkey,
+) -> anyhow::Result<Vec<(Pubkey, Investor)>> {
+    get_anchor_accounts::<Investor>(
+        client,
+        &PHOENIX_VAULTS_PROGRAM_ID,
+        vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            INVESTOR_VAULT_OFFSET,
+            vault.to_bytes().to_vec(),
+        ))],
+    )
+    .await
+}
+
+/// Deposits `amount` USDC, creating the `Investor` first if needed.
+pub async fn investor_deposit<S: Signer + ?Sized>(
+    client: &RpcClient,
+    authority: &S,
+    vault: &Pubkey,
+    amount: TokenAmount,
+    markets: &[Pubkey],
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let amount = mint_atoms(client, &vault_acct.usdc_mint, amount).await?;
+    let mut ixs = vec![];
+    if fetch_investor(client, vault, &authority.pubkey())
+        .await
+        .is_err()
+    {
+        ixs.push(initialize_investor_ix(
+            vault,
+            &authority.pubkey(),
+            &authority.pubkey(),
+        ));
+    }
+    ixs.push(investor_deposit_ix(
+        vault,
+        &authority.pubkey(),
+        &vault_acct.usdc_mint,
+        amount,
+        markets,
+    )?);
+    send_and_confirm_tx(client, authority, &ixs, &[&SignerRef(authority)]).await
+}
+
+/// Requests a withdrawal of `amount` USDC, claimable after the redeem period.
+pub async fn investor_request_withdraw<S: Signer + ?Sized>(
+    client: &RpcClient,
+    authority: &S,
+    vault: &Pubkey,
+    amount: TokenAmount,
+    markets: &[Pubkey],
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let amount = mint_atoms(client, &vault_acct.usdc_mint, amount).await?;
+    let ix = investor_request_withdraw_ix(
+        vault,
+        &authority.pubkey(),
+        &vault_acct.usdc_mint,
+        amount,
+        WithdrawUnit::Token,
+        markets,
+    )?;
+    send_and_confirm_tx(client, authority, &[ix], &[&SignerRef(authority)]).await
+}
+
+/// Cancels the pending withdraw request so the shares keep earning.
+pub async fn investor_cancel_withdraw_request<S: Signer + ?Sized>(
+    client: &RpcClient,
+    authority: &S,
+    vault: &Pubkey,
+    markets: &[Pubkey],
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let ix = investor_cancel_withdraw_request_ix(
+        vault,
+        &authority.pubkey(),
+        &vault_acct.usdc_mint,
+        markets,
+    );
+    send_and_confirm_tx(client, authority, &[ix], &[&SignerRef(authority)]).await
+}
+
+/// Completes a matured withdraw request. The program liquidates from the SOL/USDC market
+/// if the vault's USDC balance doesn't cover it.
+pub async fn investor_withdraw<S: Signer + ?Sized>(
+    client: &RpcClient,
+    authority: &S,
+    vault: &Pubkey,
+    markets: &[Pubkey],
+) -> anyhow::Result<Signature> {
+    let registry = fetch_market_registry(client).await?;
+    let market = PhoenixMarket::fetch(client, &registry.sol_usdc_market).await?;
+    let ixs = vec![
+        create_associated_token_account_idempotent(
+            &authority.pubkey(),
+            &authority.pubkey(),
+            &market.quote_mint(),
+            &spl_token::id(),
+        ),
+        investor_withdraw_ix(vault, &authority.pubkey(), &market, markets),
+    ];
+    send_and_confirm_tx(client, authority, &ixs, &[&SignerRef(authority)]).await
+}
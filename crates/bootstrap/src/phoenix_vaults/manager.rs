@@ -0,0 +1,165 @@
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::amount::TokenAmount;
+use crate::helpers::{
+    decode_account, get_account, get_mint, mint_atoms, send_and_confirm_tx, SignerRef,
+};
+use crate::market::PhoenixMarket;
+use crate::vault_update::{plan_update, UpdateRules, UpdateVaultConfig, VaultUpdatePlan};
+
+use super::*;
+
+/// phoenix_vaults only lets the manager lower limits and fees, and `updateVault` also sets the delegate.
+pub const UPDATE_RULES: UpdateRules = UpdateRules {
+    max_tokens_decrease_only: true,
+    min_deposit_decrease_only: true,
+    supports_delegate: true,
+};
+
+pub async fn fetch_vault(client: &RpcClient, vault: &Pubkey) -> anyhow::Result<Vault> {
+    let account = get_account(client, vault).await?;
+    decode_account::<Vault>(&account.data)
+}
+
+pub async fn fetch_market_registry(client: &RpcClient) -> anyhow::Result<MarketRegistry> {
+    let account = get_account(client, &get_market_registry_address().0).await?;
+    decode_account::<MarketRegistry>(&account.data)
+}
+
+/// Creates a vault managed by `manager` that takes USDC deposits and trades the registry's markets.
+/// Returns the vault address.
+pub async fn initialize_vault<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    params: VaultParams,
+) -> anyhow::Result<(Pubkey, Signature)> {
+    let registry = fetch_market_registry(client).await?;
+    let vault = get_vault_address(&params.name).0;
+    let ix = initialize_vault_ix(
+        &manager.pubkey(),
+        &manager.pubkey(),
+        &registry.usdc_mint,
+        &registry.sol_mint,
+        params,
+    )?;
+    let sig = send_and_confirm_tx(client, manager, &[ix], &[&SignerRef(manager)]).await?;
+    Ok((vault, sig))
+}
+
+/// Diffs `config` against the vault's on-chain state without sending anything.
+pub async fn plan_update_vault(
+    client: &RpcClient,
+    vault: &Pubkey,
+    config: &UpdateVaultConfig,
+) -> anyhow::Result<VaultUpdatePlan> {
+    let current = fetch_vault(client, vault).await?;
+    let decimals = get_mint(client, &current.usdc_mint).await?.decimals;
+    plan_update(&current.settings(decimals), config, UPDATE_RULES)
+}
+
+/// Sends the `updateVault` of a plan from [`plan_update_vault`], once the caller has shown it.
+/// Nothing is sent for an empty plan.
+pub async fn send_update<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    vault: &Pubkey,
+    plan: &VaultUpdatePlan,
+) -> anyhow::Result<Option<Signature>> {
+    if plan.is_empty() {
+        return Ok(None);
+    }
+    let ix = update_vault_ix(vault, &manager.pubkey(), plan.update.into())?;
+    let sig = send_and_confirm_tx(client, manager, &[ix], &[&SignerRef(manager)]).await?;
+    Ok(Some(sig))
+}
+
+/// Deposits `amount` USDC from the manager's associated token account.
+pub async fn manager_deposit<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    vault: &Pubkey,
+    amount: TokenAmount,
+    markets: &[Pubkey],
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let amount = mint_atoms(client, &vault_acct.usdc_mint, amount).await?;
+    let ix = manager_deposit_ix(
+        vault,
+        &manager.pubkey(),
+        &vault_acct.usdc_mint,
+        amount,
+        markets,
+    )?;
+    send_and_confirm_tx(client, manager, &[ix], &[&SignerRef(manager)]).await
+}
+
+/// Requests a withdrawal of `amount` USDC of the manager's shares.
+pub async fn manager_request_withdraw<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    vault: &Pubkey,
+    amount: TokenAmount,
+    markets: &[Pubkey],
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let amount = mint_atoms(client, &vault_acct.usdc_mint, amount).await?;
+    let ix = manager_request_withdraw_ix(
+        vault,
+        &manager.pubkey(),
+        &vault_acct.usdc_mint,
+        amount,
+        WithdrawUnit::Token,
+        markets,
+    )?;
+    send_and_confirm_tx(client, manager, &[ix], &[&SignerRef(manager)]).await
+}
+
+pub async fn manager_cancel_withdraw_request<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    vault: &Pubkey,
+    markets: &[Pubkey],
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let ix = manager_cancel_withdraw_request_ix(
+        vault,
+        &manager.pubkey(),
+        &vault_acct.usdc_mint,
+        markets,
+    );
+    send_and_confirm_tx(client, manager, &[ix], &[&SignerRef(manager)]).await
+}
+
+/// Completes the manager's matured withdraw request into its USDC associated token account,
+/// liquidating from the SOL/USDC market like `investor_withdraw`.
+pub async fn manager_withdraw<S: Signer + ?Sized>(
+    client: &RpcClient,
+    manager: &S,
+    vault: &Pubkey,
+    markets: &[Pubkey],
+) -> anyhow::Result<Signature> {
+    let registry = fetch_market_registry(client).await?;
+    let market = PhoenixMarket::fetch(client, &registry.sol_usdc_market).await?;
+    let ixs = vec![
+        create_associated_token_account_idempotent(
+            &manager.pubkey(),
+            &manager.pubkey(),
+            &market.quote_mint(),
+            &spl_token::id(),
+        ),
+        manager_withdraw_ix(
+            vault,
+            &manager.pubkey(),
+            &get_associated_token_address(&manager.pubkey(), &market.quote_mint()),
+            &market,
+            markets,
+        ),
+    ];
+    send_and_confirm_tx(client, manager, &ixs, &[&SignerRef(manager)]).await
+}
@@ -0,0 +1,50 @@
+mod accounts;
+mod equity;
+mod events;
+mod fund;
+mod governance;
+mod instructions;
+mod investor;
+mod manager;
+mod protocol;
+mod trader;
+mod valuation;
+
+pub use accounts::*;
+pub use equity::*;
+pub use events::*;
+pub use fund::*;
+pub use governance::*;
+pub use instructions::*;
+pub use investor::*;
+pub use manager::*;
+pub use protocol::*;
+pub use trader::*;
+pub use valuation::*;
+
+use solana_sdk::pubkey::Pubkey;
+
+pub const PHOENIX_VAULTS_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("VLt8tiD4iUGVuxFRr1NiN63BYJGKua5rNpEcsEGzdBq");
+
+pub const PHOENIX_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY");
+
+pub const PHOENIX_SEAT_MANAGER_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("PSMxQbAoDWDbvd9ezQJgARyq6R9L5kJAasaLDVcZwf1");
+
+/// Vault PDA derived from the 32 byte encoded name.
+pub fn get_vault_address(name: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", name], &PHOENIX_VAULTS_PROGRAM_ID)
+}
+
+pub fn get_investor_address(vault: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"investor", vault.as_ref(), authority.as_ref()],
+        &PHOENIX_VAULTS_PROGRAM_ID,
+    )
+}
+
+pub fn get_market_registry_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"market_registry"], &PHOENIX_VAULTS_PROGRAM_ID)
+}
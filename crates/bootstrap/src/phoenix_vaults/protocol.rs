@@ -0,0 +1,78 @@
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::amount::TokenAmount;
+use crate::helpers::{mint_atoms, send_and_confirm_tx, SignerRef};
+use crate::market::PhoenixMarket;
+
+use super::*;
+
+/// Requests a withdrawal of `amount` USDC of the protocol's shares.
+pub async fn protocol_request_withdraw<S: Signer + ?Sized>(
+    client: &RpcClient,
+    protocol: &S,
+    vault: &Pubkey,
+    amount: TokenAmount,
+    markets: &[Pubkey],
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let amount = mint_atoms(client, &vault_acct.usdc_mint, amount).await?;
+    let ix = protocol_request_withdraw_ix(
+        vault,
+        &protocol.pubkey(),
+        &vault_acct.usdc_mint,
+        amount,
+        WithdrawUnit::Token,
+        markets,
+    )?;
+    send_and_confirm_tx(client, protocol, &[ix], &[&SignerRef(protocol)]).await
+}
+
+pub async fn protocol_cancel_withdraw_request<S: Signer + ?Sized>(
+    client: &RpcClient,
+    protocol: &S,
+    vault: &Pubkey,
+    markets: &[Pubkey],
+) -> anyhow::Result<Signature> {
+    let vault_acct = fetch_vault(client, vault).await?;
+    let ix = protocol_cancel_withdraw_request_ix(
+        vault,
+        &protocol.pubkey(),
+        &vault_acct.usdc_mint,
+        markets,
+    );
+    send_and_confirm_tx(client, protocol, &[ix], &[&SignerRef(protocol)]).await
+}
+
+/// Completes the protocol's matured withdraw request into its USDC associated token account,
+/// liquidating from the SOL/USDC market like `investor_withdraw`.
+pub async fn protocol_withdraw<S: Signer + ?Sized>(
+    client: &RpcClient,
+    protocol: &S,
+    vault: &Pubkey,
+    markets: &[Pubkey],
+) -> anyhow::Result<Signature> {
+    let registry = fetch_market_registry(client).await?;
+    let market = PhoenixMarket::fetch(client, &registry.sol_usdc_market).await?;
+    let ixs = vec![
+        create_associated_token_account_idempotent(
+            &protocol.pubkey(),
+            &protocol.pubkey(),
+            &market.quote_mint(),
+            &spl_token::id(),
+        ),
+        protocol_withdraw_ix(
+            vault,
+            &protocol.pubkey(),
+            &get_associated_token_address(&protocol.pubkey(), &market.quote_mint()),
+            &market,
+            markets,
+        ),
+    ];
+    send_and_confirm_tx(client, protocol, &ixs, &[&SignerRef(protocol)]).await
+}
@@ -0,0 +1,282 @@
+use borsh_v09::BorshSerialize;
+use phoenix::program::PhoenixInstruction;
+use phoenix::state::{OrderPacket, SelfTradeBehavior, Side};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+use crate::amount::{TokenDelta, UiAmount};
+use crate::helpers::{send_and_confirm_tx, SignerRef};
+use crate::market::{OpenOrder, PhoenixMarket};
+
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderType {
+    /// Rests on the book and crosses the spread if matched.
+    Limit,
+    /// Rejected if it would cross the spread.
+    PostOnly,
+    /// Fills what it can immediately and the remainder is cancelled.
+    ImmediateOrCancel,
+}
+
+/// Order placed by a vault delegate with price and size in UI units,
+/// i.e. quote units per base unit and base units.
+#[derive(Debug, Clone, Copy)]
+pub struct VaultOrder {
+    pub side: Side,
+    pub order_type: OrderType,
+    pub price: UiAmount,
+    pub size: UiAmount,
+    pub client_order_id: u128,
+}
+
+/// Change in the vault's position on a market, measured across a transaction.
+#[derive(Debug, Clone, Copy)]
+pub struct PositionChange {
+    pub signature: Signature,
+    pub before: MarketPosition,
+    pub after: MarketPosition,
+    /// Net base tokens gained or given up.
+    pub base_filled: TokenDelta,
+    /// Net quote tokens gained or given up.
+    pub quote_filled: TokenDelta,
+}
+
+/// Trades a phoenix_vaults vault as its delegate.
+pub struct VaultTrader<'a, S: Signer + ?Sized> {
+    pub client: &'a RpcClient,
+    pub delegate: &'a S,
+    pub vault: Pubkey,
+    /// Every market in the `MarketRegistry`, passed as remaining accounts.
+    pub markets: Vec<Pubkey>,
+}
+
+impl<'a, S: Signer + ?Sized> VaultTrader<'a, S> {
+    pub fn new(
+        client: &'a RpcClient,
+        delegate: &'a S,
+        vault: Pubkey,
+        markets: Vec<Pubkey>,
+    ) -> Self {
+        Self {
+            client,
+            delegate,
+            vault,
+            markets,
+        }
+    }
+
+    pub async fn fetch_vault(&self) -> anyhow::Result<Vault> {
+        fetch_vault(self.client, &self.vault).await
+    }
+
+    /// The vault's position on `market`, or an empty position if it has none.
+    pub async fn fetch_position(&self, market: &Pubkey) -> anyhow::Result<MarketPosition> {
+        let vault = self.fetch_vault().await?;
+        Ok(vault.position(market).copied().unwrap_or(MarketPosition {
+            market: *market,
+            ..Default::default()
+        }))
+    }
+
+    pub async fn open_orders(&self, market: &Pubkey) -> anyhow::Result<Vec<OpenOrder>> {
+        PhoenixMarket::fetch(self.client, market)
+            .await?
+            .open_orders(&self.vault)
+    }
+
+    /// Places an order with the vault's deposited funds and reports the resulting fill
+    /// by diffing the vault's `MarketPosition` before and after the transaction.
+    pub async fn place_order(
+        &self,
+        market: &Pubkey,
+        order: VaultOrder,
+    ) -> anyhow::Result<PositionChange> {
+        let market_state = PhoenixMarket::fetch(self.client, market).await?;
+        if market_state.base_units_to_lots(order.size)? == 0 {
+            return Err(anyhow::anyhow!(
+                "Order size {} is below the market's base lot size",
+                order.size
+            ));
+        }
+        let packet = order_packet(&market_state, &order)?;
+        let ix = place_limit_order_ix(
+            &self.vault,
+            &self.delegate.pubkey(),
+            &market_state,
+            PlaceOrderParams {
+                order: place_order_data(&packet)?,
+            },
+            &self.markets,
+        )?;
+        self.send_with_position_change(&market_state, &[ix]).await
+    }
+
+    pub async fn cancel_all_orders(&self, market: &Pubkey) -> anyhow::Result<PositionChange> {
+        let market_state = PhoenixMarket::fetch(self.client, market).await?;
+        let ix = cancel_all_orders_ix(&self.vault, &self.delegate.pubkey(), market, &self.markets);
+        self.send_with_position_change(&market_state, &[ix]).await
+    }
+
+    pub async fn cancel_orders_by_id(
+        &self,
+        market: &Pubkey,
+        orders: &[OpenOrder],
+    ) -> anyhow::Result<PositionChange> {
+        let market_state = PhoenixMarket::fetch(self.client, market).await?;
+        let params = CancelMultipleOrdersParams {
+            orders: orders
+                .iter()
+                .map(|o| CancelOrderParams {
+                    side: o.side.into(),
+                    price_in_ticks: o.price_in_ticks,
+                    order_sequence_number: o.order_sequence_number,
+                })
+                .collect(),
+        };
+        let ix = cancel_multiple_orders_by_id_ix(
+            &self.vault,
+            &self.delegate.pubkey(),
+            market,
+            params,
+            &self.markets,
+        )?;
+        self.send_with_position_change(&market_state, &[ix]).await
+    }
+
+    /// Moves quote and base units from the vault's token accounts onto the market.
+    pub async fn market_deposit(
+        &self,
+        market: &Pubkey,
+        quote_units: UiAmount,
+        base_units: UiAmount,
+    ) -> anyhow::Result<PositionChange> {
+        let market_state = PhoenixMarket::fetch(self.client, market).await?;
+        let params = MarketTransferParams {
+            quote_lots: market_state.quote_units_to_lots(quote_units)?,
+            base_lots: market_state.base_units_to_lots(base_units)?,
+        };
+        let ix = market_deposit_ix(
+            &self.vault,
+            &self.delegate.pubkey(),
+            &market_state,
+            params,
+            &self.markets,
+        )?;
+        self.send_with_position_change(&market_state, &[ix]).await
+    }
+
+    /// Moves free quote and base units from the market back to the vault's token accounts.
+    pub async fn market_withdraw(
+        &self,
+        market: &Pubkey,
+        quote_units: UiAmount,
+        base_units: UiAmount,
+    ) -> anyhow::Result<PositionChange> {
+        let market_state = PhoenixMarket::fetch(self.client, market).await?;
+        let params = MarketTransferParams {
+            quote_lots: market_state.quote_units_to_lots(quote_units)?,
+            base_lots: market_state.base_units_to_lots(base_units)?,
+        };
+        let ix = market_withdraw_ix(
+            &self.vault,
+            &self.delegate.pubkey(),
+            &market_state,
+            params,
+            &self.markets,
+        )?;
+        self.send_with_position_change(&market_state, &[ix]).await
+    }
+
+    async fn send_with_position_change(
+        &self,
+        market: &PhoenixMarket,
+        ixs: &[solana_program::instruction::Instruction],
+    ) -> anyhow::Result<PositionChange> {
+        let before = self.fetch_position(&market.key).await?;
+        let signature = send_and_confirm_tx(
+            self.client,
+            self.delegate,
+            ixs,
+            &[&SignerRef(self.delegate)],
+        )
+        .await?;
+        let after = self.fetch_position(&market.key).await?;
+        position_change(signature, market, before, after)
+    }
+}
+
+/// Phoenix order packet for `order`, failing if its price or size isn't a whole number of ticks or lots.
+pub fn order_packet(market: &PhoenixMarket, order: &VaultOrder) -> anyhow::Result<OrderPacket> {
+    let price_in_ticks = market.price_to_ticks(order.price)?;
+    let num_base_lots = market.base_units_to_lots(order.size)?;
+    Ok(match order.order_type {
+        OrderType::Limit => OrderPacket::new_limit_order(
+            order.side,
+            price_in_ticks,
+            num_base_lots,
+            SelfTradeBehavior::CancelProvide,
+            None,
+            order.client_order_id,
+            true,
+        ),
+        OrderType::PostOnly => OrderPacket::new_post_only(
+            order.side,
+            price_in_ticks,
+            num_base_lots,
+            order.client_order_id,
+            true,
+            true,
+        ),
+        OrderType::ImmediateOrCancel => OrderPacket::new_ioc_by_lots(
+            order.side,
+            price_in_ticks,
+            num_base_lots,
+            SelfTradeBehavior::CancelProvide,
+            None,
+            order.client_order_id,
+            true,
+        ),
+    })
+}
+
+/// Phoenix instruction data for `packet`, always tagged `PlaceLimitOrderWithFreeFunds`, the only
+/// tag phoenix_vaults forwards. Phoenix matches immediate-or-cancel packets through it too.
+pub fn place_order_data(packet: &OrderPacket) -> anyhow::Result<Vec<u8>> {
+    let mut data = PhoenixInstruction::PlaceLimitOrderWithFreeFunds.to_vec();
+    packet
+        .serialize(&mut data)
+        .map_err(|e| anyhow::anyhow!("Failed to serialize order packet: {:?}", e))?;
+    Ok(data)
+}
+
+/// Net fill between two snapshots of the vault's position on `market`.
+pub fn position_change(
+    signature: Signature,
+    market: &PhoenixMarket,
+    before: MarketPosition,
+    after: MarketPosition,
+) -> anyhow::Result<PositionChange> {
+    let base_lots = |p: &MarketPosition| (p.base_lots_free + p.base_lots_locked) as i128;
+    let quote_lots = |p: &MarketPosition| (p.quote_lots_free + p.quote_lots_locked) as i128;
+    let base_delta = base_lots(&after) - base_lots(&before);
+    let quote_delta = quote_lots(&after) - quote_lots(&before);
+    Ok(PositionChange {
+        signature,
+        before,
+        after,
+        base_filled: TokenDelta::from_lots(
+            base_delta,
+            market.base_atoms_per_base_lot(),
+            u8::try_from(market.base_decimals())?,
+        )?,
+        quote_filled: TokenDelta::from_lots(
+            quote_delta,
+            market.quote_atoms_per_quote_lot(),
+            u8::try_from(market.quote_decimals())?,
+        )?,
+    })
+}
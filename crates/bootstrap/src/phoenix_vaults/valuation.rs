@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+
+use phoenix::quantities::WrapperU64;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::amount::SOL_DECIMALS;
+use crate::market::PhoenixMarket;
+use crate::pyth::{fetch_checked_prices, PriceChecks};
+use crate::vault_update::QUOTE_PRECISION;
+
+use super::*;
+
+/// Liquidating a position for less than this fraction below its marked value flags the book as thin.
+pub const DEFAULT_MAX_SLIPPAGE: f64 = 0.01;
+
+/// Where the price to mark a market's base token at comes from, in quote units per base unit.
+pub trait PriceSource: Send + Sync {
+    fn price(&self, market: &PhoenixMarket) -> anyhow::Result<Option<f64>>;
+}
+
+/// What the base could be sold for right now at the top of the book.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BestBid;
+
+impl PriceSource for BestBid {
+    fn price(&self, market: &PhoenixMarket) -> anyhow::Result<Option<f64>> {
+        Ok(market.best_bid_ask()?.0)
+    }
+}
+
+/// Midpoint of the best bid and ask, or whichever side has liquidity.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MidPrice;
+
+impl PriceSource for MidPrice {
+    fn price(&self, market: &PhoenixMarket) -> anyhow::Result<Option<f64>> {
+        Ok(match market.best_bid_ask()? {
+            (Some(bid), Some(ask)) => Some((bid + ask) / 2.0),
+            (bid, ask) => bid.or(ask),
+        })
+    }
+}
+
+/// Oracle prices keyed by the market they mark.
+#[derive(Debug, Clone, Default)]
+pub struct OraclePrices(pub HashMap<Pubkey, f64>);
+
+impl OraclePrices {
+    /// Reads the Pyth feed of each market, failing if any price is stale or too uncertain.
+    pub async fn fetch_pyth(
+        client: &RpcClient,
+        oracles: &HashMap<Pubkey, Pubkey>,
+        checks: &PriceChecks,
+    ) -> anyhow::Result<Self> {
+        let (markets, feeds): (Vec<Pubkey>, Vec<Pubkey>) = oracles.iter().unzip();
+        let prices = fetch_checked_prices(client, &feeds, checks).await?;
+        Ok(Self(markets.into_iter().zip(prices).collect()))
+    }
+}
+
+impl PriceSource for OraclePrices {
+    fn price(&self, market: &PhoenixMarket) -> anyhow::Result<Option<f64>> {
+        Ok(self.0.get(&market.key).copied())
+    }
+}
+
+/// Prices supplied by the caller, keyed by market.
+#[derive(Debug, Clone, Default)]
+pub struct FixedPrices(pub HashMap<Pubkey, f64>);
+
+impl PriceSource for FixedPrices {
+    fn price(&self, market: &PhoenixMarket) -> anyhow::Result<Option<f64>> {
+        Ok(self.0.get(&market.key).copied())
+    }
+}
+
+/// A vault's seat on one market. Amounts are UI units; values are quote units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionValuation {
+    pub market: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    /// Free and locked base.
+    pub base_units: f64,
+    /// Free and locked quote.
+    pub quote_units: f64,
+    /// Mark price, `None` if the price source had nothing for this market.
+    pub price: Option<f64>,
+    /// USDC per quote unit, `None` if the quote can't be priced in USDC.
+    pub quote_price: Option<f64>,
+    pub value: f64,
+    /// Quote received by selling `base_units` into the bids, plus `quote_units`.
+    pub liquidation_value: f64,
+    /// The bids can't absorb `base_units` within the allowed slippage of the marked value,
+    /// or the quote has no USDC price.
+    pub thin_book: bool,
+}
+
+impl PositionValuation {
+    /// `value` in USDC, zero if the quote has no USDC price.
+    pub fn usdc_value(&self) -> f64 {
+        self.value * self.quote_price.unwrap_or(0.0)
+    }
+
+    /// `liquidation_value` in USDC, zero if the quote has no USDC price.
+    pub fn usdc_liquidation_value(&self) -> f64 {
+        self.liquidation_value * self.quote_price.unwrap_or(0.0)
+    }
+}
+
+/// Whether selling `base_units` for `proceeds` falls more than `max_slippage` short of its marked value.
+pub fn is_thin_book(base_units: f64, price: Option<f64>, proceeds: f64, max_slippage: f64) -> bool {
+    match price {
+        _ if base_units <= 0.0 => false,
+        Some(price) => proceeds < base_units * price * (1.0 - max_slippage),
+        None => true,
+    }
+}
+
+/// Quote received by market selling `base_units` into `bids`, stopping when the book runs out.
+pub fn liquidation_proceeds(bids: &[(f64, f64)], base_units: f64) -> f64 {
+    let mut remaining = base_units;
+    let mut proceeds = 0.0;
+    for (price, size) in bids {
+        if remaining <= 0.0 {
+            break;
+        }
+        let fill = remaining.min(*size);
+        proceeds += fill * price;
+        remaining -= fill;
+    }
+    proceeds
+}
+
+/// Value of one asset across the vault's token account and every market seat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AssetValuation {
+    pub mint: Pubkey,
+    /// UI units
+    pub amount: f64,
+    /// USDC
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VaultValuation {
+    pub usdc_mint: Pubkey,
+    /// USDC held by the vault outside of any market.
+    pub usdc: f64,
+    pub sol_mint: Pubkey,
+    /// SOL held by the vault outside of any market.
+    pub sol: f64,
+    /// USDC per SOL, `None` if no SOL/USDC market was priced.
+    pub sol_price: Option<f64>,
+    pub positions: Vec<PositionValuation>,
+}
+
+impl VaultValuation {
+    /// USDC value of the SOL held outside of any market.
+    pub fn sol_value(&self) -> f64 {
+        self.sol * self.sol_price.unwrap_or(0.0)
+    }
+
+    /// USDC
+    pub fn total(&self) -> f64 {
+        self.usdc + self.sol_value() + self.positions.iter().map(|p| p.usdc_value()).sum::<f64>()
+    }
+
+    /// USDC the vault would hold after selling every position into the book.
+    /// SOL outside of any market is counted at `sol_price`.
+    pub fn liquidation_total(&self) -> f64 {
+        self.usdc
+            + self.sol_value()
+            + self
+                .positions
+                .iter()
+                .map(|p| p.usdc_liquidation_value())
+                .sum::<f64>()
+    }
+
+    /// Total in USDC atoms.
+    pub fn total_atoms(&self) -> u64 {
+        (self.total() * QUOTE_PRECISION as f64) as u64
+    }
+
+    pub fn has_thin_book(&self) -> bool {
+        self.positions.iter().any(|p| p.thin_book)
+    }
+
+    /// Holdings per mint, with the free USDC and SOL and every market's tokens folded into one entry each.
+    pub fn assets(&self) -> Vec<AssetValuation> {
+        let mut assets: Vec<AssetValuation> = vec![
+            AssetValuation {
+                mint: self.usdc_mint,
+                amount: self.usdc,
+                value: self.usdc,
+            },
+            AssetValuation {
+                mint: self.sol_mint,
+                amount: self.sol,
+                value: self.sol_value(),
+            },
+        ];
+        let mut add = |mint: Pubkey, amount: f64, value: f64| match assets
+            .iter_mut()
+            .find(|a| a.mint == mint)
+        {
+            Some(asset) => {
+                asset.amount += amount;
+                asset.value += value;
+            }
+            None => assets.push(AssetValuation {
+                mint,
+                amount,
+                value,
+            }),
+        };
+        for p in &self.positions {
+            let quote_price = p.quote_price.unwrap_or(0.0);
+            add(p.quote_mint, p.quote_units, p.quote_units * quote_price);
+            add(
+                p.base_mint,
+                p.base_units,
+                (p.value - p.quote_units) * quote_price,
+            );
+        }
+        assets
+    }
+}
+
+/// USDC per unit of `mint`, marked on the `mint`/USDC market in `markets`.
+/// `None` if `markets` has no such market or `price_source` has no price for it.
+pub fn usdc_price(
+    mint: &Pubkey,
+    usdc_mint: &Pubkey,
+    markets: &HashMap<Pubkey, PhoenixMarket>,
+    price_source: &dyn PriceSource,
+) -> anyhow::Result<Option<f64>> {
+    if mint == usdc_mint {
+        return Ok(Some(1.0));
+    }
+    match markets
+        .values()
+        .find(|m| m.base_mint() == *mint && m.quote_mint() == *usdc_mint)
+    {
+        Some(market) => price_source.price(market),
+        None => Ok(None),
+    }
+}
+
+/// Marks the vault's seat on `market` against `price_source`.
+/// `quote_price` is USDC per quote unit, see [`usdc_price`].
+pub fn value_position(
+    market: &PhoenixMarket,
+    vault: &Pubkey,
+    price_source: &dyn PriceSource,
+    quote_price: Option<f64>,
+    max_slippage: f64,
+) -> anyhow::Result<PositionValuation> {
+    let (base_lots, quote_lots) = match market.trader_state(vault)? {
+        Some(state) => (
+            state.base_lots_free.as_u64() + state.base_lots_locked.as_u64(),
+            state.quote_lots_free.as_u64() + state.quote_lots_locked.as_u64(),
+        ),
+        None => (0, 0),
+    };
+    let bids: Vec<(f64, f64)> = market
+        .ladder(u64::MAX)?
+        .bids
+        .iter()
+        .map(|level| {
+            Ok((
+                market.ticks_to_price(level.price_in_ticks),
+                market
+                    .base_lots_to_amount(level.size_in_base_lots)?
+                    .to_f64(),
+            ))
+        })
+        .collect::<anyhow::Result<_>>()?;
+    let base_units = market.base_lots_to_amount(base_lots)?.to_f64();
+    let quote_units = market.quote_lots_to_amount(quote_lots)?.to_f64();
+    let price = price_source.price(market)?;
+    let proceeds = liquidation_proceeds(&bids, base_units);
+    Ok(PositionValuation {
+        market: market.key,
+        base_mint: market.base_mint(),
+        quote_mint: market.quote_mint(),
+        base_units,
+        quote_units,
+        price,
+        quote_price,
+        value: base_units * price.unwrap_or(0.0) + quote_units,
+        liquidation_value: proceeds + quote_units,
+        thin_book: quote_price.is_none() || is_thin_book(base_units, price, proceeds, max_slippage),
+    })
+}
+
+/// Marks the vault's USDC, SOL and every open `MarketPosition` in USDC.
+/// `markets` must contain each market the vault has a position in. Quotes other than USDC, and SOL,
+/// are priced through their USDC market in `markets`, if it has one.
+pub fn value_vault(
+    vault: &Vault,
+    usdc_balance: u64,
+    sol_balance: u64,
+    markets: &HashMap<Pubkey, PhoenixMarket>,
+    price_source: &dyn PriceSource,
+    max_slippage: f64,
+) -> anyhow::Result<VaultValuation> {
+    let mut positions = vec![];
+    for position in vault.positions.iter().filter(|p| !p.is_available()) {
+        let market = markets
+            .get(&position.market)
+            .ok_or(anyhow::anyhow!("Market {} not loaded", position.market))?;
+        let quote_price = usdc_price(
+            &market.quote_mint(),
+            &vault.usdc_mint,
+            markets,
+            price_source,
+        )?;
+        positions.push(value_position(
+            market,
+            &vault.pubkey,
+            price_source,
+            quote_price,
+            max_slippage,
+        )?);
+    }
+    Ok(VaultValuation {
+        usdc_mint: vault.usdc_mint,
+        usdc: usdc_balance as f64 / QUOTE_PRECISION as f64,
+        sol_mint: vault.sol_mint,
+        sol: sol_balance as f64 / 10_f64.powi(SOL_DECIMALS as i32),
+        sol_price: usdc_price(&vault.sol_mint, &vault.usdc_mint, markets, price_source)?,
+        positions,
+    })
+}
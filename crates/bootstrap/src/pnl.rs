@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+
+use crate::drift_vaults::SettlePnlRecord;
+use crate::vault_update::QUOTE_PRECISION;
+
+pub const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
+/// Vault equity in USDC at a unix timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EquitySnapshot {
+    pub ts: i64,
+    pub equity: f64,
+}
+
+/// USDC gained or lost over the period ending at `ts`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pnl {
+    pub ts: i64,
+    pub pnl: f64,
+}
+
+/// The largest peak to trough fall in equity.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Drawdown {
+    pub peak_ts: i64,
+    pub trough_ts: i64,
+    /// First time equity got back to the peak, `None` if it hasn't yet.
+    pub recovery_ts: Option<i64>,
+    /// USDC
+    pub amount: f64,
+    /// Fraction of the peak lost
+    pub depth: f64,
+    /// Seconds from the peak to recovery, or to the last snapshot if still under water.
+    pub duration: i64,
+}
+
+/// Risk adjusted performance of a [`VaultPnl`]. Rates are fractions, annualised where noted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PnlStats {
+    pub start_ts: Option<i64>,
+    pub end_ts: Option<i64>,
+    pub cumulative_pnl: f64,
+    pub total_return: Option<f64>,
+    pub annualized_volatility: Option<f64>,
+    pub sharpe_ratio: Option<f64>,
+    pub sortino_ratio: Option<f64>,
+    pub max_drawdown: Option<Drawdown>,
+    pub win_rate: Option<f64>,
+    pub high_water_mark: Option<f64>,
+}
+
+/// A vault's equity curve, oldest first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VaultPnl {
+    pub data: Vec<EquitySnapshot>,
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Sample standard deviation
+fn std_dev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = mean(values);
+    let variance =
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+impl VaultPnl {
+    pub fn from_equity_snapshots(mut snapshots: Vec<EquitySnapshot>) -> Self {
+        snapshots.sort_by_key(|s| s.ts);
+        Self { data: snapshots }
+    }
+
+    /// Replays settled pnl on top of `starting_equity` (USDC), which is the equity before the first record.
+    pub fn from_settle_pnl_records(records: &[SettlePnlRecord], starting_equity: f64) -> Self {
+        let mut records = records.to_vec();
+        records.sort_by_key(|r| r.ts);
+        let mut equity = starting_equity;
+        let mut data = Vec::with_capacity(records.len() + 1);
+        if let Some(first) = records.first() {
+            data.push(EquitySnapshot {
+                ts: first.ts,
+                equity,
+            });
+        }
+        for record in records {
+            equity += record.pnl as f64 / QUOTE_PRECISION as f64;
+            data.push(EquitySnapshot {
+                ts: record.ts,
+                equity,
+            });
+        }
+        Self { data }
+    }
+
+    pub fn start_ts(&self) -> Option<i64> {
+        self.data.first().map(|s| s.ts)
+    }
+
+    pub fn end_ts(&self) -> Option<i64> {
+        self.data.last().map(|s| s.ts)
+    }
+
+    pub fn pnl_series(&self) -> Vec<Pnl> {
+        self.data
+            .windows(2)
+            .map(|w| Pnl {
+                ts: w[1].ts,
+                pnl: w[1].equity - w[0].equity,
+            })
+            .collect()
+    }
+
+    pub fn cumulative_pnl(&self) -> f64 {
+        match (self.data.first(), self.data.last()) {
+            (Some(first), Some(last)) => last.equity - first.equity,
+            _ => 0.0,
+        }
+    }
+
+    /// Simple return of each period, skipping periods that start with no equity.
+    pub fn returns(&self) -> Vec<f64> {
+        self.data
+            .windows(2)
+            .filter(|w| w[0].equity > 0.0)
+            .map(|w| w[1].equity / w[0].equity - 1.0)
+            .collect()
+    }
+
+    pub fn total_return(&self) -> Option<f64> {
+        let first = self.data.first()?;
+        let last = self.data.last()?;
+        if first.equity <= 0.0 {
+            return None;
+        }
+        Some(last.equity / first.equity - 1.0)
+    }
+
+    /// Periods per year implied by the average spacing of the snapshots.
+    fn periods_per_year(&self) -> Option<f64> {
+        let span = (self.end_ts()? - self.start_ts()?) as f64;
+        if span <= 0.0 || self.data.len() < 2 {
+            return None;
+        }
+        Some((self.data.len() - 1) as f64 * SECONDS_PER_YEAR / span)
+    }
+
+    pub fn annualized_volatility(&self) -> Option<f64> {
+        Some(std_dev(&self.returns())? * self.periods_per_year()?.sqrt())
+    }
+
+    /// `risk_free_rate` is annual.
+    pub fn sharpe_ratio(&self, risk_free_rate: f64) -> Option<f64> {
+        let returns = self.returns();
+        let periods = self.periods_per_year()?;
+        let volatility = std_dev(&returns)? * periods.sqrt();
+        if volatility == 0.0 {
+            return None;
+        }
+        Some((mean(&returns) * periods - risk_free_rate) / volatility)
+    }
+
+    /// Like [`Self::sharpe_ratio`] but only penalises returns below the risk free rate.
+    pub fn sortino_ratio(&self, risk_free_rate: f64) -> Option<f64> {
+        let returns = self.returns();
+        if returns.len() < 2 {
+            return None;
+        }
+        let periods = self.periods_per_year()?;
+        let target = risk_free_rate / periods;
+        let downside = returns
+            .iter()
+            .map(|r| (r - target).min(0.0).powi(2))
+            .sum::<f64>()
+            / returns.len() as f64;
+        if downside == 0.0 {
+            return None;
+        }
+        Some((mean(&returns) * periods - risk_free_rate) / (downside.sqrt() * periods.sqrt()))
+    }
+
+    pub fn max_drawdown(&self) -> Option<Drawdown> {
+        let mut peak = *self.data.first()?;
+        let mut max: Option<(EquitySnapshot, EquitySnapshot)> = None;
+        for snapshot in &self.data {
+            if snapshot.equity >= peak.equity {
+                peak = *snapshot;
+            } else if peak.equity > 0.0 {
+                let depth = 1.0 - snapshot.equity / peak.equity;
+                if max.is_none_or(|(p, t)| depth > 1.0 - t.equity / p.equity) {
+                    max = Some((peak, *snapshot));
+                }
+            }
+        }
+        let (peak, trough) = max?;
+        let recovery_ts = self
+            .data
+            .iter()
+            .find(|s| s.ts > trough.ts && s.equity >= peak.equity)
+            .map(|s| s.ts);
+        Some(Drawdown {
+            peak_ts: peak.ts,
+            trough_ts: trough.ts,
+            recovery_ts,
+            amount: peak.equity - trough.equity,
+            depth: 1.0 - trough.equity / peak.equity,
+            duration: recovery_ts.or(self.end_ts())? - peak.ts,
+        })
+    }
+
+    /// Fraction of periods with a gain, ignoring periods where equity didn't move.
+    pub fn win_rate(&self) -> Option<f64> {
+        let pnl: Vec<f64> = self
+            .pnl_series()
+            .into_iter()
+            .map(|p| p.pnl)
+            .filter(|p| *p != 0.0)
+            .collect();
+        if pnl.is_empty() {
+            return None;
+        }
+        Some(pnl.iter().filter(|p| **p > 0.0).count() as f64 / pnl.len() as f64)
+    }
+
+    /// Highest equity reached, in USDC.
+    pub fn high_water_mark(&self) -> Option<f64> {
+        self.data.iter().map(|s| s.equity).reduce(f64::max)
+    }
+
+    pub fn stats(&self, risk_free_rate: f64) -> PnlStats {
+        PnlStats {
+            start_ts: self.start_ts(),
+            end_ts: self.end_ts(),
+            cumulative_pnl: self.cumulative_pnl(),
+            total_return: self.total_return(),
+            annualized_volatility: self.annualized_volatility(),
+            sharpe_ratio: self.sharpe_ratio(risk_free_rate),
+            sortino_ratio: self.sortino_ratio(risk_free_rate),
+            max_drawdown: self.max_drawdown(),
+            win_rate: self.win_rate(),
+            high_water_mark: self.high_water_mark(),
+        }
+    }
+}
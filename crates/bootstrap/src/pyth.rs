@@ -0,0 +1,319 @@
+use anchor_lang::prelude::*;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::instruction::Instruction;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+
+use crate::helpers::{
+    get_account, get_multiple_accounts, instruction_data, send_and_confirm_tx, SignerPair,
+    SignerRef,
+};
+
+pub const PYTH_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+
+/// Size of a Pyth v2 `Price` account, which the mock program writes into as well.
+pub const PYTH_PRICE_ACCOUNT_SIZE: usize = 3312;
+
+const EXPO_OFFSET: usize = 20;
+/// `Price.agg`: price, conf, status, corp_act, pub_slot
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+const AGG_PUB_SLOT_OFFSET: usize = 232;
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> anyhow::Result<[u8; N]> {
+    let bytes = data
+        .get(offset..offset + N)
+        .ok_or(anyhow::anyhow!("Pyth price account too small"))?;
+    Ok(bytes.try_into()?)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceStatus {
+    Unknown,
+    Trading,
+    Halted,
+    Auction,
+}
+
+impl From<u32> for PriceStatus {
+    fn from(status: u32) -> Self {
+        match status {
+            1 => PriceStatus::Trading,
+            2 => PriceStatus::Halted,
+            3 => PriceStatus::Auction,
+            _ => PriceStatus::Unknown,
+        }
+    }
+}
+
+/// The aggregate price of a Pyth price account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PythPrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub status: PriceStatus,
+    pub publish_slot: u64,
+}
+
+/// Thresholds a price has to meet before it is used. `None` skips the check.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceChecks {
+    /// Slots since `publish_slot`.
+    pub max_staleness_slots: Option<u64>,
+    /// `conf / price`
+    pub max_confidence_ratio: Option<f64>,
+    /// The mock program never sets the status, so localnet feeds are `Unknown`, see [`PriceChecks::localnet`].
+    pub require_trading: bool,
+}
+
+impl Default for PriceChecks {
+    fn default() -> Self {
+        Self {
+            max_staleness_slots: Some(25),
+            max_confidence_ratio: Some(0.02),
+            require_trading: true,
+        }
+    }
+}
+
+impl PriceChecks {
+    /// Checks the mock feeds on localnet can pass. They are never `Trading`, and only
+    /// [`set_mock_price_info`] stamps a publish slot, so status and staleness are skipped.
+    pub fn localnet() -> Self {
+        Self {
+            max_staleness_slots: None,
+            require_trading: false,
+            ..Default::default()
+        }
+    }
+}
+
+impl PythPrice {
+    pub fn decode(data: &[u8]) -> anyhow::Result<Self> {
+        Ok(Self {
+            price: i64::from_le_bytes(read_bytes(data, AGG_PRICE_OFFSET)?),
+            conf: u64::from_le_bytes(read_bytes(data, AGG_CONF_OFFSET)?),
+            expo: i32::from_le_bytes(read_bytes(data, EXPO_OFFSET)?),
+            status: u32::from_le_bytes(read_bytes(data, AGG_STATUS_OFFSET)?).into(),
+            publish_slot: u64::from_le_bytes(read_bytes(data, AGG_PUB_SLOT_OFFSET)?),
+        })
+    }
+
+    pub fn ui_price(&self) -> f64 {
+        self.price as f64 * 10_f64.powi(self.expo)
+    }
+
+    pub fn ui_conf(&self) -> f64 {
+        self.conf as f64 * 10_f64.powi(self.expo)
+    }
+
+    /// Returns the UI price if it passes `checks` at `current_slot`.
+    pub fn checked_price(&self, current_slot: u64, checks: &PriceChecks) -> anyhow::Result<f64> {
+        if checks.require_trading && self.status != PriceStatus::Trading {
+            return Err(anyhow::anyhow!(
+                "Pyth price is not trading: {:?}",
+                self.status
+            ));
+        }
+        if self.price <= 0 {
+            return Err(anyhow::anyhow!(
+                "Pyth price is not positive: {}",
+                self.price
+            ));
+        }
+        if let Some(max_staleness) = checks.max_staleness_slots {
+            let staleness = current_slot.saturating_sub(self.publish_slot);
+            if staleness > max_staleness {
+                return Err(anyhow::anyhow!(
+                    "Pyth price is stale: published {} slots ago, max {}",
+                    staleness,
+                    max_staleness
+                ));
+            }
+        }
+        if let Some(max_ratio) = checks.max_confidence_ratio {
+            let ratio = self.conf as f64 / self.price as f64;
+            if ratio > max_ratio {
+                return Err(anyhow::anyhow!(
+                    "Pyth confidence too wide: {} of price, max {}",
+                    ratio,
+                    max_ratio
+                ));
+            }
+        }
+        Ok(self.ui_price())
+    }
+}
+
+pub async fn fetch_pyth_price(client: &RpcClient, oracle: &Pubkey) -> anyhow::Result<PythPrice> {
+    let account = get_account(client, oracle).await?;
+    PythPrice::decode(&account.data)
+}
+
+/// UI prices for each of `oracles` in one batch, each checked against the current slot.
+pub async fn fetch_checked_prices(
+    client: &RpcClient,
+    oracles: &[Pubkey],
+    checks: &PriceChecks,
+) -> anyhow::Result<Vec<f64>> {
+    let slot = client
+        .get_slot_with_commitment(CommitmentConfig::processed())
+        .await?;
+    let accounts = get_multiple_accounts(client, oracles).await?;
+    oracles
+        .iter()
+        .zip(accounts)
+        .map(|(oracle, account)| {
+            let account = account.ok_or(anyhow::anyhow!("Oracle not found: {:?}", oracle))?;
+            PythPrice::decode(&account.data)?
+                .checked_price(slot, checks)
+                .map_err(|e| anyhow::anyhow!("Oracle {}: {:?}", oracle, e))
+        })
+        .collect()
+}
+
+//
+// Mock Pyth program loaded on localnet
+//
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+struct InitializeParams {
+    price: i64,
+    expo: i32,
+    conf: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+struct SetPriceInfoParams {
+    price: i64,
+    conf: u64,
+    slot: u64,
+}
+
+fn mock_ix(price_account: &Pubkey, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: PYTH_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(*price_account, false)],
+        data,
+    }
+}
+
+pub fn mock_initialize_ix(
+    price_account: &Pubkey,
+    price: i64,
+    expo: i32,
+    conf: u64,
+) -> anyhow::Result<Instruction> {
+    let params = InitializeParams { price, expo, conf };
+    Ok(mock_ix(
+        price_account,
+        instruction_data([175, 175, 109, 31, 13, 152, 155, 237], &params)?,
+    ))
+}
+
+pub fn mock_set_price_ix(price_account: &Pubkey, price: i64) -> anyhow::Result<Instruction> {
+    Ok(mock_ix(
+        price_account,
+        instruction_data([16, 19, 182, 8, 149, 83, 72, 181], &price)?,
+    ))
+}
+
+pub fn mock_set_price_info_ix(
+    price_account: &Pubkey,
+    price: i64,
+    conf: u64,
+    slot: u64,
+) -> anyhow::Result<Instruction> {
+    let params = SetPriceInfoParams { price, conf, slot };
+    Ok(mock_ix(
+        price_account,
+        instruction_data([52, 225, 243, 132, 19, 89, 254, 181], &params)?,
+    ))
+}
+
+pub fn mock_set_twap_ix(price_account: &Pubkey, twap: i64) -> anyhow::Result<Instruction> {
+    Ok(mock_ix(
+        price_account,
+        instruction_data([10, 194, 43, 204, 120, 214, 177, 206], &twap)?,
+    ))
+}
+
+fn to_raw_price(ui_price: f64, expo: i32) -> i64 {
+    (ui_price * 10_f64.powi(-expo)).round() as i64
+}
+
+/// What [`create_mock_price_feed`] initializes a feed to: `ui_price` with confidence at 1% of the price,
+/// inside the default `max_confidence_ratio`. The mock program leaves status and publish slot unset.
+pub fn mock_price(ui_price: f64, expo: i32) -> PythPrice {
+    let price = to_raw_price(ui_price, expo);
+    PythPrice {
+        price,
+        conf: (price / 100).unsigned_abs(),
+        expo,
+        status: PriceStatus::Unknown,
+        publish_slot: 0,
+    }
+}
+
+/// Creates a mock price feed at `ui_price`, see [`mock_price`]. It passes [`PriceChecks::localnet`].
+pub async fn create_mock_price_feed<P: Signer + ?Sized, F: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &P,
+    feed: &F,
+    ui_price: f64,
+    expo: i32,
+) -> anyhow::Result<Signature> {
+    let price = mock_price(ui_price, expo);
+    let lamports = client
+        .get_minimum_balance_for_rent_exemption(PYTH_PRICE_ACCOUNT_SIZE)
+        .await?;
+    let ixs = vec![
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &feed.pubkey(),
+            lamports,
+            PYTH_PRICE_ACCOUNT_SIZE as u64,
+            &PYTH_PROGRAM_ID,
+        ),
+        mock_initialize_ix(&feed.pubkey(), price.price, expo, price.conf)?,
+    ];
+    send_and_confirm_tx(client, payer, &ixs, &SignerPair(payer, feed)).await
+}
+
+/// Moves a mock feed to `ui_price`, keeping its exponent.
+pub async fn set_mock_price<S: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &S,
+    feed: &Pubkey,
+    ui_price: f64,
+) -> anyhow::Result<Signature> {
+    let current = fetch_pyth_price(client, feed).await?;
+    let ix = mock_set_price_ix(feed, to_raw_price(ui_price, current.expo))?;
+    send_and_confirm_tx(client, payer, &[ix], &[&SignerRef(payer)]).await
+}
+
+/// Sets price and confidence and stamps the feed with the current slot so it passes staleness checks.
+pub async fn set_mock_price_info<S: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &S,
+    feed: &Pubkey,
+    ui_price: f64,
+    ui_conf: f64,
+) -> anyhow::Result<Signature> {
+    let current = fetch_pyth_price(client, feed).await?;
+    let slot = client
+        .get_slot_with_commitment(CommitmentConfig::processed())
+        .await?;
+    let ix = mock_set_price_info_ix(
+        feed,
+        to_raw_price(ui_price, current.expo),
+        to_raw_price(ui_conf, current.expo).unsigned_abs(),
+        slot,
+    )?;
+    send_and_confirm_tx(client, payer, &[ix], &[&SignerRef(payer)]).await
+}
@@ -0,0 +1,214 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use bip39::{Language, Mnemonic};
+use solana_sdk::derivation_path::DerivationPath;
+use solana_sdk::signature::{
+    generate_seed_from_seed_phrase_and_passphrase, keypair_from_seed_and_derivation_path,
+    keypair_from_seed_phrase_and_passphrase, read_keypair_file, Keypair,
+};
+use solana_sdk::signer::Signer;
+
+/// The derivation path Phantom, Solflare and `solana-keygen` with `?key=0/0` use.
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/501'/0'/0'";
+
+/// Where to load a keypair from.
+///
+/// Parsed from a string:
+/// - `default` or the empty string: `~/.config/solana/id.json`
+/// - `env:NAME`: the value of an environment variable, in any of the inline formats below
+/// - `mnemonic:<words>`: a BIP39 seed phrase, optionally followed by `?key=0/0`
+///   or `?path=m/44'/501'/0'/0'`. Without either it is the `solana-keygen` legacy key.
+/// - `[1,2,...]`: the 64 secret key bytes, as in a Solana CLI keypair file
+/// - a path to a Solana CLI keypair file, `~` expanded
+/// - otherwise a base58 encoded secret key, as exported by wallets
+///
+/// `Debug` redacts the secret keys, seed phrase and passphrase.
+#[derive(Clone, PartialEq, Eq)]
+pub enum SignerSource {
+    Default,
+    File(PathBuf),
+    Bytes(Vec<u8>),
+    Base58(String),
+    Env(String),
+    Mnemonic {
+        phrase: String,
+        passphrase: String,
+        derivation_path: Option<DerivationPath>,
+    },
+}
+
+impl fmt::Debug for SignerSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const REDACTED: &str = "<redacted>";
+        match self {
+            SignerSource::Default => write!(f, "Default"),
+            SignerSource::File(path) => f.debug_tuple("File").field(path).finish(),
+            SignerSource::Bytes(_) => f.debug_tuple("Bytes").field(&REDACTED).finish(),
+            SignerSource::Base58(_) => f.debug_tuple("Base58").field(&REDACTED).finish(),
+            SignerSource::Env(name) => f.debug_tuple("Env").field(name).finish(),
+            SignerSource::Mnemonic {
+                derivation_path, ..
+            } => f
+                .debug_struct("Mnemonic")
+                .field("phrase", &REDACTED)
+                .field("passphrase", &REDACTED)
+                .field("derivation_path", derivation_path)
+                .finish(),
+        }
+    }
+}
+
+pub fn default_keypair_path() -> anyhow::Result<PathBuf> {
+    let home = std::env::var("HOME").map_err(|_| anyhow::anyhow!("HOME is not set"))?;
+    Ok(PathBuf::from(home).join(".config/solana/id.json"))
+}
+
+fn expand_home(path: &str) -> anyhow::Result<PathBuf> {
+    match path.strip_prefix("~/") {
+        Some(rest) => {
+            let home = std::env::var("HOME").map_err(|_| anyhow::anyhow!("HOME is not set"))?;
+            Ok(Path::new(&home).join(rest))
+        }
+        None => Ok(PathBuf::from(path)),
+    }
+}
+
+/// Splits `words?key=0/0` or `words?path=m/...` into the phrase and its derivation path.
+fn parse_mnemonic(s: &str) -> anyhow::Result<(String, Option<DerivationPath>)> {
+    let (phrase, query) = match s.split_once('?') {
+        Some((phrase, query)) => (phrase, Some(query)),
+        None => (s, None),
+    };
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    let derivation_path = match query.map(|q| q.split_once('=')) {
+        None => None,
+        Some(Some(("key", key))) => Some(
+            DerivationPath::from_key_str(key)
+                .map_err(|e| anyhow::anyhow!("Invalid derivation key {}: {:?}", key, e))?,
+        ),
+        Some(Some(("path", path))) => Some(
+            DerivationPath::from_absolute_path_str(path)
+                .map_err(|e| anyhow::anyhow!("Invalid derivation path {}: {:?}", path, e))?,
+        ),
+        Some(_) => {
+            return Err(anyhow::anyhow!(
+                "Expected ?key= or ?path= after the seed phrase"
+            ))
+        }
+    };
+    Ok((phrase, derivation_path))
+}
+
+/// Inline formats, shared by strings and environment variables.
+fn parse_inline(s: &str) -> anyhow::Result<SignerSource> {
+    let s = s.trim();
+    if let Some(rest) = s.strip_prefix("mnemonic:") {
+        let (phrase, derivation_path) = parse_mnemonic(rest)?;
+        return Ok(SignerSource::Mnemonic {
+            phrase,
+            passphrase: String::new(),
+            derivation_path,
+        });
+    }
+    if s.starts_with('[') {
+        let bytes: Vec<u8> = serde_json::from_str(s)
+            .map_err(|e| anyhow::anyhow!("Invalid keypair bytes: {:?}", e))?;
+        return Ok(SignerSource::Bytes(bytes));
+    }
+    Ok(SignerSource::Base58(s.to_string()))
+}
+
+impl FromStr for SignerSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let s = s.trim();
+        if s.is_empty() || s == "default" {
+            return Ok(SignerSource::Default);
+        }
+        if let Some(name) = s.strip_prefix("env:") {
+            return Ok(SignerSource::Env(name.to_string()));
+        }
+        if s.starts_with("mnemonic:") || s.starts_with('[') {
+            return parse_inline(s);
+        }
+        let path = expand_home(s)?;
+        if path.exists() || s.ends_with(".json") {
+            return Ok(SignerSource::File(path));
+        }
+        parse_inline(s)
+    }
+}
+
+impl SignerSource {
+    pub fn load_keypair(&self) -> anyhow::Result<Keypair> {
+        match self {
+            SignerSource::Default => SignerSource::File(default_keypair_path()?).load_keypair(),
+            SignerSource::File(path) => read_keypair_file(path)
+                .map_err(|e| anyhow::anyhow!("Failed to read keypair {}: {:?}", path.display(), e)),
+            SignerSource::Bytes(bytes) => Keypair::from_bytes(bytes)
+                .map_err(|e| anyhow::anyhow!("Invalid keypair bytes: {:?}", e)),
+            SignerSource::Base58(s) => {
+                let bytes = bs58::decode(s)
+                    .into_vec()
+                    .map_err(|e| anyhow::anyhow!("Invalid base58 keypair: {:?}", e))?;
+                SignerSource::Bytes(bytes).load_keypair()
+            }
+            SignerSource::Env(name) => {
+                let value = std::env::var(name)
+                    .map_err(|_| anyhow::anyhow!("Environment variable {} is not set", name))?;
+                parse_inline(&value)?.load_keypair()
+            }
+            SignerSource::Mnemonic {
+                phrase,
+                passphrase,
+                derivation_path,
+            } => keypair_from_mnemonic(phrase, passphrase, derivation_path.clone()),
+        }
+    }
+
+    pub fn load(&self) -> anyhow::Result<Box<dyn Signer + Send + Sync>> {
+        Ok(Box::new(self.load_keypair()?))
+    }
+}
+
+/// BIP39 seed phrase to keypair. Without a derivation path this is the key `solana-keygen`
+/// derives from the bare seed, with one it is the BIP32 key wallets use. The phrase is checked
+/// against the English wordlist and its checksum first, since any phrase derives some key and
+/// a typo would otherwise load a different one.
+pub fn keypair_from_mnemonic(
+    phrase: &str,
+    passphrase: &str,
+    derivation_path: Option<DerivationPath>,
+) -> anyhow::Result<Keypair> {
+    let words = phrase.split_whitespace().count();
+    if ![12, 15, 18, 21, 24].contains(&words) {
+        return Err(anyhow::anyhow!(
+            "Seed phrase has {} words, expected 12, 15, 18, 21 or 24",
+            words
+        ));
+    }
+    Mnemonic::from_phrase(phrase, Language::English)
+        .map_err(|e| anyhow::anyhow!("Invalid seed phrase: {}", e))?;
+    match derivation_path {
+        None => keypair_from_seed_phrase_and_passphrase(phrase, passphrase),
+        Some(path) => {
+            let seed = generate_seed_from_seed_phrase_and_passphrase(phrase, passphrase);
+            keypair_from_seed_and_derivation_path(&seed, Some(path))
+        }
+    }
+    .map_err(|e| anyhow::anyhow!("Failed to derive keypair from seed phrase: {:?}", e))
+}
+
+/// Loads the keypair `source` describes, see [`SignerSource`].
+pub fn load_keypair(source: &str) -> anyhow::Result<Keypair> {
+    SignerSource::from_str(source)?.load_keypair()
+}
+
+/// Loads the signer `source` describes, see [`SignerSource`]. It is `Sync` so it can be passed
+/// to [`crate::fund::Fund`] methods.
+pub fn load_signer(source: &str) -> anyhow::Result<Box<dyn Signer + Send + Sync>> {
+    SignerSource::from_str(source)?.load()
+}
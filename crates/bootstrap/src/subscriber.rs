@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anchor_lang::Discriminator;
+use futures::StreamExt;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::{hash, Hash};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::drift_vaults::{self, VaultDepositor, DRIFT_VAULTS_PROGRAM_ID};
+use crate::helpers::decode_account;
+use crate::phoenix_vaults::{self, Investor, PHOENIX_VAULTS_PROGRAM_ID};
+
+/// A decoded `Vault`, `VaultDepositor` or `Investor` account.
+#[derive(Debug, Clone)]
+pub enum VaultAccount {
+    DriftVault(Box<drift_vaults::Vault>),
+    VaultDepositor(VaultDepositor),
+    PhoenixVault(Box<phoenix_vaults::Vault>),
+    Investor(Investor),
+}
+
+impl VaultAccount {
+    /// `None` for accounts of other programs or types.
+    pub fn decode(owner: &Pubkey, data: &[u8]) -> Option<Self> {
+        let disc: [u8; 8] = data.get(..8)?.try_into().ok()?;
+        if *owner == DRIFT_VAULTS_PROGRAM_ID {
+            match disc {
+                drift_vaults::Vault::DISCRIMINATOR => decode_account(data)
+                    .ok()
+                    .map(|v| VaultAccount::DriftVault(Box::new(v))),
+                VaultDepositor::DISCRIMINATOR => {
+                    decode_account(data).ok().map(VaultAccount::VaultDepositor)
+                }
+                _ => None,
+            }
+        } else if *owner == PHOENIX_VAULTS_PROGRAM_ID {
+            match disc {
+                phoenix_vaults::Vault::DISCRIMINATOR => decode_account(data)
+                    .ok()
+                    .map(|v| VaultAccount::PhoenixVault(Box::new(v))),
+                Investor::DISCRIMINATOR => decode_account(data).ok().map(VaultAccount::Investor),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountUpdate {
+    pub pubkey: Pubkey,
+    pub slot: u64,
+    pub account: VaultAccount,
+}
+
+/// A failed poll or dropped connection. Subscribers retry on their own, this only reports it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriberError {
+    /// The program or market being followed.
+    pub key: Pubkey,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionMode {
+    /// `programSubscribe`, polling while the websocket is down.
+    Websocket,
+    /// `getProgramAccounts` every `poll_interval`.
+    Polling,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubscriberConfig {
+    pub rpc_url: String,
+    pub ws_url: String,
+    pub programs: Vec<Pubkey>,
+    pub mode: SubscriptionMode,
+    /// Between polls, and between websocket reconnect attempts.
+    pub poll_interval: Duration,
+    pub commitment: CommitmentConfig,
+    /// Updates a receiver can fall behind by before it starts missing them.
+    pub channel_capacity: usize,
+}
+
+impl SubscriberConfig {
+    pub fn new(rpc_url: &str, ws_url: &str) -> Self {
+        Self {
+            rpc_url: rpc_url.to_string(),
+            ws_url: ws_url.to_string(),
+            programs: vec![DRIFT_VAULTS_PROGRAM_ID, PHOENIX_VAULTS_PROGRAM_ID],
+            mode: SubscriptionMode::Websocket,
+            poll_interval: Duration::from_secs(10),
+            commitment: CommitmentConfig::confirmed(),
+            channel_capacity: 1_024,
+        }
+    }
+}
+
+/// Tracks every vault account of `config.programs` and broadcasts each change once.
+pub struct VaultSubscriber {
+    pub config: SubscriberConfig,
+    /// Slot and data hash of the last update broadcast for each account.
+    seen: Mutex<HashMap<Pubkey, (u64, Hash)>>,
+    sender: broadcast::Sender<AccountUpdate>,
+    errors: broadcast::Sender<SubscriberError>,
+}
+
+impl VaultSubscriber {
+    pub fn new(config: SubscriberConfig) -> Self {
+        let (sender, _) = broadcast::channel(config.channel_capacity);
+        let (errors, _) = broadcast::channel(config.channel_capacity);
+        Self {
+            config,
+            seen: Mutex::new(HashMap::new()),
+            sender,
+            errors,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AccountUpdate> {
+        self.sender.subscribe()
+    }
+
+    /// Errors [`Self::run_program`] recovered from.
+    pub fn subscribe_errors(&self) -> broadcast::Receiver<SubscriberError> {
+        self.errors.subscribe()
+    }
+
+    fn report(&self, key: &Pubkey, error: anyhow::Error) {
+        // like updates, errors nobody listens for are dropped
+        let _ = self.errors.send(SubscriberError {
+            key: *key,
+            error: format!("{:?}", error),
+        });
+    }
+
+    /// Broadcasts `account` unless it was already seen at the same or a newer slot, or its data
+    /// hasn't changed. Returns whether it was broadcast.
+    pub fn publish(&self, pubkey: Pubkey, slot: u64, account: &Account) -> bool {
+        let decoded = match VaultAccount::decode(&account.owner, &account.data) {
+            Some(decoded) => decoded,
+            None => return false,
+        };
+        let data_hash = hash(&account.data);
+        {
+            let mut seen = self.seen.lock().unwrap();
+            match seen.get(&pubkey) {
+                Some((last_slot, last_hash)) if *last_slot >= slot || *last_hash == data_hash => {
+                    return false
+                }
+                _ => seen.insert(pubkey, (slot, data_hash)),
+            };
+        }
+        // no receivers is not an error, updates are simply dropped
+        let _ = self.sender.send(AccountUpdate {
+            pubkey,
+            slot,
+            account: decoded,
+        });
+        true
+    }
+
+    fn program_config(&self) -> RpcProgramAccountsConfig {
+        RpcProgramAccountsConfig {
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.config.commitment),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Loads every account of `program` with `getProgramAccounts`. Returns how many changed.
+    pub async fn poll_program(
+        &self,
+        client: &RpcClient,
+        program: &Pubkey,
+    ) -> anyhow::Result<usize> {
+        // a lower bound on the slot the accounts are read at
+        let slot = client
+            .get_slot_with_commitment(self.config.commitment)
+            .await?;
+        let accounts = client
+            .get_program_accounts_with_config(program, self.program_config())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to poll {}: {:?}", program, e))?;
+        Ok(accounts
+            .into_iter()
+            .filter(|(pubkey, account)| self.publish(*pubkey, slot, account))
+            .count())
+    }
+
+    /// Forwards `programSubscribe` notifications until the websocket closes.
+    async fn stream_program(&self, pubsub: &PubsubClient, program: &Pubkey) -> anyhow::Result<()> {
+        let (mut stream, unsubscribe) = pubsub
+            .program_subscribe(program, Some(self.program_config()))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to subscribe to {}: {:?}", program, e))?;
+        while let Some(response) = stream.next().await {
+            let pubkey = Pubkey::from_str(&response.value.pubkey)?;
+            if let Some(account) = response.value.account.decode::<Account>() {
+                self.publish(pubkey, response.context.slot, &account);
+            }
+        }
+        unsubscribe().await;
+        Err(anyhow::anyhow!("Subscription to {} closed", program))
+    }
+
+    /// Runs forever, reconnecting the websocket and polling to catch up on anything missed.
+    pub async fn run_program(&self, program: Pubkey) {
+        let client =
+            RpcClient::new_with_commitment(self.config.rpc_url.clone(), self.config.commitment);
+        loop {
+            if let Err(e) = self.poll_program(&client, &program).await {
+                self.report(&program, e);
+            }
+            if self.config.mode == SubscriptionMode::Websocket {
+                match PubsubClient::new(&self.config.ws_url).await {
+                    Ok(pubsub) => {
+                        if let Err(e) = self.stream_program(&pubsub, &program).await {
+                            self.report(&program, e);
+                        }
+                    }
+                    Err(e) => self.report(
+                        &program,
+                        anyhow::anyhow!("Websocket unavailable, polling: {:?}", e),
+                    ),
+                }
+            }
+            tokio::time::sleep(self.config.poll_interval).await;
+        }
+    }
+
+    /// Spawns [`Self::run_program`] for each program.
+    pub fn spawn(self: &Arc<Self>) -> Vec<JoinHandle<()>> {
+        self.config
+            .programs
+            .iter()
+            .map(|program| {
+                let subscriber = self.clone();
+                let program = *program;
+                tokio::spawn(async move { subscriber.run_program(program).await })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,122 @@
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use solana_program::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use spl_token_2022::extension::{interest_bearing_mint, metadata_pointer, transfer_fee};
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use spl_token_2022::state::{Account, Mint};
+
+/// Mint extensions [`crate::helpers::create_token_2022_mint`] can initialize. Each one must be
+/// set up before the mint itself, and sizes the mint account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MintExtension {
+    /// Withholds `basis_points` of every transfer, capped at `maximum_fee` atoms.
+    TransferFee {
+        authority: Option<Pubkey>,
+        basis_points: u16,
+        maximum_fee: u64,
+    },
+    /// Points wallets and explorers at the account holding the mint's metadata, usually the
+    /// mint itself.
+    MetadataPointer {
+        authority: Option<Pubkey>,
+        metadata: Option<Pubkey>,
+    },
+    /// Displays balances with interest accrued continuously at `rate` basis points a year.
+    InterestBearing {
+        rate_authority: Option<Pubkey>,
+        rate: i16,
+    },
+}
+
+impl MintExtension {
+    pub fn extension_type(&self) -> ExtensionType {
+        match self {
+            MintExtension::TransferFee { .. } => ExtensionType::TransferFeeConfig,
+            MintExtension::MetadataPointer { .. } => ExtensionType::MetadataPointer,
+            MintExtension::InterestBearing { .. } => ExtensionType::InterestBearingConfig,
+        }
+    }
+
+    pub fn initialize_ix(&self, mint: &Pubkey) -> anyhow::Result<Instruction> {
+        let program = spl_token_2022::id();
+        let ix = match *self {
+            MintExtension::TransferFee {
+                authority,
+                basis_points,
+                maximum_fee,
+            } => transfer_fee::instruction::initialize_transfer_fee_config(
+                &program,
+                mint,
+                authority.as_ref(),
+                authority.as_ref(),
+                basis_points,
+                maximum_fee,
+            ),
+            MintExtension::MetadataPointer {
+                authority,
+                metadata,
+            } => metadata_pointer::instruction::initialize(&program, mint, authority, metadata),
+            MintExtension::InterestBearing {
+                rate_authority,
+                rate,
+            } => {
+                interest_bearing_mint::instruction::initialize(&program, mint, rate_authority, rate)
+            }
+        };
+        ix.map_err(|e| anyhow::anyhow!("Failed to build {:?} instruction: {:?}", self, e))
+    }
+}
+
+/// Size of a Token-2022 mint with `extensions`, which is [`Mint::LEN`] without any.
+pub fn mint_len(extensions: &[MintExtension]) -> anyhow::Result<usize> {
+    let types: Vec<ExtensionType> = extensions.iter().map(|e| e.extension_type()).collect();
+    ExtensionType::try_calculate_account_len::<Mint>(&types)
+        .map_err(|e| anyhow::anyhow!("Failed to size mint for {:?}: {:?}", types, e))
+}
+
+/// Space reserved for the `TokenMetadata` entry of a mint whose metadata pointer points at
+/// itself: the 12 byte TLV header, update authority and mint, a name, symbol and uri of up to
+/// Metaplex's 32, 10 and 200 bytes behind 4 byte length prefixes, and no additional fields.
+pub const MINT_METADATA_RESERVE_LEN: usize = 12 + 32 + 32 + (4 + 32) + (4 + 10) + (4 + 200) + 4;
+
+/// Space the rent of `mint` must cover: [`mint_len`], plus [`MINT_METADATA_RESERVE_LEN`] if a
+/// metadata pointer points at the mint. Token-2022 grows the mint when its metadata is written
+/// but doesn't fund the extra rent, so it is paid up front.
+pub fn mint_rent_len(mint: &Pubkey, extensions: &[MintExtension]) -> anyhow::Result<usize> {
+    let self_described = extensions.iter().any(|e| {
+        matches!(e, MintExtension::MetadataPointer { metadata: Some(metadata), .. } if metadata == mint)
+    });
+    let len = mint_len(extensions)?;
+    Ok(if self_described {
+        len + MINT_METADATA_RESERVE_LEN
+    } else {
+        len
+    })
+}
+
+pub fn is_token_program(program: &Pubkey) -> bool {
+    *program == spl_token::id() || *program == spl_token_2022::id()
+}
+
+/// Unpacks a mint of either token program. Token-2022 extensions are skipped, the base layout
+/// is the same.
+pub fn unpack_mint(data: &[u8]) -> anyhow::Result<Mint> {
+    StateWithExtensions::<Mint>::unpack(data)
+        .map(|state| state.base)
+        .map_err(|e| anyhow::anyhow!("Failed to unpack mint: {:?}", e))
+}
+
+/// Unpacks a token account of either token program, see [`unpack_mint`].
+pub fn unpack_token_account(data: &[u8]) -> anyhow::Result<Account> {
+    StateWithExtensions::<Account>::unpack(data)
+        .map(|state| state.base)
+        .map_err(|e| anyhow::anyhow!("Failed to unpack token account: {:?}", e))
+}
+
+/// Extensions initialized on a Token-2022 mint, empty for an SPL Token mint.
+pub fn mint_extension_types(data: &[u8]) -> anyhow::Result<Vec<ExtensionType>> {
+    StateWithExtensions::<Mint>::unpack(data)
+        .and_then(|state| state.get_extension_types())
+        .map_err(|e| anyhow::anyhow!("Failed to unpack mint: {:?}", e))
+}
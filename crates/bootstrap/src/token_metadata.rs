@@ -0,0 +1,188 @@
+use anchor_lang::prelude::*;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::instruction::Instruction;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+use crate::helpers::{send_and_confirm_tx, SignerPair};
+
+/// The Metaplex token metadata program, which wallets and explorers read SPL Token names from.
+/// `anchor localnet` clones it from mainnet, see `Anchor.toml`. A bare `solana-test-validator`
+/// doesn't load it.
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_URI_LENGTH: usize = 200;
+
+pub fn get_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// Name, symbol and URI of a mint, as wallets display it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MintMetadata {
+    pub name: String,
+    pub symbol: String,
+    /// JSON with the image and description, may be empty.
+    #[serde(default)]
+    pub uri: String,
+}
+
+impl MintMetadata {
+    pub fn new(name: &str, symbol: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            uri: String::new(),
+        }
+    }
+
+    /// The program rejects fields longer than these byte limits.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (field, value, max) in [
+            ("name", &self.name, MAX_NAME_LENGTH),
+            ("symbol", &self.symbol, MAX_SYMBOL_LENGTH),
+            ("uri", &self.uri, MAX_URI_LENGTH),
+        ] {
+            if value.len() > max {
+                return Err(anyhow::anyhow!(
+                    "Metadata {} {:?} is longer than {} bytes",
+                    field,
+                    value,
+                    max
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    /// Percentage of royalties
+    pub share: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Collection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum UseMethod {
+    Burn,
+    Multiple,
+    Single,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Uses {
+    pub use_method: UseMethod,
+    pub remaining: u64,
+    pub total: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum CollectionDetails {
+    V1 { size: u64 },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DataV2 {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<Creator>>,
+    pub collection: Option<Collection>,
+    pub uses: Option<Uses>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CreateMetadataAccountArgsV3 {
+    pub data: DataV2,
+    pub is_mutable: bool,
+    pub collection_details: Option<CollectionDetails>,
+}
+
+impl From<&MintMetadata> for CreateMetadataAccountArgsV3 {
+    fn from(metadata: &MintMetadata) -> Self {
+        Self {
+            data: DataV2 {
+                name: metadata.name.clone(),
+                symbol: metadata.symbol.clone(),
+                uri: metadata.uri.clone(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            },
+            is_mutable: true,
+            collection_details: None,
+        }
+    }
+}
+
+/// The program is native rather than Anchor, instructions start with a single byte tag.
+pub const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+
+/// Creates the metadata account of `mint`, which must be signed for by its mint authority.
+/// `update_authority` signs as well, so it can later edit the metadata.
+pub fn create_metadata_account_v3_ix(
+    mint: &Pubkey,
+    mint_authority: &Pubkey,
+    payer: &Pubkey,
+    update_authority: &Pubkey,
+    metadata: &MintMetadata,
+) -> Instruction {
+    let mut data = vec![CREATE_METADATA_ACCOUNT_V3];
+    CreateMetadataAccountArgsV3::from(metadata)
+        .serialize(&mut data)
+        .unwrap();
+    Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(get_metadata_address(mint).0, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*mint_authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*update_authority, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Attaches `metadata` to `mint`, with the mint authority as update authority.
+pub async fn create_mint_metadata<P: Signer + ?Sized, A: Signer + ?Sized>(
+    client: &RpcClient,
+    payer: &P,
+    mint_authority: &A,
+    mint: &Pubkey,
+    metadata: &MintMetadata,
+) -> anyhow::Result<Signature> {
+    metadata.validate()?;
+    let ix = create_metadata_account_v3_ix(
+        mint,
+        &mint_authority.pubkey(),
+        &payer.pubkey(),
+        &mint_authority.pubkey(),
+        metadata,
+    );
+    send_and_confirm_tx(client, payer, &[ix], &SignerPair(payer, mint_authority)).await
+}
@@ -0,0 +1,239 @@
+use std::fmt;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::amount::TokenAmount;
+
+/// Fees and profit shares are stored multiplied by this on chain.
+pub const PERCENTAGE_PRECISION: u64 = 1_000_000;
+/// USDC (and Drift spot market) token precision.
+pub const QUOTE_PRECISION: u64 = 1_000_000;
+
+/// Desired vault configuration in on-chain units. `None` leaves the current value untouched.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UpdateVaultConfig {
+    pub redeem_period: Option<i64>,
+    pub max_tokens: Option<u64>,
+    pub management_fee: Option<i64>,
+    pub min_deposit_amount: Option<u64>,
+    pub profit_share: Option<u32>,
+    pub hurdle_rate: Option<u32>,
+    pub permissioned: Option<bool>,
+    pub delegate: Option<Pubkey>,
+}
+
+/// The updatable fields of a vault as currently stored on chain. Token amounts are in atoms of
+/// the deposit mint, which has `deposit_decimals`.
+#[derive(Debug, Clone, Copy)]
+pub struct VaultSettings {
+    pub redeem_period: i64,
+    pub max_tokens: u64,
+    pub management_fee: i64,
+    pub min_deposit_amount: u64,
+    pub profit_share: u32,
+    pub hurdle_rate: u32,
+    pub permissioned: bool,
+    pub delegate: Pubkey,
+    pub total_withdraw_requested: u64,
+    pub deposit_decimals: u8,
+}
+
+/// Which fields a venue's `updateVault` accepts and which may only be lowered.
+#[derive(Debug, Clone, Copy)]
+pub struct UpdateRules {
+    pub max_tokens_decrease_only: bool,
+    pub min_deposit_decrease_only: bool,
+    pub supports_delegate: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+/// The minimal set of changes needed to reach the desired config.
+#[derive(Debug, Clone, Default)]
+pub struct VaultUpdatePlan {
+    pub update: UpdateVaultConfig,
+    pub changes: Vec<FieldChange>,
+}
+
+impl VaultUpdatePlan {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for VaultUpdatePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return writeln!(f, "vault is already up to date");
+        }
+        let width = self
+            .changes
+            .iter()
+            .map(|c| c.field.len())
+            .max()
+            .unwrap_or(0);
+        for change in &self.changes {
+            writeln!(
+                f,
+                "{:width$}  {} -> {}",
+                change.field,
+                change.before,
+                change.after,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn percent(value: i64) -> String {
+    format!("{}%", value as f64 / PERCENTAGE_PRECISION as f64 * 100.0)
+}
+
+fn tokens(atoms: u64, decimals: u8) -> String {
+    TokenAmount::new(atoms, decimals).to_string()
+}
+
+fn duration(seconds: i64) -> String {
+    format!("{}s ({:.2} days)", seconds, seconds as f64 / 86_400.0)
+}
+
+fn lower_only<T: PartialOrd + fmt::Debug>(
+    field: &str,
+    current: T,
+    desired: T,
+) -> anyhow::Result<()> {
+    if desired > current {
+        return Err(anyhow::anyhow!(
+            "{} can only be lowered: {:?} -> {:?}",
+            field,
+            current,
+            desired
+        ));
+    }
+    Ok(())
+}
+
+/// Computes the minimal update from `current` to `desired`, rejecting changes the program forbids.
+pub fn plan_update(
+    current: &VaultSettings,
+    desired: &UpdateVaultConfig,
+    rules: UpdateRules,
+) -> anyhow::Result<VaultUpdatePlan> {
+    let mut plan = VaultUpdatePlan::default();
+
+    if let Some(redeem_period) = desired
+        .redeem_period
+        .filter(|v| *v != current.redeem_period)
+    {
+        lower_only("redeem_period", current.redeem_period, redeem_period)?;
+        if current.total_withdraw_requested > 0 {
+            return Err(anyhow::anyhow!(
+                "redeem_period cannot change while {} of withdrawals are pending",
+                tokens(current.total_withdraw_requested, current.deposit_decimals)
+            ));
+        }
+        plan.update.redeem_period = Some(redeem_period);
+        plan.changes.push(FieldChange {
+            field: "redeem_period",
+            before: duration(current.redeem_period),
+            after: duration(redeem_period),
+        });
+    }
+
+    if let Some(max_tokens) = desired.max_tokens.filter(|v| *v != current.max_tokens) {
+        if rules.max_tokens_decrease_only {
+            lower_only("max_tokens", current.max_tokens, max_tokens)?;
+        }
+        plan.update.max_tokens = Some(max_tokens);
+        plan.changes.push(FieldChange {
+            field: "max_tokens",
+            before: tokens(current.max_tokens, current.deposit_decimals),
+            after: tokens(max_tokens, current.deposit_decimals),
+        });
+    }
+
+    if let Some(management_fee) = desired
+        .management_fee
+        .filter(|v| *v != current.management_fee)
+    {
+        lower_only("management_fee", current.management_fee, management_fee)?;
+        plan.update.management_fee = Some(management_fee);
+        plan.changes.push(FieldChange {
+            field: "management_fee",
+            before: percent(current.management_fee),
+            after: percent(management_fee),
+        });
+    }
+
+    if let Some(min_deposit_amount) = desired
+        .min_deposit_amount
+        .filter(|v| *v != current.min_deposit_amount)
+    {
+        if rules.min_deposit_decrease_only {
+            lower_only(
+                "min_deposit_amount",
+                current.min_deposit_amount,
+                min_deposit_amount,
+            )?;
+        }
+        plan.update.min_deposit_amount = Some(min_deposit_amount);
+        plan.changes.push(FieldChange {
+            field: "min_deposit_amount",
+            before: tokens(current.min_deposit_amount, current.deposit_decimals),
+            after: tokens(min_deposit_amount, current.deposit_decimals),
+        });
+    }
+
+    if let Some(profit_share) = desired.profit_share.filter(|v| *v != current.profit_share) {
+        lower_only("profit_share", current.profit_share, profit_share)?;
+        plan.update.profit_share = Some(profit_share);
+        plan.changes.push(FieldChange {
+            field: "profit_share",
+            before: percent(current.profit_share as i64),
+            after: percent(profit_share as i64),
+        });
+    }
+
+    // Both programs' update_vault check "new hurdle rate must be less than existing hurdle rate",
+    // and initialize_vault only accepts 0, so in practice it never changes.
+    if let Some(hurdle_rate) = desired.hurdle_rate.filter(|v| *v != current.hurdle_rate) {
+        lower_only("hurdle_rate", current.hurdle_rate, hurdle_rate)?;
+        plan.update.hurdle_rate = Some(hurdle_rate);
+        plan.changes.push(FieldChange {
+            field: "hurdle_rate",
+            before: percent(current.hurdle_rate as i64),
+            after: percent(hurdle_rate as i64),
+        });
+    }
+
+    if let Some(permissioned) = desired.permissioned.filter(|v| *v != current.permissioned) {
+        plan.update.permissioned = Some(permissioned);
+        plan.changes.push(FieldChange {
+            field: "permissioned",
+            before: current.permissioned.to_string(),
+            after: permissioned.to_string(),
+        });
+    }
+
+    if let Some(delegate) = desired.delegate.filter(|v| *v != current.delegate) {
+        if !rules.supports_delegate {
+            return Err(anyhow::anyhow!(
+                "delegate is not part of this venue's updateVault, rotate it with update_delegate"
+            ));
+        }
+        plan.update.delegate = Some(delegate);
+        plan.changes.push(FieldChange {
+            field: "delegate",
+            before: current.delegate.to_string(),
+            after: delegate.to_string(),
+        });
+    }
+
+    Ok(plan)
+}
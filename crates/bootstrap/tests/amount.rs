@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+use bootstrap::amount::*;
+
+#[test]
+fn ui_amounts_parse_exactly() -> anyhow::Result<()> {
+    // 0.29 * 1e6 as f64 truncates to 289_999.
+    assert_eq!(TokenAmount::parse("0.29", USDC_DECIMALS)?.atoms, 290_000);
+    assert_eq!(
+        TokenAmount::parse("1.000000001", SOL_DECIMALS)?.atoms,
+        1_000_000_001
+    );
+    assert_eq!(UiAmount::from_str("12")?.to_atoms(0)?, 12);
+    assert_eq!(UiAmount::from_str(".5")?, UiAmount::from_str("0.50")?);
+    assert_eq!(UiAmount::from_str("20.0")?, UiAmount::from(20));
+
+    assert!(UiAmount::from_str("0.0000001")?
+        .to_atoms(USDC_DECIMALS)
+        .is_err());
+    assert!(UiAmount::from_str("18446744073709.551616")?
+        .to_atoms(USDC_DECIMALS)
+        .is_err());
+    for invalid in ["", ".", "-1", "1e6", "1.2.3", "1,000", "0x10"] {
+        assert!(UiAmount::from_str(invalid).is_err(), "{}", invalid);
+    }
+    Ok(())
+}
+
+#[test]
+fn amounts_format_and_serialize() -> anyhow::Result<()> {
+    assert_eq!(TokenAmount::new(290_000, USDC_DECIMALS).to_string(), "0.29");
+    assert_eq!(TokenAmount::new(5, SOL_DECIMALS).to_string(), "0.000000005");
+    assert_eq!(
+        TokenAmount::new(20_000_000, USDC_DECIMALS).to_string(),
+        "20"
+    );
+    assert_eq!(UiAmount::from_str("007.100")?.to_string(), "7.1");
+    // past 38 decimals 10^decimals no longer fits a u128
+    assert_eq!(
+        TokenAmount::new(12, 40).to_string(),
+        format!("0.{}12", "0".repeat(38))
+    );
+    assert!(TokenAmount::new(u64::MAX, u8::MAX)
+        .to_string()
+        .starts_with("0.000"));
+
+    assert_eq!(
+        serde_json::to_string(&UiAmount::from_str("0.29")?)?,
+        r#""0.29""#
+    );
+    for json in [r#""0.29""#, "0.29"] {
+        assert_eq!(
+            serde_json::from_str::<UiAmount>(json)?,
+            UiAmount::from_str("0.29")?
+        );
+    }
+    assert_eq!(serde_json::from_str::<UiAmount>("20")?, UiAmount::from(20));
+    assert!(serde_json::from_str::<UiAmount>("-1").is_err());
+    Ok(())
+}
+
+#[test]
+fn token_amounts_convert_to_lots() -> anyhow::Result<()> {
+    let size = TokenAmount::parse("1.234", SOL_DECIMALS)?;
+    assert_eq!(size.to_lots(1_000_000)?, 1_234);
+    // Half a lot is rejected rather than rounded away.
+    assert!(TokenAmount::parse("1.2345", SOL_DECIMALS)?
+        .to_lots(1_000_000)
+        .is_err());
+    assert!(size.to_lots(0).is_err());
+
+    let filled = TokenAmount::from_lots(1_000, 1_000_000, SOL_DECIMALS)?;
+    assert_eq!(filled.to_string(), "1");
+    assert!(TokenAmount::from_lots(u64::MAX, 2, SOL_DECIMALS).is_err());
+    assert_eq!(
+        size.checked_sub(filled),
+        Some(TokenAmount::new(234_000_000, SOL_DECIMALS))
+    );
+    assert_eq!(filled.checked_sub(size), None);
+    assert_eq!(size.checked_add(TokenAmount::new(1, USDC_DECIMALS)), None);
+    Ok(())
+}
+
+#[test]
+fn token_amounts_only_convert_to_atoms_of_their_decimals() -> anyhow::Result<()> {
+    let deposit = TokenAmount::parse("2.5", USDC_DECIMALS)?;
+    assert_eq!(deposit.to_atoms(USDC_DECIMALS)?, 2_500_000);
+    // A USDC amount sent to a SOL vault is refused rather than read as 0.0025 SOL.
+    assert!(deposit.to_atoms(SOL_DECIMALS).is_err());
+    Ok(())
+}
+
+#[test]
+fn token_deltas_keep_their_sign() -> anyhow::Result<()> {
+    let sold = TokenDelta::from_lots(-1_500, 1_000, USDC_DECIMALS)?;
+    assert_eq!(sold.amount, TokenAmount::new(1_500_000, USDC_DECIMALS));
+    assert!(sold.negative);
+    assert_eq!(sold.to_string(), "-1.5");
+    assert_eq!(sold.to_f64(), -1.5);
+    assert_eq!(
+        TokenDelta::from_lots(0, 1_000, USDC_DECIMALS)?.to_string(),
+        "0"
+    );
+    assert!(TokenDelta::from_lots(i128::MIN, 1, USDC_DECIMALS).is_err());
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use anchor_lang::AnchorDeserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use bootstrap::drift_vaults::*;
+
+/// An account of `T` with every field zeroed, to set only the fields under test.
+fn zeroed<T: AnchorDeserialize>() -> T {
+    T::deserialize(&mut &[0u8; 1024][..]).unwrap()
+}
+
+#[test]
+fn delegate_is_synced_only_when_drift_agrees() {
+    let delegate = Pubkey::new_unique();
+    let mut status = DelegateStatus {
+        vault: Pubkey::new_unique(),
+        vault_delegate: delegate,
+        user_delegate: delegate,
+    };
+    assert!(status.is_synced());
+    // A vault in liquidation hands the Drift user to the liquidation delegate.
+    status.user_delegate = Pubkey::new_unique();
+    assert!(!status.is_synced());
+}
+
+#[test]
+fn withdraw_is_due_once_the_redeem_period_passes() {
+    let mut vault: Vault = zeroed();
+    vault.redeem_period = 3_600;
+    let mut depositor: VaultDepositor = zeroed();
+    depositor.last_withdraw_request.ts = 1_000;
+    assert!(!is_withdraw_due(&vault, &depositor, 10_000));
+
+    depositor.last_withdraw_request.shares = 50;
+    assert!(!is_withdraw_due(&vault, &depositor, 4_599));
+    assert!(is_withdraw_due(&vault, &depositor, 4_600));
+
+    depositor.last_withdraw_request.ts = i64::MAX;
+    assert!(!is_withdraw_due(&vault, &depositor, i64::MAX - 1));
+}
+
+#[test]
+fn insurance_fund_and_competitor_accounts_read_back() -> anyhow::Result<()> {
+    let vault = Pubkey::new_unique();
+    let mut stake: InsuranceFundStake = zeroed();
+    stake.authority = vault;
+    stake.if_shares = 1_000;
+    stake.last_withdraw_request_shares = 250;
+    stake.cost_basis = 900;
+    stake.market_index = 1;
+    let key = get_insurance_fund_stake_address(&vault, 1).0;
+    let position = InsuranceFundPosition::from((key, stake));
+    assert_eq!(position.pubkey, key);
+    assert_eq!(position.market_index, 1);
+    assert_eq!(position.if_shares, 1_000);
+    assert_eq!(position.pending_withdraw_shares, 250);
+    assert_eq!(position.cost_basis, 900);
+
+    let competition = Pubkey::new_unique();
+    let user_stats = get_user_stats_address(&vault).0;
+    let key = get_competitor_address(&competition, &vault).0;
+    let mut data = vec![0; 8];
+    data.extend(vault.to_bytes());
+    data.extend(competition.to_bytes());
+    data.extend(user_stats.to_bytes());
+    let competitor = CompetitorInfo::parse(key, &data)?;
+    assert_eq!(
+        competitor,
+        CompetitorInfo {
+            pubkey: key,
+            authority: vault,
+            competition,
+            user_stats,
+        }
+    );
+    assert!(CompetitorInfo::parse(key, &data[..8 + 64]).is_err());
+    Ok(())
+}
+
+#[test]
+fn spot_balances_reject_more_decimals_than_drift() -> anyhow::Result<()> {
+    let position = DriftSpotPosition {
+        market_index: 0,
+        scaled_balance: 2_000_000_000,
+        is_borrow: true,
+    };
+    let mut market = SpotMarketBalances {
+        decimals: 6,
+        cumulative_deposit_interest: 10_000_000_000,
+        cumulative_borrow_interest: 10_000_000_000,
+        last_oracle_price: PRICE_PRECISION as i64,
+    };
+    assert_eq!(market.token_amount(&position)?, -2_000_000);
+    assert_eq!(market.value(&position)?, -2_000_000);
+    market.decimals = 20;
+    assert!(market.token_amount(&position).is_err());
+    assert!(market.value(&position).is_err());
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use anchor_lang::{AnchorSerialize, Discriminator};
+use bootstrap::drift_vaults::{
+    self, MarketType, OrderAction, OrderActionExplanation, PositionDirection, VaultDepositorAction,
+    VaultDepositorRecord, DRIFT_PROGRAM_ID, DRIFT_VAULTS_PROGRAM_ID,
+};
+use bootstrap::events::*;
+use bootstrap::helpers::encode_name;
+use bootstrap::nav::ShareAction;
+use bootstrap::phoenix_vaults;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiCompiledInstruction, UiInnerInstructions,
+    UiInstruction,
+};
+
+/// `getTransaction` responses, base64 encoded, of transactions sent to the programs in `deps/`.
+fn fixture(json: &str) -> EncodedConfirmedTransactionWithStatusMeta {
+    serde_json::from_str(json).unwrap()
+}
+
+fn logs(tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<String> {
+    match &tx.transaction.meta.as_ref().unwrap().log_messages {
+        OptionSerializer::Some(logs) => logs.clone(),
+        _ => panic!("fixture has no logs"),
+    }
+}
+
+#[test]
+fn parses_drift_vaults_deposit() -> anyhow::Result<()> {
+    let tx = fixture(include_str!("fixtures/drift_vaults_deposit.json"));
+    let events = parse_transaction_events(&tx)?;
+    // the Drift `DepositRecord` logged inside the vault's CPI isn't modeled
+    assert_eq!(events.len(), 1);
+    match events[0] {
+        VaultEvent::VaultDepositorRecord(record) => {
+            assert_eq!(
+                record.vault,
+                drift_vaults::get_vault_address(&encode_name("Drift Fund")).0
+            );
+            assert_eq!(record.action, VaultDepositorAction::Deposit);
+            assert_eq!(record.amount, 100_000_000);
+            assert_eq!(record.vault_equity_before, 0);
+            assert_eq!(record.user_vault_shares_after, 100_000_000);
+            assert_eq!(record.total_vault_shares_after, 100_000_000);
+        }
+        event => panic!("unexpected event: {:?}", event),
+    }
+    // the vault logs with `emit!`, its inner instructions are the token and Drift CPIs
+    assert_eq!(parse_logs(&logs(&tx))?, events);
+    let account_keys = transaction_account_keys(&tx)?;
+    let inner = match &tx.transaction.meta.as_ref().unwrap().inner_instructions {
+        OptionSerializer::Some(inner) => inner.clone(),
+        _ => panic!("fixture has no inner instructions"),
+    };
+    assert!(account_keys.contains(&DRIFT_PROGRAM_ID));
+    assert_eq!(inner[0].instructions.len(), 3);
+    assert!(parse_cpi_events(&account_keys, &inner)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn parses_phoenix_vaults_request_withdraw() -> anyhow::Result<()> {
+    let tx = fixture(include_str!(
+        "fixtures/phoenix_vaults_request_withdraw.json"
+    ));
+    let events = parse_transaction_events(&tx)?;
+    assert_eq!(events.len(), 1);
+    let share_event = events[0].share_event().unwrap();
+    assert_eq!(share_event.action, ShareAction::WithdrawRequest);
+    assert_eq!(share_event.amount, 50_000_000);
+    assert_eq!(share_event.vault_equity_before, 100_000_000);
+    assert_eq!(
+        share_event.vault,
+        phoenix_vaults::get_vault_address(&encode_name("Phoenix Fund")).0
+    );
+    assert_eq!(parse_logs(&logs(&tx))?, events);
+    Ok(())
+}
+
+#[test]
+fn parses_drift_order_records_from_program_log() -> anyhow::Result<()> {
+    let tx = fixture(include_str!("fixtures/drift_vaults_perp_fill.json"));
+    let events = parse_transaction_events(&tx)?;
+    // the `OrderRecord` between the two isn't modeled
+    let records: Vec<_> = events
+        .iter()
+        .map(|event| match event {
+            VaultEvent::OrderActionRecord(record) => *record,
+            _ => panic!("unexpected event {:?}", event),
+        })
+        .collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].action, OrderAction::Place);
+    let fill = records[1];
+    assert_eq!(fill.action, OrderAction::Fill);
+    assert_eq!(
+        fill.action_explanation,
+        OrderActionExplanation::OrderFilledWithAMM
+    );
+    assert_eq!(fill.market_type, MarketType::Perp);
+    let vault = drift_vaults::get_vault_address(&encode_name("Drift Fund")).0;
+    assert_eq!(
+        fill.taker,
+        Some(drift_vaults::get_user_address(&vault, 0).0)
+    );
+    assert_eq!(fill.taker_order_direction, Some(PositionDirection::Long));
+    assert_eq!(fill.maker, None);
+    assert_eq!(fill.base_asset_amount_filled, Some(100_000_000));
+    assert_eq!(fill.oracle_price, 100_000_000);
+    assert_eq!(parse_logs(&logs(&tx))?, events);
+    Ok(())
+}
+
+#[test]
+fn parses_cpi_events() -> anyhow::Result<()> {
+    let record = VaultDepositorRecord {
+        ts: 1_717_000_000,
+        vault: Pubkey::new_unique(),
+        depositor_authority: Pubkey::new_unique(),
+        action: VaultDepositorAction::Withdraw,
+        amount: 10,
+        spot_market_index: 0,
+        vault_shares_before: 10,
+        vault_shares_after: 0,
+        vault_equity_before: 100,
+        user_vault_shares_before: 100,
+        total_vault_shares_before: 100,
+        user_vault_shares_after: 90,
+        total_vault_shares_after: 90,
+        profit_share: 0,
+        management_fee: 0,
+        management_fee_shares: 0,
+    };
+    let mut data = EVENT_IX_TAG.to_vec();
+    data.extend_from_slice(&VaultDepositorRecord::DISCRIMINATOR);
+    record.serialize(&mut data)?;
+    let ix = |program_id_index: u8, data: &[u8]| {
+        UiInstruction::Compiled(UiCompiledInstruction {
+            program_id_index,
+            accounts: vec![],
+            data: bs58::encode(data).into_string(),
+            stack_height: Some(2),
+        })
+    };
+    let account_keys = [Pubkey::new_unique(), DRIFT_VAULTS_PROGRAM_ID];
+    let inner = [UiInnerInstructions {
+        index: 0,
+        instructions: vec![ix(0, &[1, 2, 3]), ix(1, &data)],
+    }];
+    let events = parse_cpi_events(&account_keys, &inner)?;
+    assert_eq!(events, vec![VaultEvent::VaultDepositorRecord(record)]);
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use bootstrap::amount::{TokenAmount, UiAmount, USDC_DECIMALS};
+use bootstrap::drift_vaults::{
+    DriftFund, DriftMarketPrices, DriftSpotPosition, DriftUserPositions, SpotMarketBalances,
+    PRICE_PRECISION,
+};
+use bootstrap::fund::*;
+use bootstrap::helpers::{encode_name, transfer, unwrap_sol};
+use bootstrap::phoenix_vaults::PhoenixFund;
+use bootstrap::signer::load_signer;
+use bootstrap::vault_update::QUOTE_PRECISION;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::keypair::Keypair;
+
+fn investor(cumulative_profit_share_amount: i64) -> InvestorPosition {
+    InvestorPosition {
+        pubkey: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        shares: 0,
+        net_deposits: 0,
+        cumulative_profit_share_amount,
+        pending_withdraw_shares: 0,
+        pending_withdraw_value: 0,
+        pending_withdraw_ts: 0,
+    }
+}
+
+#[test]
+fn fund_overview_splits_profit_and_fees() -> anyhow::Result<()> {
+    let summary = FundSummary {
+        vault: Pubkey::new_unique(),
+        manager: Pubkey::new_unique(),
+        venue: Venue::Phoenix,
+        name: encode_name("Test Fund"),
+        init_ts: 1_700_000_000,
+        manager_total_profit_share: 20 * QUOTE_PRECISION,
+        protocol_total_profit_share: 5 * QUOTE_PRECISION,
+    };
+    let investors = [
+        investor(100 * QUOTE_PRECISION as i64),
+        investor(50 * QUOTE_PRECISION as i64),
+    ];
+    let overview = FundOverview::compute(
+        &summary,
+        &investors,
+        1_000 * QUOTE_PRECISION,
+        DepositPrice::USDC,
+    );
+    assert_eq!(overview.investor_profit, 150.0);
+    assert_eq!(overview.profit, 150.0);
+    assert_eq!(overview.profit_after_fees, 125.0);
+    assert_eq!(overview.tvl, 1_000.0);
+    assert_eq!(overview.investors.len(), 2);
+    assert_eq!(overview.title, "Test Fund");
+
+    let json = serde_json::to_value(&overview)?;
+    assert_eq!(json["vault"], summary.vault.to_string());
+    assert_eq!(json["profitAfterFees"], 125.0);
+    assert_eq!(json["investors"][investors[0].pubkey.to_string()], 100.0);
+    assert_eq!(serde_json::from_value::<FundOverview>(json)?, overview);
+    Ok(())
+}
+
+#[test]
+fn fund_overview_values_non_usdc_deposits_in_usd() -> anyhow::Result<()> {
+    const SOL_MARKET_INDEX: u16 = 1;
+    let sol = SpotMarketBalances {
+        decimals: 9,
+        cumulative_deposit_interest: 10_000_000_000,
+        cumulative_borrow_interest: 10_000_000_000,
+        last_oracle_price: 150 * PRICE_PRECISION as i64,
+    };
+    let prices = DriftMarketPrices {
+        spot_markets: HashMap::from([(SOL_MARKET_INDEX, sol)]),
+        perp_prices: HashMap::new(),
+    };
+    // 10 SOL deposited, scaled to Drift's 19 digit balance precision
+    let positions = DriftUserPositions {
+        spot: vec![DriftSpotPosition {
+            market_index: SOL_MARKET_INDEX,
+            scaled_balance: 10_000_000_000,
+            is_borrow: false,
+        }],
+        perp: vec![],
+    };
+    let equity = prices.vault_equity(&positions, SOL_MARKET_INDEX)?;
+    assert_eq!(equity, 10_000_000_000);
+    let deposit = prices.deposit_price(SOL_MARKET_INDEX)?;
+    assert_eq!(
+        deposit,
+        DepositPrice {
+            decimals: 9,
+            price: 150.0
+        }
+    );
+
+    let summary = FundSummary {
+        vault: Pubkey::new_unique(),
+        manager: Pubkey::new_unique(),
+        venue: Venue::Drift,
+        name: encode_name("SOL Fund"),
+        init_ts: 1_700_000_000,
+        manager_total_profit_share: 100_000_000,
+        protocol_total_profit_share: 0,
+    };
+    let investors = [investor(1_000_000_000)];
+    let overview = FundOverview::compute(&summary, &investors, equity, deposit);
+    assert_eq!(overview.tvl, 1_500.0);
+    assert_eq!(overview.investor_profit, 150.0);
+    assert_eq!(overview.manager_profit, 15.0);
+    assert_eq!(overview.profit_after_fees, 135.0);
+    Ok(())
+}
+
+#[test]
+fn funds_of_every_venue_box_as_dyn_fund() {
+    let client = RpcClient::new("http://localhost:8899".to_string());
+    let drift = Pubkey::new_unique();
+    let phoenix = Pubkey::new_unique();
+    let funds: Vec<Box<dyn Fund + '_>> = vec![
+        Box::new(DriftFund::new(&client, drift)),
+        Box::new(PhoenixFund::new(&client, phoenix, vec![])),
+    ];
+    let venues: Vec<(Venue, Pubkey)> = funds.iter().map(|f| (f.venue(), f.vault())).collect();
+    assert_eq!(
+        venues,
+        vec![(Venue::Drift, drift), (Venue::Phoenix, phoenix)]
+    );
+}
+
+#[tokio::test]
+async fn loaded_signers_are_accepted_by_funds_and_helpers() -> anyhow::Result<()> {
+    // Nothing listens here, so each call fails at its first request.
+    let client = RpcClient::new("http://127.0.0.1:1".to_string());
+    let signer = load_signer(&Keypair::new().to_base58_string())?;
+    let fund: Box<dyn Fund + '_> =
+        Box::new(PhoenixFund::new(&client, Pubkey::new_unique(), vec![]));
+    assert!(fund
+        .deposit(&*signer, TokenAmount::new(1, USDC_DECIMALS))
+        .await
+        .is_err());
+    assert!(fund.withdraw(&*signer).await.is_err());
+    assert!(
+        transfer(&client, &*signer, &Pubkey::new_unique(), UiAmount::from(1))
+            .await
+            .is_err()
+    );
+    assert!(unwrap_sol(&client, &*signer).await.is_err());
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use std::collections::{HashMap, HashSet};
+
+use bootstrap::drift_vaults::{self, DRIFT_VAULTS_PROGRAM_ID};
+use bootstrap::events::parse_transaction_events;
+use bootstrap::fund::Venue;
+use bootstrap::helpers::encode_name;
+use bootstrap::indexer::{Indexer, VaultTrade};
+use bootstrap::market::PhoenixMarket;
+use bootstrap::market_bootstrap::MOCK_SOL_USDC_MARKET;
+use bootstrap::market_subscriber::parse_trades;
+use bootstrap::phoenix_vaults;
+use phoenix::program::{MarketHeader, MarketSizeParams, TokenParams};
+use phoenix::quantities::{
+    BaseAtomsPerBaseLot, QuoteAtomsPerBaseUnitPerTick, QuoteAtomsPerQuoteLot, WrapperU64,
+};
+use phoenix::state::Side;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+
+fn count(indexer: &Indexer, table: &str) -> i64 {
+    indexer
+        .conn
+        .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+}
+
+fn fixture(json: &str) -> EncodedConfirmedTransactionWithStatusMeta {
+    serde_json::from_str(json).unwrap()
+}
+
+/// The bootstrapped SOL/USDC market the Phoenix fixtures trade on: 0.001 SOL lots and 0.01 USDC ticks.
+fn sol_usdc_markets() -> anyhow::Result<HashMap<Pubkey, PhoenixMarket>> {
+    let token = |decimals: u32| TokenParams {
+        decimals,
+        vault_bump: 0,
+        mint_key: Pubkey::new_unique(),
+        vault_key: Pubkey::new_unique(),
+    };
+    let header = MarketHeader::new(
+        MarketSizeParams::default(),
+        token(9),
+        BaseAtomsPerBaseLot::new(1_000_000),
+        token(6),
+        QuoteAtomsPerQuoteLot::new(10),
+        QuoteAtomsPerBaseUnitPerTick::new(10_000),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        1,
+    );
+    let key = Keypair::from_bytes(&MOCK_SOL_USDC_MARKET)?.pubkey();
+    let market = PhoenixMarket::new(key, bytemuck::bytes_of(&header).to_vec())?;
+    Ok(HashMap::from([(key, market)]))
+}
+
+#[test]
+fn indexer_writes_events_and_resumes_from_cursor() -> anyhow::Result<()> {
+    let tx = fixture(include_str!("fixtures/drift_vaults_deposit.json"));
+    let events = parse_transaction_events(&tx)?;
+
+    let mut indexer = Indexer::open_in_memory()?;
+    assert_eq!(indexer.cursor(&DRIFT_VAULTS_PROGRAM_ID)?, None);
+
+    let signature = Signature::new_unique();
+    for _ in 0..2 {
+        indexer.insert_transaction(
+            &DRIFT_VAULTS_PROGRAM_ID,
+            &signature.to_string(),
+            100,
+            Some(1_717_000_000),
+            &events,
+        )?;
+    }
+    assert_eq!(indexer.cursor(&DRIFT_VAULTS_PROGRAM_ID)?, Some(signature));
+    assert_eq!(count(&indexer, "transactions"), 1);
+    assert_eq!(count(&indexer, "deposits"), 1);
+    assert_eq!(count(&indexer, "trades"), 0);
+    assert_eq!(count(&indexer, "withdrawals"), 0);
+    assert_eq!(count(&indexer, "fees"), 0);
+
+    let amount: i64 = indexer
+        .conn
+        .query_row("SELECT amount FROM deposits", [], |row| row.get(0))?;
+    assert_eq!(amount, 100_000_000);
+    Ok(())
+}
+
+#[test]
+fn drift_user_fills_become_vault_trades() -> anyhow::Result<()> {
+    let tx = fixture(include_str!("fixtures/drift_vaults_perp_fill.json"));
+    let events = parse_transaction_events(&tx)?;
+    let vault = drift_vaults::get_vault_address(&encode_name("Drift Fund")).0;
+    let user = drift_vaults::get_user_address(&vault, 0).0;
+
+    // the delegate's limit buy of 0.1 SOL-PERP, taken by the AMM around the oracle price of 100
+    let trades = VaultTrade::from_drift_events(&events, &vault, &user);
+    assert_eq!(trades.len(), 1);
+    let (index, trade) = trades[0];
+    // after the `Place` record
+    assert_eq!(index, 1);
+    assert_eq!(trade.vault, vault);
+    assert_eq!(trade.venue, Venue::Drift);
+    assert_eq!(trade.market, drift_vaults::get_perp_market_address(0).0);
+    assert_eq!(trade.side, Side::Bid);
+    assert!(!trade.maker);
+    assert_eq!(trade.base_amount, 100_000_000);
+    assert_eq!(trade.quote_amount, 10_000_101);
+
+    assert!(VaultTrade::from_drift_events(&events, &vault, &Pubkey::new_unique()).is_empty());
+    Ok(())
+}
+
+#[test]
+fn phoenix_fills_become_vault_trades() -> anyhow::Result<()> {
+    let markets = sol_usdc_markets()?;
+    let vault = phoenix_vaults::get_vault_address(&encode_name("Phoenix Fund")).0;
+    let vaults = HashSet::from([vault]);
+
+    // another trader sells 0.2 SOL into the vault's resting bid at 100
+    let tx = fixture(include_str!(
+        "fixtures/phoenix_sol_usdc_vault_maker_fill.json"
+    ));
+    let trades = parse_trades(&tx, "maker", &markets)?;
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].maker, vault);
+    let maker_fills = VaultTrade::from_phoenix_trades(&trades, &vaults);
+    assert_eq!(maker_fills.len(), 1);
+    let (index, trade) = maker_fills[0];
+    assert_eq!(index, 0);
+    assert_eq!(trade.venue, Venue::Phoenix);
+    assert_eq!(trade.market, *markets.keys().next().unwrap());
+    assert_eq!(trade.side, Side::Bid);
+    assert!(trade.maker);
+    assert_eq!(trade.base_amount, 200_000_000);
+    assert_eq!(trade.quote_amount, 20_000_000);
+
+    // the vault lifts 0.05 SOL of a resting ask at 101 through phoenix_vaults
+    let tx = fixture(include_str!("fixtures/phoenix_vaults_taker_fill.json"));
+    let trades = parse_trades(&tx, "taker", &markets)?;
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].taker, vault);
+    let (_, trade) = VaultTrade::from_phoenix_trades(&trades, &vaults)[0];
+    assert_eq!(trade.side, Side::Bid);
+    assert!(!trade.maker);
+    assert_eq!(trade.base_amount, 50_000_000);
+    assert_eq!(trade.quote_amount, 5_050_000);
+
+    assert!(VaultTrade::from_phoenix_trades(&trades, &HashSet::new()).is_empty());
+    Ok(())
+}
+
+#[test]
+fn indexer_writes_trades_and_keeps_a_cursor_per_address() -> anyhow::Result<()> {
+    let markets = sol_usdc_markets()?;
+    let market = *markets.keys().next().unwrap();
+    let vault = phoenix_vaults::get_vault_address(&encode_name("Phoenix Fund")).0;
+    let tx = fixture(include_str!(
+        "fixtures/phoenix_sol_usdc_vault_maker_fill.json"
+    ));
+    let signature = Signature::new_unique();
+    let trades = VaultTrade::from_phoenix_trades(
+        &parse_trades(&tx, &signature.to_string(), &markets)?,
+        &HashSet::from([vault]),
+    );
+
+    let mut indexer = Indexer::open_in_memory()?;
+    for _ in 0..2 {
+        indexer.insert_trades(&market, &signature.to_string(), tx.slot, &trades)?;
+    }
+    let unrelated = Signature::new_unique();
+    indexer.insert_trades(&DRIFT_VAULTS_PROGRAM_ID, &unrelated.to_string(), 1, &[])?;
+
+    assert_eq!(indexer.cursor(&market)?, Some(signature));
+    assert_eq!(indexer.cursor(&DRIFT_VAULTS_PROGRAM_ID)?, Some(unrelated));
+    assert_eq!(count(&indexer, "trades"), 1);
+    let (venue, side, maker, quote_amount): (String, String, bool, i64) = indexer.conn.query_row(
+        "SELECT venue, side, maker, quote_amount FROM trades",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )?;
+    assert_eq!(
+        (venue.as_str(), side.as_str(), maker, quote_amount),
+        ("Phoenix", "Bid", true, 20_000_000)
+    );
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use anchor_lang::AnchorSerialize;
+use solana_sdk::pubkey::Pubkey;
+
+use bootstrap::drift_vaults::{self, manager_cancel_withdraw_request_ix, VaultParams};
+use bootstrap::helpers::encode_name;
+use bootstrap::phoenix_vaults;
+use bootstrap::vault_update::UpdateVaultConfig;
+
+#[test]
+fn drift_vault_params_follow_the_idl_layout() -> anyhow::Result<()> {
+    let params = VaultParams {
+        name: encode_name("Test Vault"),
+        redeem_period: 3_600,
+        max_tokens: 1_000_000,
+        management_fee: 20_000,
+        min_deposit_amount: 5,
+        profit_share: 100_000,
+        hurdle_rate: 0,
+        spot_market_index: 1,
+        permissioned: true,
+    };
+    let mut expected = encode_name("Test Vault").to_vec();
+    expected.extend(3_600i64.to_le_bytes());
+    expected.extend(1_000_000u64.to_le_bytes());
+    expected.extend(20_000i64.to_le_bytes());
+    expected.extend(5u64.to_le_bytes());
+    expected.extend(100_000u32.to_le_bytes());
+    expected.extend(0u32.to_le_bytes());
+    expected.extend(1u16.to_le_bytes());
+    expected.push(1);
+    assert_eq!(params.try_to_vec()?, expected);
+    Ok(())
+}
+
+#[test]
+fn drift_manager_cancel_uses_the_program_spelling() {
+    let ix =
+        manager_cancel_withdraw_request_ix(&Pubkey::new_unique(), &Pubkey::new_unique(), vec![]);
+    // sha256("global:manger_cancel_withdraw_request")
+    assert_eq!(ix.data, [235, 253, 32, 176, 145, 94, 162, 244]);
+}
+
+#[test]
+fn phoenix_update_vault_params_keep_the_delegate() {
+    let delegate = Pubkey::new_unique();
+    let config = UpdateVaultConfig {
+        profit_share: Some(100_000),
+        delegate: Some(delegate),
+        ..Default::default()
+    };
+    let params = phoenix_vaults::UpdateVaultParams::from(config);
+    assert_eq!(params.profit_share, Some(100_000));
+    assert_eq!(params.delegate, Some(delegate));
+    assert_eq!(params.redeem_period, None);
+}
+
+#[test]
+fn phoenix_manager_withdraw_requests_pass_the_registry_markets() -> anyhow::Result<()> {
+    let vault = Pubkey::new_unique();
+    let manager = Pubkey::new_unique();
+    let usdc_mint = Pubkey::new_unique();
+    let markets = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let request = phoenix_vaults::manager_request_withdraw_ix(
+        &vault,
+        &manager,
+        &usdc_mint,
+        10,
+        phoenix_vaults::WithdrawUnit::Token,
+        &markets,
+    )?;
+    let cancel =
+        phoenix_vaults::manager_cancel_withdraw_request_ix(&vault, &manager, &usdc_mint, &markets);
+    for ix in [&request, &cancel] {
+        assert_eq!(ix.accounts.len(), 4 + markets.len());
+        assert!(ix.accounts[1].is_signer);
+        assert_eq!(ix.accounts[1].pubkey, manager);
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            phoenix_vaults::get_market_registry_address().0
+        );
+        assert_eq!(ix.accounts[4].pubkey, markets[0]);
+    }
+    assert_eq!(cancel.data, [187, 21, 195, 44, 87, 97, 230, 151]);
+    Ok(())
+}
+
+#[test]
+fn manager_deposits_hash_the_idl_name_on_both_venues() -> anyhow::Result<()> {
+    let vault = Pubkey::new_unique();
+    let manager = Pubkey::new_unique();
+    let token_account = Pubkey::new_unique();
+    let drift = drift_vaults::manager_deposit_ix(&vault, &manager, &token_account, 0, 7, vec![])?;
+    let phoenix = phoenix_vaults::manager_deposit_ix(&vault, &manager, &token_account, 7, &[])?;
+    for ix in [&drift, &phoenix] {
+        // sha256("global:manager_deposit")
+        assert_eq!(ix.data[..8], [73, 3, 16, 168, 143, 226, 201, 254]);
+        assert_eq!(ix.data[8..], 7u64.to_le_bytes());
+        assert!(ix.accounts[1].is_signer);
+        assert_eq!(ix.accounts[1].pubkey, manager);
+    }
+    assert_eq!(drift.accounts[7].pubkey, token_account);
+    Ok(())
+}
+
+#[test]
+fn drift_protocol_withdraws_name_the_vault_protocol() -> anyhow::Result<()> {
+    let vault = Pubkey::new_unique();
+    let protocol = Pubkey::new_unique();
+    let vault_protocol = drift_vaults::get_vault_protocol_address(&vault).0;
+    let request = drift_vaults::protocol_request_withdraw_ix(
+        &vault,
+        &protocol,
+        10,
+        drift_vaults::WithdrawUnit::Token,
+        vec![],
+    )?;
+    let cancel = drift_vaults::protocol_cancel_withdraw_request_ix(&vault, &protocol, vec![]);
+    let withdraw =
+        drift_vaults::protocol_withdraw_ix(&vault, &protocol, &Pubkey::new_unique(), 0, vec![]);
+    for ix in [&request, &cancel, &withdraw] {
+        assert_eq!(ix.accounts[1].pubkey, vault_protocol);
+        assert!(ix.accounts[1].is_writable);
+        assert!(ix.accounts[2].is_signer);
+        assert_eq!(ix.accounts[2].pubkey, protocol);
+    }
+    assert_eq!(request.data[..8], [189, 46, 14, 31, 7, 254, 150, 132]);
+    assert_eq!(cancel.data, [194, 217, 171, 94, 56, 253, 179, 242]);
+    assert_eq!(withdraw.data, [166, 24, 188, 209, 21, 251, 63, 199]);
+    assert_eq!(withdraw.accounts.len(), 12);
+    Ok(())
+}
+
+#[test]
+fn phoenix_protocol_withdraw_requests_pass_the_registry_markets() -> anyhow::Result<()> {
+    let vault = Pubkey::new_unique();
+    let protocol = Pubkey::new_unique();
+    let usdc_mint = Pubkey::new_unique();
+    let markets = [Pubkey::new_unique()];
+    let request = phoenix_vaults::protocol_request_withdraw_ix(
+        &vault,
+        &protocol,
+        &usdc_mint,
+        10,
+        phoenix_vaults::WithdrawUnit::Token,
+        &markets,
+    )?;
+    let cancel = phoenix_vaults::protocol_cancel_withdraw_request_ix(
+        &vault, &protocol, &usdc_mint, &markets,
+    );
+    for ix in [&request, &cancel] {
+        assert_eq!(ix.accounts.len(), 4 + markets.len());
+        assert!(ix.accounts[1].is_signer);
+        assert_eq!(ix.accounts[1].pubkey, protocol);
+        assert_eq!(ix.accounts[4].pubkey, markets[0]);
+    }
+    assert_eq!(request.data[..8], [189, 46, 14, 31, 7, 254, 150, 132]);
+    assert_eq!(cancel.data, [194, 217, 171, 94, 56, 253, 179, 242]);
+    Ok(())
+}
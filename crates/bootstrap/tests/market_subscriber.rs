@@ -0,0 +1,67 @@
+use bootstrap::market_subscriber::*;
+use phoenix::state::Side;
+use solana_sdk::pubkey::Pubkey;
+
+#[test]
+fn l2_book_diff_applies_incrementally() {
+    let mut book = L2Book {
+        bids: vec![(100.0, 1.0), (99.0, 2.0)],
+        asks: vec![(101.0, 1.0), (102.0, 3.0)],
+    };
+    let next = L2Book {
+        bids: vec![(100.5, 1.0), (100.0, 1.0)],
+        asks: vec![(101.0, 0.5), (102.0, 3.0)],
+    };
+    let changes = book.diff(&next);
+    assert_eq!(changes.len(), 3);
+    assert!(changes.contains(&LevelChange {
+        side: Side::Bid,
+        price: 99.0,
+        size: 0.0,
+    }));
+    book.apply(&changes);
+    assert_eq!(book, next);
+    assert_eq!(book.mid(), Some(100.75));
+    assert!(book.diff(&next).is_empty());
+}
+
+#[test]
+fn candle_series_closes_on_new_period() {
+    let mut series = CandleSeries::new(60);
+    assert_eq!(series.update(120, 10.0), None);
+    assert_eq!(series.update(150, 12.0), None);
+    assert_eq!(series.update(170, 9.0), None);
+    // late sample of an earlier period
+    assert_eq!(series.update(100, 50.0), None);
+
+    let closed = series.update(185, 11.0).unwrap();
+    assert_eq!(
+        closed,
+        Candle {
+            start: 120,
+            open: 10.0,
+            high: 12.0,
+            low: 9.0,
+            close: 9.0,
+        }
+    );
+    assert_eq!(series.candles.len(), 2);
+    assert_eq!(series.candles[1].start, 180);
+}
+
+#[tokio::test]
+async fn market_subscriber_reports_dropped_streams() {
+    let market = Pubkey::new_unique();
+    let config =
+        MarketSubscriberConfig::new("http://127.0.0.1:1", "ws://127.0.0.1:1", vec![market]);
+    let subscriber = std::sync::Arc::new(PhoenixMarketSubscriber::new(config));
+    let mut errors = subscriber.subscribe_errors();
+    let handles = subscriber.spawn();
+
+    let error = tokio::time::timeout(std::time::Duration::from_secs(10), errors.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(error.key, market);
+    handles.iter().for_each(|h| h.abort());
+}
@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+
+use anchor_lang::{AnchorDeserialize, AnchorSerialize, Discriminator};
+use anchor_spl::associated_token::get_associated_token_address;
+use phoenix::program::{MarketHeader, MarketSizeParams, TokenParams};
+use phoenix::quantities::{
+    BaseAtomsPerBaseLot, QuoteAtomsPerBaseUnitPerTick, QuoteAtomsPerQuoteLot, WrapperU64,
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use bootstrap::drift_vaults::*;
+use bootstrap::helpers::{decode_account, encode_name};
+use bootstrap::market::PhoenixMarket;
+use bootstrap::multisig::*;
+use bootstrap::phoenix_vaults;
+use bootstrap::vault_update::UpdateVaultConfig;
+
+fn sol_usdc_market(usdc_mint: Pubkey) -> anyhow::Result<PhoenixMarket> {
+    let token = |decimals: u32, mint_key: Pubkey| TokenParams {
+        decimals,
+        vault_bump: 0,
+        mint_key,
+        vault_key: Pubkey::new_unique(),
+    };
+    let header = MarketHeader::new(
+        MarketSizeParams::default(),
+        token(9, Pubkey::new_unique()),
+        BaseAtomsPerBaseLot::new(1_000_000),
+        token(6, usdc_mint),
+        QuoteAtomsPerQuoteLot::new(10),
+        QuoteAtomsPerBaseUnitPerTick::new(1_000),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        1,
+    );
+    PhoenixMarket::new(Pubkey::new_unique(), bytemuck::bytes_of(&header).to_vec())
+}
+
+/// Runs the multisig program's instructions against in-memory accounts, enforcing the same
+/// checks it does on chain. `execute` returns the instruction the program would invoke.
+#[derive(Default)]
+struct MockMultisig {
+    accounts: HashMap<Pubkey, Vec<u8>>,
+}
+
+impl MockMultisig {
+    fn allocate(&mut self, key: Pubkey, space: usize) {
+        self.accounts.insert(key, vec![0; space]);
+    }
+
+    fn read<T: AnchorDeserialize + Discriminator>(&self, key: &Pubkey) -> anyhow::Result<T> {
+        decode_account(
+            self.accounts
+                .get(key)
+                .ok_or(anyhow::anyhow!("No account {}", key))?,
+        )
+    }
+
+    fn write<T: AnchorSerialize + Discriminator>(
+        &mut self,
+        key: &Pubkey,
+        value: &T,
+    ) -> anyhow::Result<()> {
+        let mut data = T::DISCRIMINATOR.to_vec();
+        value.serialize(&mut data)?;
+        let account = self
+            .accounts
+            .get_mut(key)
+            .ok_or(anyhow::anyhow!("No account {}", key))?;
+        if data.len() > account.len() {
+            return Err(anyhow::anyhow!(
+                "{} bytes do not fit in {}",
+                data.len(),
+                account.len()
+            ));
+        }
+        account[..data.len()].copy_from_slice(&data);
+        Ok(())
+    }
+
+    /// Anchor's `zero` constraint: allocated but never initialized.
+    fn require_zero(&self, key: &Pubkey) -> anyhow::Result<()> {
+        match self.accounts.get(key) {
+            Some(data) if data[..8] == [0; 8] => Ok(()),
+            _ => Err(anyhow::anyhow!("{} is not an uninitialized account", key)),
+        }
+    }
+
+    fn process(&mut self, ix: &Instruction) -> anyhow::Result<Option<Instruction>> {
+        assert_eq!(ix.program_id, MULTISIG_PROGRAM_ID);
+        let signed = |i: usize| -> anyhow::Result<Pubkey> {
+            let meta = &ix.accounts[i];
+            if !meta.is_signer {
+                return Err(anyhow::anyhow!("{} did not sign", meta.pubkey));
+            }
+            Ok(meta.pubkey)
+        };
+        let (discriminator, mut args) = ix.data.split_at(8);
+        match discriminator {
+            [148, 146, 240, 10, 226, 215, 167, 174] => {
+                let args = CreateMultisigArgs::deserialize(&mut args)?;
+                let multisig = signed(0)?;
+                self.require_zero(&multisig)?;
+                assert_eq!(args.nonce, get_multisig_signer_address(&multisig).1);
+                let multisig_acct = Multisig {
+                    owners: args.owners,
+                    threshold: args.threshold,
+                    nonce: args.nonce,
+                    owner_set_seqno: 0,
+                };
+                self.write(&multisig, &multisig_acct)?;
+                Ok(None)
+            }
+            [227, 193, 53, 239, 55, 126, 112, 105] => {
+                let args = CreateTransactionArgs::deserialize(&mut args)?;
+                let multisig = ix.accounts[0].pubkey;
+                let transaction = signed(1)?;
+                let proposer = signed(2)?;
+                let multisig_acct: Multisig = self.read(&multisig)?;
+                self.require_zero(&transaction)?;
+                let owner = multisig_acct
+                    .owner_index(&proposer)
+                    .ok_or(anyhow::anyhow!("{} is not an owner", proposer))?;
+                let mut signers = vec![false; multisig_acct.owners.len()];
+                signers[owner] = true;
+                let proposal = MultisigTransaction {
+                    multisig,
+                    program_id: args.pid,
+                    accounts: args.accs,
+                    data: args.data,
+                    signers,
+                    did_execute: false,
+                    owner_set_seqno: multisig_acct.owner_set_seqno,
+                };
+                self.write(&transaction, &proposal)?;
+                Ok(None)
+            }
+            [69, 74, 217, 36, 115, 117, 97, 76] => {
+                let multisig = ix.accounts[0].pubkey;
+                let transaction = ix.accounts[1].pubkey;
+                let owner = signed(2)?;
+                let multisig_acct: Multisig = self.read(&multisig)?;
+                let mut proposal: MultisigTransaction = self.read(&transaction)?;
+                assert_eq!(proposal.multisig, multisig);
+                let index = multisig_acct
+                    .owner_index(&owner)
+                    .ok_or(anyhow::anyhow!("{} is not an owner", owner))?;
+                proposal.signers[index] = true;
+                self.write(&transaction, &proposal)?;
+                Ok(None)
+            }
+            [231, 173, 49, 91, 235, 24, 68, 19] => {
+                let multisig = ix.accounts[0].pubkey;
+                let transaction = ix.accounts[2].pubkey;
+                let multisig_acct: Multisig = self.read(&multisig)?;
+                let mut proposal: MultisigTransaction = self.read(&transaction)?;
+                assert_eq!(proposal.multisig, multisig);
+                assert_eq!(
+                    ix.accounts[1].pubkey,
+                    get_multisig_signer_address(&multisig).0
+                );
+                if proposal.did_execute {
+                    return Err(anyhow::anyhow!("Already executed"));
+                }
+                if (proposal.approvals() as u64) < multisig_acct.threshold {
+                    return Err(anyhow::anyhow!("Not enough signers"));
+                }
+                // The invoked program and every account it needs must be passed along.
+                let remaining = &ix.accounts[3..];
+                let inner = proposal.instruction();
+                assert_eq!(remaining.last().unwrap().pubkey, inner.program_id);
+                assert!(remaining.iter().all(|meta| !meta.is_signer));
+                for (meta, passed) in inner.accounts.iter().zip(remaining) {
+                    assert_eq!(meta.pubkey, passed.pubkey);
+                    assert_eq!(meta.is_writable, passed.is_writable);
+                }
+                proposal.did_execute = true;
+                self.write(&transaction, &proposal)?;
+                Ok(Some(inner))
+            }
+            _ => Err(anyhow::anyhow!("Unknown instruction")),
+        }
+    }
+}
+
+/// Proposes `ix` as `owners[0]`, approves it as `owners[2]` and executes it, checking that
+/// non-owners, too few approvals and executing twice are rejected along the way.
+fn govern(
+    program: &mut MockMultisig,
+    multisig: &Pubkey,
+    owners: &[Pubkey],
+    ix: Instruction,
+) -> anyhow::Result<()> {
+    let signer = get_multisig_signer_address(multisig).0;
+    assert!(ix
+        .accounts
+        .contains(&AccountMeta::new_readonly(signer, true)));
+    let transaction = Pubkey::new_unique();
+    program.allocate(transaction, MultisigTransaction::space(&ix, owners.len()));
+    assert!(program
+        .process(&create_transaction_ix(
+            multisig,
+            &transaction,
+            &Pubkey::new_unique(),
+            &ix
+        )?)
+        .is_err());
+    program.process(&create_transaction_ix(
+        multisig,
+        &transaction,
+        &owners[0],
+        &ix,
+    )?)?;
+
+    let proposal: MultisigTransaction = program.read(&transaction)?;
+    assert_eq!(proposal.approvals(), 1);
+    assert_eq!(proposal.instruction(), ix);
+    assert!(program
+        .process(&execute_transaction_ix(multisig, &transaction, &proposal))
+        .is_err());
+    assert!(program
+        .process(&approve_ix(multisig, &transaction, &Pubkey::new_unique()))
+        .is_err());
+
+    program.process(&approve_ix(multisig, &transaction, &owners[2]))?;
+    let proposal: MultisigTransaction = program.read(&transaction)?;
+    assert_eq!(proposal.signers, vec![true, false, true]);
+    assert_eq!(
+        program.process(&execute_transaction_ix(multisig, &transaction, &proposal))?,
+        Some(ix)
+    );
+    assert!(program
+        .process(&execute_transaction_ix(multisig, &transaction, &proposal))
+        .is_err());
+    Ok(())
+}
+
+/// A 2 of 3 multisig, returning its owners.
+fn create_2_of_3(program: &mut MockMultisig, multisig: &Pubkey) -> anyhow::Result<[Pubkey; 3]> {
+    let owners = [
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ];
+    program.allocate(*multisig, Multisig::space(owners.len()));
+    program.process(&create_multisig_ix(multisig, owners.to_vec(), 2)?)?;
+    assert_eq!(program.read::<Multisig>(multisig)?.threshold, 2);
+    Ok(owners)
+}
+
+#[test]
+fn multisig_governs_vault_admin() -> anyhow::Result<()> {
+    let mut program = MockMultisig::default();
+    let multisig = Pubkey::new_unique();
+    let signer = get_multisig_signer_address(&multisig).0;
+    let owners = create_2_of_3(&mut program, &multisig)?;
+
+    let vault = get_vault_address(&encode_name("governed")).0;
+    let config = UpdateVaultConfig {
+        max_tokens: Some(1_000_000_000_000),
+        profit_share: Some(200_000),
+        ..Default::default()
+    };
+    let admin_ixs = [
+        update_vault_ix(&vault, &signer, config.into())?,
+        update_delegate_ix(&vault, &signer, &Pubkey::new_unique())?,
+        manager_request_withdraw_ix(&vault, &signer, 1_000_000, WithdrawUnit::Token, vec![])?,
+        manager_withdraw_ix(
+            &vault,
+            &signer,
+            &get_associated_token_address(&signer, &Pubkey::new_unique()),
+            0,
+            vec![AccountMeta::new_readonly(Pubkey::new_unique(), false)],
+        ),
+    ];
+    for ix in admin_ixs {
+        govern(&mut program, &multisig, &owners, ix)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn multisig_governs_phoenix_vault_admin() -> anyhow::Result<()> {
+    let mut program = MockMultisig::default();
+    let multisig = Pubkey::new_unique();
+    let signer = get_multisig_signer_address(&multisig).0;
+    let owners = create_2_of_3(&mut program, &multisig)?;
+
+    let vault = phoenix_vaults::get_vault_address(&encode_name("governed")).0;
+    let usdc_mint = Pubkey::new_unique();
+    let sol_usdc = sol_usdc_market(usdc_mint)?;
+    let markets = [sol_usdc.key];
+    // phoenix_vaults vaults change their delegate through updateVault
+    let delegate = Pubkey::new_unique();
+    let config = UpdateVaultConfig {
+        max_tokens: Some(1_000_000_000_000),
+        delegate: Some(delegate),
+        ..Default::default()
+    };
+    let update_ix = phoenix_vaults::update_vault_ix(&vault, &signer, config.into())?;
+    let params = phoenix_vaults::UpdateVaultParams::deserialize(&mut &update_ix.data[8..])?;
+    assert_eq!(params.delegate, Some(delegate));
+
+    let admin_ixs = [
+        update_ix,
+        phoenix_vaults::manager_request_withdraw_ix(
+            &vault,
+            &signer,
+            &usdc_mint,
+            1_000_000,
+            phoenix_vaults::WithdrawUnit::Token,
+            &markets,
+        )?,
+        phoenix_vaults::manager_cancel_withdraw_request_ix(&vault, &signer, &usdc_mint, &markets),
+        phoenix_vaults::manager_withdraw_ix(
+            &vault,
+            &signer,
+            &get_associated_token_address(&signer, &usdc_mint),
+            &sol_usdc,
+            &markets,
+        ),
+    ];
+    for ix in admin_ixs {
+        govern(&mut program, &multisig, &owners, ix)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn governance_futures_are_send() {
+    fn assert_send<T: Send>(_: T) {}
+    let client = RpcClient::new("http://localhost:8899".to_string());
+    // as loaded by `load_signer`
+    let proposer: Box<dyn Signer + Send + Sync> = Box::new(Keypair::new());
+    let key = Pubkey::new_unique();
+    let ix = approve_ix(&key, &key, &key);
+    assert_send(create_multisig(
+        &client,
+        proposer.as_ref(),
+        &Keypair::new(),
+        vec![key],
+        1,
+    ));
+    assert_send(propose(&client, proposer.as_ref(), &key, &ix));
+    assert_send(approve(&client, proposer.as_ref(), &key, &key));
+    assert_send(execute(&client, proposer.as_ref(), &key, &key));
+    assert_send(propose_update_delegate(
+        &client,
+        proposer.as_ref(),
+        &key,
+        &key,
+        &key,
+    ));
+    assert_send(phoenix_vaults::propose_manager_withdraw(
+        &client,
+        proposer.as_ref(),
+        &key,
+        &key,
+        &[],
+    ));
+}
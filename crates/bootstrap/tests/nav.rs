@@ -0,0 +1,143 @@
+use bootstrap::fund::Fund;
+use bootstrap::nav::*;
+use bootstrap::phoenix_vaults::PhoenixFund;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+const YEAR: i64 = 31_536_000;
+
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-6, "{} != {}", a, b);
+}
+
+fn deposit(
+    vault: Pubkey,
+    authority: Pubkey,
+    ts: i64,
+    amount: u64,
+    equity_before: u64,
+    shares_before: u128,
+    shares: u128,
+) -> ShareEvent {
+    ShareEvent {
+        ts,
+        vault,
+        authority,
+        action: ShareAction::Deposit,
+        amount,
+        vault_equity_before: equity_before,
+        total_shares_before: shares_before,
+        total_shares_after: shares_before + shares,
+        investor_shares_after: shares,
+    }
+}
+
+#[test]
+fn nav_per_share_separates_returns_from_flows() {
+    let vault = Pubkey::new_unique();
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let events = [
+        deposit(vault, alice, 0, 1_000_000, 0, 0, 1_000_000),
+        // the vault made 10% by the time bob joins at a NAV of 1.1
+        deposit(
+            vault,
+            bob,
+            YEAR / 2,
+            1_100_000,
+            1_100_000,
+            1_000_000,
+            1_000_000,
+        ),
+    ];
+    let mut history = NavHistory::default();
+    history.record_events(&events);
+    history.record(NavSnapshot {
+        vault,
+        ts: YEAR,
+        equity: 2_420_000,
+        total_shares: 2_000_000,
+    });
+
+    assert_eq!(history.series(&vault).len(), 3);
+    assert_eq!(history.nav_at(&vault, 0), Some(1.0));
+    assert_eq!(history.nav_at(&vault, YEAR - 1), Some(1.1));
+    assert_close(history.time_weighted_return(&vault, 0, YEAR).unwrap(), 0.21);
+    assert_close(
+        history
+            .annualized_time_weighted_return(&vault, 0, YEAR)
+            .unwrap(),
+        0.21,
+    );
+    assert_eq!(history.nav_pnl(&vault).high_water_mark(), Some(1.21));
+
+    // alice earned 21% over a year, bob 10% over half a year
+    assert_close(
+        history
+            .money_weighted_return(&events, &vault, &alice, YEAR)
+            .unwrap(),
+        0.21,
+    );
+    assert_close(
+        history
+            .money_weighted_return(&events, &vault, &bob, YEAR)
+            .unwrap(),
+        0.21,
+    );
+    assert_eq!(
+        history.money_weighted_return(&events, &vault, &Pubkey::new_unique(), YEAR),
+        None
+    );
+}
+
+#[test]
+fn nav_store_appends_and_reloads_each_vault() -> anyhow::Result<()> {
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let store = NavStore::new(std::env::temp_dir().join(format!("nav_{}.jsonl", a)));
+    assert!(store.load()?.series(&a).is_empty());
+
+    let snapshot = |vault, ts, equity, total_shares| NavSnapshot {
+        vault,
+        ts,
+        equity,
+        total_shares,
+    };
+    store.append(&snapshot(a, 10, 1_100_000, 1_000_000))?;
+    store.append(&snapshot(b, 10, 5, u64::MAX as u128 + 1))?;
+    // appended out of order, and the same ts again replaces the earlier line
+    store.append(&snapshot(a, 0, 1_000_000, 1_000_000))?;
+    store.append(&snapshot(a, 10, 1_200_000, 1_000_000))?;
+
+    let history = store.load()?;
+    assert_eq!(
+        history.series(&a),
+        [
+            snapshot(a, 0, 1_000_000, 1_000_000),
+            snapshot(a, 10, 1_200_000, 1_000_000)
+        ]
+    );
+    assert_eq!(
+        history.series(&b),
+        [snapshot(b, 10, 5, u64::MAX as u128 + 1)]
+    );
+    assert_close(history.time_weighted_return(&a, 0, 10).unwrap(), 0.2);
+    std::fs::remove_file(store.path())?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn recording_funds_reports_failures_without_appending() -> anyhow::Result<()> {
+    // Nothing listens here, so every snapshot fails.
+    let client = RpcClient::new("http://127.0.0.1:1".to_string());
+    let vault = Pubkey::new_unique();
+    let funds: Vec<Box<dyn Fund + '_>> = vec![Box::new(PhoenixFund::new(&client, vault, vec![]))];
+    let store = NavStore::new(std::env::temp_dir().join(format!("nav_{}.jsonl", vault)));
+    let mut history = NavHistory::default();
+
+    let results = store.record_funds(&mut history, &funds).await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0, vault);
+    assert!(results[0].1.is_err());
+    assert!(history.series(&vault).is_empty());
+    assert!(!store.path().exists());
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+
+use bootstrap::amount::UiAmount;
+use bootstrap::helpers::{airdrop, get_lamports};
+use bootstrap::nonce::*;
+use bootstrap::offline::*;
+
+#[test]
+fn offline_transactions_collect_detached_signatures() -> anyhow::Result<()> {
+    let payer = Keypair::new();
+    let manager = Keypair::new();
+    let nonce = NonceInfo {
+        nonce: Pubkey::new_unique(),
+        authority: manager.pubkey(),
+        blockhash: Hash::new_unique(),
+    };
+    let ixs = [system_instruction::transfer(
+        &manager.pubkey(),
+        &payer.pubkey(),
+        1_000,
+    )];
+    let mut unsigned = OfflineTransaction::new(&ixs, &payer.pubkey(), &nonce);
+    assert_eq!(unsigned.blockhash()?, nonce.blockhash);
+    assert_eq!(unsigned.signers()?, vec![payer.pubkey(), manager.pubkey()]);
+
+    // The cold side only sees the exported JSON.
+    let mut cold: OfflineTransaction = serde_json::from_str(&serde_json::to_string(&unsigned)?)?;
+    let detached = cold.sign(&manager)?;
+    let detached = DetachedSignature::from_str(&detached.to_string())?;
+
+    unsigned.sign(&payer)?;
+    assert!(unsigned.assemble().is_err());
+    assert_eq!(unsigned.missing_signers()?, vec![manager.pubkey()]);
+
+    let stranger = Keypair::new();
+    let mut other = OfflineTransaction::new(&ixs, &payer.pubkey(), &nonce);
+    assert!(other.sign(&stranger).is_err());
+    assert!(unsigned
+        .add_signature(DetachedSignature {
+            signer: manager.pubkey(),
+            signature: other.sign(&payer)?.signature,
+        })
+        .is_err());
+
+    unsigned.add_signature(detached)?;
+    let tx = unsigned.assemble()?;
+    assert_eq!(tx.message.instructions.len(), 2);
+    assert_eq!(tx.signatures[1], detached.signature);
+    Ok(())
+}
+
+#[test]
+fn nonce_futures_are_send() {
+    fn assert_send<T: Send>(_: T) {}
+    let client = RpcClient::new("http://localhost:8899".to_string());
+    // as loaded by `load_signer`
+    let payer: Box<dyn Signer + Send + Sync> = Box::new(Keypair::new());
+    let nonce = Keypair::new();
+    let authority = nonce.pubkey();
+    assert_send(create_nonce_account(
+        &client,
+        payer.as_ref(),
+        &nonce,
+        &authority,
+    ));
+    assert_send(advance_nonce_account(
+        &client,
+        payer.as_ref(),
+        &nonce,
+        &authority,
+    ));
+    assert_send(authorize_nonce_account(
+        &client,
+        payer.as_ref(),
+        &nonce,
+        &authority,
+        &authority,
+    ));
+}
+
+/// Needs a local validator, run with `cargo test -- --ignored` against `anchor localnet`.
+#[tokio::test]
+#[ignore]
+async fn durable_nonce_transactions_land_once() -> anyhow::Result<()> {
+    let client = RpcClient::new_with_commitment(
+        "http://localhost:8899".to_string(),
+        CommitmentConfig::processed(),
+    );
+    let payer = Keypair::new();
+    let manager = Keypair::new();
+    let nonce = Keypair::new();
+    airdrop(&client, &payer.pubkey(), UiAmount::from(1)).await?;
+    airdrop(&client, &manager.pubkey(), UiAmount::from(1)).await?;
+
+    create_nonce_account(&client, &payer, &nonce, &manager.pubkey()).await?;
+    let info = fetch_nonce(&client, &nonce.pubkey()).await?;
+    assert_eq!(info.authority, manager.pubkey());
+    // A nonce can only advance once the slot it was stored in has passed.
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let ixs = [system_instruction::transfer(
+        &manager.pubkey(),
+        &payer.pubkey(),
+        1_000,
+    )];
+    let mut offline =
+        OfflineTransaction::build(&client, &ixs, &payer.pubkey(), &nonce.pubkey()).await?;
+    offline.sign(&payer)?;
+    offline.sign(&manager)?;
+    let before = get_lamports(&client, &manager.pubkey()).await?;
+    offline.submit(&client).await?;
+    assert!(get_lamports(&client, &manager.pubkey()).await? < before);
+
+    // Landing advanced the nonce, so anything else signed against it is dead.
+    assert_ne!(
+        fetch_nonce(&client, &nonce.pubkey()).await?.blockhash,
+        info.blockhash
+    );
+    let mut stale = OfflineTransaction::new(&ixs[..0], &payer.pubkey(), &info);
+    stale.sign(&payer)?;
+    stale.sign(&manager)?;
+    assert!(stale.submit(&client).await.is_err());
+
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    advance_nonce_account(&client, &payer, &manager, &nonce.pubkey()).await?;
+    let cold = Keypair::new();
+    authorize_nonce_account(&client, &payer, &manager, &nonce.pubkey(), &cold.pubkey()).await?;
+    assert_eq!(
+        fetch_nonce(&client, &nonce.pubkey()).await?.authority,
+        cold.pubkey()
+    );
+    Ok(())
+}
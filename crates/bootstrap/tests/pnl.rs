@@ -0,0 +1,73 @@
+use bootstrap::drift_vaults::{SettlePnlExplanation, SettlePnlRecord};
+use bootstrap::pnl::*;
+use bootstrap::vault_update::QUOTE_PRECISION;
+use solana_sdk::pubkey::Pubkey;
+
+const DAY: i64 = 86_400;
+
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+}
+
+#[test]
+fn vault_pnl_measures_drawdown_and_risk() {
+    let equity = [100.0, 110.0, 88.0, 99.0, 110.0, 121.0];
+    let pnl = VaultPnl::from_equity_snapshots(
+        equity
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, equity)| EquitySnapshot {
+                ts: i as i64 * DAY,
+                equity: *equity,
+            })
+            .collect(),
+    );
+    assert_eq!(pnl.start_ts(), Some(0));
+    assert_eq!(pnl.cumulative_pnl(), 21.0);
+    assert_close(pnl.total_return().unwrap(), 0.21);
+    assert_eq!(pnl.returns().len(), 5);
+    assert_eq!(pnl.win_rate(), Some(0.8));
+    assert_eq!(pnl.high_water_mark(), Some(121.0));
+
+    let drawdown = pnl.max_drawdown().unwrap();
+    assert_eq!(drawdown.peak_ts, DAY);
+    assert_eq!(drawdown.trough_ts, 2 * DAY);
+    assert_eq!(drawdown.recovery_ts, Some(4 * DAY));
+    assert_eq!(drawdown.amount, 22.0);
+    assert_close(drawdown.depth, 0.2);
+    assert_eq!(drawdown.duration, 3 * DAY);
+
+    let stats = pnl.stats(0.05);
+    assert!(stats.annualized_volatility.unwrap() > 0.0);
+    assert!(stats.sharpe_ratio.unwrap() > 0.0);
+    assert!(stats.sortino_ratio.unwrap() > stats.sharpe_ratio.unwrap());
+
+    let flat = VaultPnl::from_equity_snapshots(vec![EquitySnapshot {
+        ts: 0,
+        equity: 100.0,
+    }]);
+    assert_eq!(flat.max_drawdown(), None);
+    assert_eq!(flat.sharpe_ratio(0.0), None);
+}
+
+#[test]
+fn vault_pnl_replays_settle_pnl_records() {
+    let record = |ts: i64, pnl: i128| SettlePnlRecord {
+        ts,
+        user: Pubkey::new_unique(),
+        market_index: 0,
+        pnl: pnl * QUOTE_PRECISION as i128,
+        base_asset_amount: 0,
+        quote_asset_amount_after: 0,
+        quote_entry_amount: 0,
+        settle_price: 0,
+        explanation: SettlePnlExplanation::None,
+    };
+    let pnl = VaultPnl::from_settle_pnl_records(&[record(2 * DAY, -20), record(DAY, 50)], 1_000.0);
+    let equity: Vec<f64> = pnl.data.iter().map(|s| s.equity).collect();
+    assert_eq!(equity, vec![1_000.0, 1_050.0, 1_030.0]);
+    assert_eq!(pnl.cumulative_pnl(), 30.0);
+    assert_eq!(pnl.win_rate(), Some(0.5));
+    assert_eq!(pnl.max_drawdown().unwrap().recovery_ts, None);
+}
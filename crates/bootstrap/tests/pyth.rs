@@ -0,0 +1,93 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use bootstrap::amount::UiAmount;
+use bootstrap::helpers::airdrop;
+use bootstrap::pyth::*;
+
+fn price_account(price: i64, conf: u64, expo: i32, status: u32, publish_slot: u64) -> Vec<u8> {
+    let mut data = vec![0u8; PYTH_PRICE_ACCOUNT_SIZE];
+    data[20..24].copy_from_slice(&expo.to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[216..224].copy_from_slice(&conf.to_le_bytes());
+    data[224..228].copy_from_slice(&status.to_le_bytes());
+    data[232..240].copy_from_slice(&publish_slot.to_le_bytes());
+    data
+}
+
+#[test]
+fn pyth_price_checks_staleness_and_confidence() -> anyhow::Result<()> {
+    let price = PythPrice::decode(&price_account(14_250_000_000, 10_000_000, -8, 1, 1_000))?;
+    assert_eq!(price.status, PriceStatus::Trading);
+    assert_eq!(price.publish_slot, 1_000);
+    assert_eq!(price.ui_price(), 142.5);
+    assert_eq!(price.ui_conf(), 0.1);
+
+    let checks = PriceChecks::default();
+    assert_eq!(price.checked_price(1_010, &checks)?, 142.5);
+    assert!(price.checked_price(1_100, &checks).is_err());
+
+    let wide = PythPrice {
+        conf: 1_000_000_000,
+        ..price
+    };
+    assert!(wide.checked_price(1_000, &checks).is_err());
+
+    let halted = PythPrice {
+        status: PriceStatus::Halted,
+        ..price
+    };
+    assert!(halted.checked_price(1_000, &checks).is_err());
+    assert_eq!(
+        halted.checked_price(u64::MAX, &PriceChecks::localnet())?,
+        142.5
+    );
+    assert!(wide.checked_price(1_000, &PriceChecks::localnet()).is_err());
+
+    assert!(PythPrice::decode(&[0u8; 100]).is_err());
+    Ok(())
+}
+
+#[test]
+fn mock_feeds_pass_localnet_checks() -> anyhow::Result<()> {
+    let mock = mock_price(142.5, -8);
+    assert_eq!(mock.ui_price(), 142.5);
+    assert_eq!(
+        mock.checked_price(1_000_000, &PriceChecks::localnet())?,
+        142.5
+    );
+    // never trading, so the mainnet defaults reject it
+    assert!(mock.checked_price(0, &PriceChecks::default()).is_err());
+    let trading = PythPrice {
+        status: PriceStatus::Trading,
+        ..mock
+    };
+    assert_eq!(trading.checked_price(0, &PriceChecks::default())?, 142.5);
+    Ok(())
+}
+
+/// Needs the mock Pyth program, run against `anchor localnet`.
+#[tokio::test]
+#[ignore]
+async fn mock_feeds_read_back_checked() -> anyhow::Result<()> {
+    let client = RpcClient::new_with_commitment(
+        "http://localhost:8899".to_string(),
+        CommitmentConfig::processed(),
+    );
+    let payer = Keypair::new();
+    airdrop(&client, &payer.pubkey(), UiAmount::from(1)).await?;
+
+    let feed = Keypair::new();
+    create_mock_price_feed(&client, &payer, &feed, 142.5, -8).await?;
+    let checks = PriceChecks::localnet();
+    let prices = fetch_checked_prices(&client, &[feed.pubkey()], &checks).await?;
+    assert_eq!(prices, vec![142.5]);
+
+    set_mock_price_info(&client, &payer, &feed.pubkey(), 150.0, 0.5).await?;
+    let price = fetch_pyth_price(&client, &feed.pubkey()).await?;
+    assert_eq!(price.ui_price(), 150.0);
+    assert!(price.publish_slot > 0);
+    Ok(())
+}
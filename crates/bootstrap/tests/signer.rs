@@ -0,0 +1,150 @@
+use std::str::FromStr;
+
+use solana_sdk::derivation_path::DerivationPath;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::Message;
+use solana_sdk::signature::{write_keypair_file, Keypair, Presigner};
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+
+use bootstrap::helpers::sign_tx;
+use bootstrap::signer::*;
+
+const PHRASE: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+#[test]
+fn signer_sources_parse() -> anyhow::Result<()> {
+    assert_eq!(SignerSource::from_str("")?, SignerSource::Default);
+    assert_eq!(SignerSource::from_str("default")?, SignerSource::Default);
+    assert_eq!(
+        SignerSource::from_str("env:PROPSHOP_KEY")?,
+        SignerSource::Env("PROPSHOP_KEY".to_string())
+    );
+    assert_eq!(
+        SignerSource::from_str("keys/payer.json")?,
+        SignerSource::File("keys/payer.json".into())
+    );
+    assert_eq!(
+        SignerSource::from_str("[1, 2, 3]")?,
+        SignerSource::Bytes(vec![1, 2, 3])
+    );
+    assert_eq!(
+        SignerSource::from_str(&format!("mnemonic:{}?key=0/0", PHRASE))?,
+        SignerSource::Mnemonic {
+            phrase: PHRASE.to_string(),
+            passphrase: String::new(),
+            derivation_path: Some(DerivationPath::from_key_str("0/0").unwrap()),
+        }
+    );
+    assert!(SignerSource::from_str(&format!("mnemonic:{}?account=0", PHRASE)).is_err());
+    Ok(())
+}
+
+#[test]
+fn signers_load_from_files_bytes_base58_and_env() -> anyhow::Result<()> {
+    let keypair = Keypair::new();
+    let path = std::env::temp_dir().join(format!("signer_{}.json", keypair.pubkey()));
+    write_keypair_file(&keypair, &path).map_err(|e| anyhow::anyhow!("{:?}", e))?;
+
+    let from_file = load_keypair(path.to_str().unwrap())?;
+    let from_bytes = load_keypair(&serde_json::to_string(&keypair.to_bytes().to_vec())?)?;
+    let from_base58 = load_signer(&keypair.to_base58_string())?;
+    std::env::set_var("BOOTSTRAP_SIGNER_TEST_KEY", keypair.to_base58_string());
+    let from_env = load_signer("env:BOOTSTRAP_SIGNER_TEST_KEY")?;
+
+    assert_eq!(from_file.pubkey(), keypair.pubkey());
+    assert_eq!(from_bytes.pubkey(), keypair.pubkey());
+    assert_eq!(from_base58.pubkey(), keypair.pubkey());
+    assert_eq!(from_env.pubkey(), keypair.pubkey());
+
+    assert!(load_keypair("env:BOOTSTRAP_SIGNER_TEST_UNSET").is_err());
+    assert!(load_keypair("not-a-key").is_err());
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+#[test]
+fn mnemonic_derivation_paths() -> anyhow::Result<()> {
+    let legacy = load_keypair(&format!("mnemonic:{}", PHRASE))?;
+    let wallet = load_keypair(&format!("mnemonic:{}?key=0/0", PHRASE))?;
+    let same_wallet = load_keypair(&format!(
+        "mnemonic:{}?path={}",
+        PHRASE, DEFAULT_DERIVATION_PATH
+    ))?;
+    let second_account = load_keypair(&format!("mnemonic:{}?key=1/0", PHRASE))?;
+
+    assert_eq!(wallet.pubkey(), same_wallet.pubkey());
+    assert_ne!(wallet.pubkey(), legacy.pubkey());
+    assert_ne!(wallet.pubkey(), second_account.pubkey());
+    assert_eq!(
+        keypair_from_mnemonic(PHRASE, "", None)?.pubkey(),
+        legacy.pubkey()
+    );
+    assert_ne!(
+        keypair_from_mnemonic(PHRASE, "passphrase", None)?.pubkey(),
+        legacy.pubkey()
+    );
+    assert!(keypair_from_mnemonic("abandon abandon about", "", None).is_err());
+    Ok(())
+}
+
+#[test]
+fn debug_redacts_secrets() -> anyhow::Result<()> {
+    let keypair = Keypair::new();
+    let base58 = keypair.to_base58_string();
+    let mnemonic = SignerSource::from_str(&format!("mnemonic:{}?key=0/0", PHRASE))?;
+    let debug = format!("{:?}", mnemonic);
+    assert!(!debug.contains("abandon"), "{}", debug);
+    assert!(debug.contains("derivation_path"), "{}", debug);
+    assert_eq!(
+        format!("{:?}", SignerSource::Bytes(keypair.to_bytes().to_vec())),
+        "Bytes(\"<redacted>\")"
+    );
+    let debug = format!("{:?}", SignerSource::Base58(base58.clone()));
+    assert!(!debug.contains(&base58), "{}", debug);
+    assert_eq!(
+        format!("{:?}", SignerSource::Env("PROPSHOP_KEY".to_string())),
+        "Env(\"PROPSHOP_KEY\")"
+    );
+    Ok(())
+}
+
+#[test]
+fn mistyped_mnemonics_are_rejected() -> anyhow::Result<()> {
+    // Every word is in the wordlist, but the last one no longer matches the checksum.
+    let bad_checksum = PHRASE.replace("about", "abandon");
+    assert!(keypair_from_mnemonic(&bad_checksum, "", None).is_err());
+    assert!(load_keypair(&format!("mnemonic:{}?key=0/0", bad_checksum)).is_err());
+    // A word that is not in the wordlist.
+    let misspelled = PHRASE.replace("about", "abuot");
+    assert!(keypair_from_mnemonic(&misspelled, "", None).is_err());
+    Ok(())
+}
+
+#[test]
+fn presigned_signatures_complete_transactions() -> anyhow::Result<()> {
+    let payer = load_signer(&Keypair::new().to_base58_string())?;
+    let offline = Keypair::new();
+    let ixs = [system_instruction::transfer(
+        &offline.pubkey(),
+        &payer.pubkey(),
+        1_000,
+    )];
+    let blockhash = Hash::new_unique();
+
+    // The offline signer only ever sees the message bytes.
+    let mut message = Message::new(&ixs, Some(&payer.pubkey()));
+    message.recent_blockhash = blockhash;
+    let signature = offline.sign_message(&message.serialize());
+    let presigner = Presigner::new(&offline.pubkey(), &signature);
+
+    let signers: [&dyn Signer; 2] = [payer.as_ref(), &presigner];
+    let tx = sign_tx(payer.as_ref(), &ixs, &signers, blockhash)?;
+    tx.verify()?;
+    assert_eq!(tx.signatures[1], signature);
+
+    // A presigned signature only covers the message it was made for.
+    assert!(sign_tx(payer.as_ref(), &ixs, &signers, Hash::new_unique()).is_err());
+    Ok(())
+}
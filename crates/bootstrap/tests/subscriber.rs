@@ -0,0 +1,71 @@
+use anchor_lang::Discriminator;
+use bootstrap::drift_vaults::{VaultDepositor, DRIFT_VAULTS_PROGRAM_ID};
+use bootstrap::subscriber::*;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+
+fn vault_depositor(shares: u8) -> Account {
+    let mut data = VaultDepositor::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&[0u8; 296]);
+    // low byte of vault_shares
+    data[8 + 96] = shares;
+    Account {
+        lamports: 1,
+        data,
+        owner: DRIFT_VAULTS_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+#[test]
+fn subscriber_deduplicates_by_slot_and_data() {
+    let subscriber = VaultSubscriber::new(SubscriberConfig::new(
+        "http://localhost:8899",
+        "ws://localhost:8900",
+    ));
+    let mut receiver = subscriber.subscribe();
+    let key = Pubkey::new_unique();
+
+    assert!(subscriber.publish(key, 10, &vault_depositor(1)));
+    // replayed or stale notifications
+    assert!(!subscriber.publish(key, 10, &vault_depositor(2)));
+    assert!(!subscriber.publish(key, 9, &vault_depositor(2)));
+    // a poll at a later slot that found nothing new
+    assert!(!subscriber.publish(key, 11, &vault_depositor(1)));
+    assert!(subscriber.publish(key, 12, &vault_depositor(2)));
+
+    let other = Account {
+        owner: Pubkey::new_unique(),
+        ..vault_depositor(3)
+    };
+    assert!(!subscriber.publish(Pubkey::new_unique(), 13, &other));
+
+    let first = receiver.try_recv().unwrap();
+    assert_eq!(first.slot, 10);
+    let second = receiver.try_recv().unwrap();
+    assert_eq!(second.slot, 12);
+    match second.account {
+        VaultAccount::VaultDepositor(depositor) => assert_eq!(depositor.vault_shares, 2),
+        account => panic!("unexpected account: {:?}", account),
+    }
+    assert!(receiver.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn subscriber_reports_failed_polls() {
+    let mut config = SubscriberConfig::new("http://127.0.0.1:1", "ws://127.0.0.1:1");
+    config.programs = vec![DRIFT_VAULTS_PROGRAM_ID];
+    config.mode = SubscriptionMode::Polling;
+    let subscriber = std::sync::Arc::new(VaultSubscriber::new(config));
+    let mut errors = subscriber.subscribe_errors();
+    let handles = subscriber.spawn();
+
+    let error = tokio::time::timeout(std::time::Duration::from_secs(10), errors.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(error.key, DRIFT_VAULTS_PROGRAM_ID);
+    assert!(!error.error.is_empty());
+    handles.iter().for_each(|h| h.abort());
+}
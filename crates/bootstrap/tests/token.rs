@@ -0,0 +1,272 @@
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use spl_token_2022::extension::{ExtensionType, StateWithExtensionsMut};
+use spl_token_2022::solana_program::program_option::COption;
+use spl_token_2022::solana_program::program_pack::Pack;
+use spl_token_2022::state::{Account, AccountState, Mint};
+
+use bootstrap::amount::{TokenAmount, UiAmount};
+use bootstrap::helpers::*;
+use bootstrap::token::*;
+
+fn transfer_fee(authority: &Pubkey) -> MintExtension {
+    MintExtension::TransferFee {
+        authority: Some(*authority),
+        basis_points: 50,
+        maximum_fee: 1_000_000,
+    }
+}
+
+#[test]
+fn mints_are_sized_for_their_extensions() -> anyhow::Result<()> {
+    let authority = Pubkey::new_unique();
+    assert_eq!(mint_len(&[])?, Mint::LEN);
+    // Base account length and account type, then a 4 byte header per extension.
+    assert_eq!(mint_len(&[transfer_fee(&authority)])?, 166 + 4 + 108);
+    let all = [
+        transfer_fee(&authority),
+        MintExtension::MetadataPointer {
+            authority: Some(authority),
+            metadata: None,
+        },
+        MintExtension::InterestBearing {
+            rate_authority: None,
+            rate: 500,
+        },
+    ];
+    assert_eq!(mint_len(&all)?, 166 + (4 + 108) + (4 + 64) + (4 + 52));
+
+    let mint = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let ixs = create_mint_ixs(
+        &spl_token_2022::id(),
+        &payer,
+        &authority,
+        None,
+        6,
+        &mint,
+        &all,
+        1_000_000,
+    )?;
+    assert_eq!(ixs.len(), 5);
+    assert!(ixs[1..]
+        .iter()
+        .all(|ix| ix.program_id == spl_token_2022::id()));
+
+    let ixs = create_mint_ixs(&spl_token::id(), &payer, &authority, None, 6, &mint, &[], 1)?;
+    assert_eq!(ixs.len(), 2);
+    assert_eq!(ixs[1].program_id, spl_token::id());
+    assert!(create_mint_ixs(
+        &spl_token::id(),
+        &payer,
+        &authority,
+        None,
+        6,
+        &mint,
+        &all,
+        1
+    )
+    .is_err());
+    assert!(create_mint_ixs(&payer, &payer, &authority, None, 6, &mint, &[], 1).is_err());
+    Ok(())
+}
+
+#[test]
+fn self_described_mints_reserve_rent_for_metadata() -> anyhow::Result<()> {
+    let mint = Pubkey::new_unique();
+    let pointer = |metadata| MintExtension::MetadataPointer {
+        authority: None,
+        metadata: Some(metadata),
+    };
+    let elsewhere = [pointer(Pubkey::new_unique())];
+    let itself = [pointer(mint)];
+    assert_eq!(mint_rent_len(&mint, &elsewhere)?, mint_len(&elsewhere)?);
+    assert_eq!(
+        mint_rent_len(&mint, &itself)?,
+        mint_len(&itself)? + MINT_METADATA_RESERVE_LEN
+    );
+
+    // Only the rent is paid up front, the account keeps the size Token-2022 expects.
+    let ixs = create_mint_ixs(
+        &spl_token_2022::id(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        None,
+        6,
+        &mint,
+        &itself,
+        42,
+    )?;
+    // CreateAccount data is a 4 byte tag, then the lamports and space.
+    let data = &ixs[0].data;
+    assert_eq!(u64::from_le_bytes(data[4..12].try_into()?), 42);
+    assert_eq!(
+        u64::from_le_bytes(data[12..20].try_into()?),
+        mint_len(&itself)? as u64
+    );
+    Ok(())
+}
+
+#[test]
+fn accounts_of_either_token_program_unpack() -> anyhow::Result<()> {
+    let authority = Pubkey::new_unique();
+    let mint = Mint {
+        mint_authority: COption::Some(authority),
+        supply: 42,
+        decimals: 6,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    let mut data = vec![0; Mint::LEN];
+    Mint::pack(mint, &mut data)?;
+    assert_eq!(unpack_mint(&data)?, mint);
+    assert!(mint_extension_types(&data)?.is_empty());
+
+    let mut data = vec![0; mint_len(&[transfer_fee(&authority)])?];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data)?;
+    state.init_extension::<TransferFeeConfig>(true)?;
+    state.base = mint;
+    state.pack_base();
+    state.init_account_type()?;
+    assert_eq!(unpack_mint(&data)?, mint);
+    assert_eq!(
+        mint_extension_types(&data)?,
+        vec![ExtensionType::TransferFeeConfig]
+    );
+
+    let account = Account {
+        mint: Pubkey::new_unique(),
+        owner: authority,
+        amount: 7,
+        state: AccountState::Initialized,
+        ..Default::default()
+    };
+    let mut data = vec![0; Account::LEN];
+    Account::pack(account, &mut data)?;
+    assert_eq!(unpack_token_account(&data)?, account);
+    assert!(unpack_token_account(&data[..Mint::LEN]).is_err());
+    Ok(())
+}
+
+#[test]
+fn wsol_instructions_wrap_into_the_associated_account() -> anyhow::Result<()> {
+    let payer = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let wsol = get_wsol_address(&owner);
+    assert_eq!(native_mint(), spl_token::native_mint::id());
+
+    let ixs = wrap_sol_ixs(&payer, &owner, 1_000_000_000)?;
+    assert_eq!(ixs.len(), 3);
+    assert_eq!(ixs[0].program_id, spl_associated_token_account::id());
+    assert_eq!(ixs[1].accounts[1].pubkey, wsol);
+    assert_eq!(ixs[2], sync_native_ix(&wsol)?);
+    assert_eq!(ixs[2].program_id, spl_token::id());
+
+    let close = close_account_ix(&spl_token::id(), &wsol, &owner, &owner)?;
+    assert_eq!(close.accounts[0].pubkey, wsol);
+    assert_eq!(close.accounts[1].pubkey, owner);
+    assert!(close.accounts[2].is_signer);
+    assert!(close_account_ix(&payer, &wsol, &owner, &owner).is_err());
+    Ok(())
+}
+
+/// Needs a local validator, run with `cargo test -- --ignored` against `anchor localnet`.
+#[tokio::test]
+#[ignore]
+async fn wsol_wraps_and_unwraps() -> anyhow::Result<()> {
+    let client = RpcClient::new_with_commitment(
+        "http://localhost:8899".to_string(),
+        CommitmentConfig::processed(),
+    );
+    let owner = Keypair::new();
+    airdrop(&client, &owner.pubkey(), UiAmount::from(2)).await?;
+
+    let (wsol, _) = wrap_sol(&client, &owner, UiAmount::from(1)).await?;
+    assert_eq!(get_token_balance(&client, &wsol).await?, 1_000_000_000);
+
+    // Lamports sent directly only count once synced.
+    let transfer = solana_sdk::system_instruction::transfer(&owner.pubkey(), &wsol, 500_000_000);
+    send_and_confirm_tx(&client, &owner, &[transfer], &[&owner]).await?;
+    assert_eq!(get_token_balance(&client, &wsol).await?, 1_000_000_000);
+    sync_native(&client, &owner, &wsol).await?;
+    assert_eq!(get_token_balance(&client, &wsol).await?, 1_500_000_000);
+
+    let before = get_lamports(&client, &owner.pubkey()).await?;
+    unwrap_sol(&client, &owner).await?;
+    assert!(get_account(&client, &wsol).await.is_err());
+    // The balance and the account's rent, less the fee.
+    assert!(get_lamports(&client, &owner.pubkey()).await? > before + 1_500_000_000);
+    Ok(())
+}
+
+/// Needs a local validator with the Token-2022 program, which `solana-test-validator` loads.
+/// Run with `cargo test -- --ignored` against `anchor localnet`.
+#[tokio::test]
+#[ignore]
+async fn token_2022_mints_create_and_mint() -> anyhow::Result<()> {
+    let client = RpcClient::new_with_commitment(
+        "http://localhost:8899".to_string(),
+        CommitmentConfig::processed(),
+    );
+    let payer = Keypair::new();
+    let mint = Keypair::new();
+    airdrop(&client, &payer.pubkey(), UiAmount::from(1)).await?;
+
+    create_token_2022_mint(
+        &client,
+        &payer,
+        &payer.pubkey(),
+        None,
+        6,
+        &mint,
+        &[
+            transfer_fee(&payer.pubkey()),
+            MintExtension::InterestBearing {
+                rate_authority: None,
+                rate: 500,
+            },
+        ],
+    )
+    .await?;
+    assert_eq!(
+        get_token_program(&client, &mint.pubkey()).await?,
+        spl_token_2022::id()
+    );
+    assert_eq!(get_mint(&client, &mint.pubkey()).await?.decimals, 6);
+
+    let (ata, _) =
+        create_associated_token_account(&client, &payer, &mint.pubkey(), &spl_token_2022::id())
+            .await?;
+    mint_tokens(
+        &client,
+        &payer,
+        &payer,
+        &mint.pubkey(),
+        &ata,
+        TokenAmount::new(1_000_000, 6),
+        None,
+    )
+    .await?;
+    // mint_to_checked rejects an amount made for other decimals than the mint's
+    assert!(mint_tokens(
+        &client,
+        &payer,
+        &payer,
+        &mint.pubkey(),
+        &ata,
+        TokenAmount::new(1_000_000, 9),
+        None,
+    )
+    .await
+    .is_err());
+    let account = get_token_account(&client, &ata).await?;
+    assert_eq!(account.mint, mint.pubkey());
+    assert_eq!(account.amount, 1_000_000);
+    Ok(())
+}
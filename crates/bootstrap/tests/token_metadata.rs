@@ -0,0 +1,47 @@
+use anchor_lang::AnchorDeserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use bootstrap::market_bootstrap::BootstrapManifest;
+use bootstrap::token_metadata::*;
+
+#[test]
+fn metadata_instructions_match_the_program() -> anyhow::Result<()> {
+    let mint = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let metadata: MintMetadata =
+        serde_json::from_str(r#"{"name":"Mock USD Coin","symbol":"USDC"}"#)?;
+    assert_eq!(metadata, MintMetadata::new("Mock USD Coin", "USDC"));
+
+    let ix = create_metadata_account_v3_ix(&mint, &authority, &payer, &authority, &metadata);
+    assert_eq!(ix.program_id, TOKEN_METADATA_PROGRAM_ID);
+    assert_eq!(ix.accounts[0].pubkey, get_metadata_address(&mint).0);
+    assert!(ix.accounts[0].is_writable);
+    assert!(ix.accounts[2].is_signer && ix.accounts[3].is_signer && ix.accounts[4].is_signer);
+    assert_eq!(ix.data[0], CREATE_METADATA_ACCOUNT_V3);
+    let args = CreateMetadataAccountArgsV3::try_from_slice(&ix.data[1..])?;
+    assert_eq!(args.data.name, "Mock USD Coin");
+    assert_eq!(args.data.symbol, "USDC");
+    assert_eq!(args.data.uri, "");
+    assert_eq!(args.data.creators, None);
+    // name, symbol and uri with their length prefixes, then the fee and four empty options.
+    assert_eq!(ix.data.len(), 1 + (4 + 13) + (4 + 4) + 4 + 2 + 3 + 1 + 1);
+
+    let too_long = MintMetadata::new("Mock USD Coin", "MOCKUSDCOIN");
+    assert!(too_long.validate().is_err());
+    for mint in BootstrapManifest::mock().mints {
+        mint.metadata.unwrap().validate()?;
+    }
+    Ok(())
+}
+
+#[test]
+fn localnet_clones_the_metadata_program() {
+    let anchor_toml = include_str!("../../../Anchor.toml");
+    let clones: Vec<&str> = anchor_toml
+        .split("[[test.validator.clone]]")
+        .skip(1)
+        .filter_map(|section| section.lines().find(|l| l.starts_with("address")))
+        .collect();
+    assert!(clones.contains(&format!("address = \"{}\"", TOKEN_METADATA_PROGRAM_ID).as_str()));
+}
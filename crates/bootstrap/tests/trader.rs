@@ -0,0 +1,123 @@
+use borsh_v09::BorshDeserialize;
+use phoenix::program::{MarketHeader, MarketSizeParams, PhoenixInstruction, TokenParams};
+use phoenix::quantities::{
+    BaseAtomsPerBaseLot, QuoteAtomsPerBaseUnitPerTick, QuoteAtomsPerQuoteLot, WrapperU64,
+};
+use phoenix::state::{OrderPacket, Side};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use bootstrap::amount::UiAmount;
+use bootstrap::market::PhoenixMarket;
+use bootstrap::phoenix_vaults::*;
+
+/// SOL/USDC with 0.001 SOL lots, 0.00001 USDC quote lots and 0.001 USDC ticks.
+fn sol_usdc_market() -> anyhow::Result<PhoenixMarket> {
+    let token = |decimals: u32| TokenParams {
+        decimals,
+        vault_bump: 0,
+        mint_key: Pubkey::new_unique(),
+        vault_key: Pubkey::new_unique(),
+    };
+    let header = MarketHeader::new(
+        MarketSizeParams::default(),
+        token(9),
+        BaseAtomsPerBaseLot::new(1_000_000),
+        token(6),
+        QuoteAtomsPerQuoteLot::new(10),
+        QuoteAtomsPerBaseUnitPerTick::new(1_000),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        1,
+    );
+    PhoenixMarket::new(Pubkey::new_unique(), bytemuck::bytes_of(&header).to_vec())
+}
+
+fn order(order_type: OrderType, price: &str, size: &str) -> anyhow::Result<VaultOrder> {
+    Ok(VaultOrder {
+        side: Side::Bid,
+        order_type,
+        price: price.parse::<UiAmount>()?,
+        size: size.parse::<UiAmount>()?,
+        client_order_id: 7,
+    })
+}
+
+#[test]
+fn order_packets_match_the_order_type() -> anyhow::Result<()> {
+    let market = sol_usdc_market()?;
+    for order_type in [
+        OrderType::Limit,
+        OrderType::PostOnly,
+        OrderType::ImmediateOrCancel,
+    ] {
+        let packet = order_packet(&market, &order(order_type, "150.25", "1.5")?)?;
+        match (order_type, &packet) {
+            (OrderType::Limit, OrderPacket::Limit { .. })
+            | (OrderType::PostOnly, OrderPacket::PostOnly { .. })
+            | (OrderType::ImmediateOrCancel, OrderPacket::ImmediateOrCancel { .. }) => {}
+            _ => panic!("{:?} built {:?}", order_type, packet),
+        }
+        assert_eq!(packet.side(), Side::Bid);
+        assert_eq!(packet.get_price_in_ticks().as_u64(), 150_250);
+        assert_eq!(packet.num_base_lots().as_u64(), 1_500);
+        assert_eq!(packet.client_order_id(), 7);
+    }
+    Ok(())
+}
+
+#[test]
+fn orders_are_forwarded_as_place_limit_order_with_free_funds() -> anyhow::Result<()> {
+    let market = sol_usdc_market()?;
+    for order_type in [OrderType::PostOnly, OrderType::ImmediateOrCancel] {
+        let packet = order_packet(&market, &order(order_type, "150.25", "1.5")?)?;
+        let data = place_order_data(&packet)?;
+        // phoenix_vaults rejects the `SwapWithFreeFunds` tag Phoenix's builder uses for IOC packets
+        assert_eq!(
+            data[0],
+            PhoenixInstruction::PlaceLimitOrderWithFreeFunds as u8
+        );
+        assert_eq!(OrderPacket::try_from_slice(&data[1..])?, packet);
+    }
+    Ok(())
+}
+
+#[test]
+fn order_packets_reject_partial_ticks_and_lots() -> anyhow::Result<()> {
+    let market = sol_usdc_market()?;
+    assert!(order_packet(&market, &order(OrderType::Limit, "150.2505", "1")?).is_err());
+    assert!(order_packet(&market, &order(OrderType::Limit, "0", "1")?).is_err());
+    assert!(order_packet(&market, &order(OrderType::Limit, "150", "1.0005")?).is_err());
+    assert!(order_packet(&market, &order(OrderType::Limit, "150", "0.0000000001")?).is_err());
+    Ok(())
+}
+
+#[test]
+fn position_change_nets_free_and_locked_lots() -> anyhow::Result<()> {
+    let market = sol_usdc_market()?;
+    let before = MarketPosition {
+        market: market.key,
+        quote_lots_free: 10_000_000,
+        quote_lots_locked: 5_000_000,
+        ..Default::default()
+    };
+    // bought 1 SOL for 150 USDC, 50 USDC still resting in a bid
+    let after = MarketPosition {
+        market: market.key,
+        quote_lots_free: 0,
+        quote_lots_locked: 500_000,
+        base_lots_free: 1_000,
+        base_lots_locked: 0,
+    };
+    let change = position_change(Signature::default(), &market, before, after)?;
+    assert_eq!(change.base_filled.to_string(), "1");
+    assert!(!change.base_filled.negative);
+    assert_eq!(change.quote_filled.to_string(), "-145");
+    assert!(change.quote_filled.negative);
+
+    let unchanged = position_change(Signature::default(), &market, after, after)?;
+    assert_eq!(unchanged.base_filled.amount.atoms, 0);
+    assert_eq!(unchanged.quote_filled.amount.atoms, 0);
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use phoenix::program::{MarketHeader, MarketSizeParams, TokenParams};
+use phoenix::quantities::{
+    BaseAtomsPerBaseLot, QuoteAtomsPerBaseUnitPerTick, QuoteAtomsPerQuoteLot, WrapperU64,
+};
+use solana_sdk::pubkey::Pubkey;
+
+use bootstrap::market::PhoenixMarket;
+use bootstrap::phoenix_vaults::*;
+
+fn header_only_market(base_mint: Pubkey, quote_mint: Pubkey) -> anyhow::Result<PhoenixMarket> {
+    let token = |mint_key: Pubkey| TokenParams {
+        decimals: 6,
+        vault_bump: 0,
+        mint_key,
+        vault_key: Pubkey::new_unique(),
+    };
+    let header = MarketHeader::new(
+        MarketSizeParams::default(),
+        token(base_mint),
+        BaseAtomsPerBaseLot::new(1_000),
+        token(quote_mint),
+        QuoteAtomsPerQuoteLot::new(1),
+        QuoteAtomsPerBaseUnitPerTick::new(1),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        1,
+    );
+    PhoenixMarket::new(Pubkey::new_unique(), bytemuck::bytes_of(&header).to_vec())
+}
+
+#[test]
+fn thin_book_flags_positions_the_bids_cannot_absorb() {
+    let bids = [(100.0, 1.0), (99.0, 1.0), (90.0, 10.0)];
+
+    // sells into the first two levels, within 1% of a 100 mark
+    let proceeds = liquidation_proceeds(&bids, 2.0);
+    assert_eq!(proceeds, 199.0);
+    assert!(!is_thin_book(
+        2.0,
+        Some(100.0),
+        proceeds,
+        DEFAULT_MAX_SLIPPAGE
+    ));
+
+    // has to reach down to 90
+    let proceeds = liquidation_proceeds(&bids, 5.0);
+    assert_eq!(proceeds, 469.0);
+    assert!(is_thin_book(
+        5.0,
+        Some(100.0),
+        proceeds,
+        DEFAULT_MAX_SLIPPAGE
+    ));
+
+    // more base than the whole book
+    let proceeds = liquidation_proceeds(&bids, 20.0);
+    assert_eq!(proceeds, 1_099.0);
+    assert!(is_thin_book(
+        20.0,
+        Some(90.0),
+        proceeds,
+        DEFAULT_MAX_SLIPPAGE
+    ));
+
+    assert!(is_thin_book(1.0, None, 0.0, DEFAULT_MAX_SLIPPAGE));
+    assert!(!is_thin_book(0.0, None, 0.0, DEFAULT_MAX_SLIPPAGE));
+}
+
+#[test]
+fn vault_valuation_breaks_down_assets() {
+    let usdc_mint = Pubkey::new_unique();
+    let sol_mint = Pubkey::new_unique();
+    let position = PositionValuation {
+        market: Pubkey::new_unique(),
+        base_mint: sol_mint,
+        quote_mint: usdc_mint,
+        base_units: 2.0,
+        quote_units: 50.0,
+        price: Some(100.0),
+        quote_price: Some(1.0),
+        value: 250.0,
+        liquidation_value: 240.0,
+        thin_book: true,
+    };
+    let valuation = VaultValuation {
+        usdc_mint,
+        usdc: 1_000.0,
+        sol_mint,
+        sol: 0.5,
+        sol_price: Some(100.0),
+        positions: vec![position],
+    };
+    assert_eq!(valuation.total(), 1_300.0);
+    assert_eq!(valuation.total_atoms(), 1_300_000_000);
+    assert_eq!(valuation.liquidation_total(), 1_290.0);
+    assert!(valuation.has_thin_book());
+
+    let assets = valuation.assets();
+    assert_eq!(assets.len(), 2);
+    assert_eq!(assets[0].mint, usdc_mint);
+    assert_eq!(assets[0].amount, 1_050.0);
+    assert_eq!(assets[1].mint, sol_mint);
+    assert_eq!(assets[1].amount, 2.5);
+    assert_eq!(assets[1].value, 250.0);
+}
+
+#[test]
+fn non_usdc_quotes_are_valued_through_their_usdc_market() -> anyhow::Result<()> {
+    let usdc_mint = Pubkey::new_unique();
+    let sol_mint = Pubkey::new_unique();
+    let jup_mint = Pubkey::new_unique();
+    let sol_usdc = header_only_market(sol_mint, usdc_mint)?;
+    let jup_sol = header_only_market(jup_mint, sol_mint)?;
+    let prices = FixedPrices(HashMap::from([(sol_usdc.key, 150.0), (jup_sol.key, 0.01)]));
+    let markets = HashMap::from([(sol_usdc.key, sol_usdc), (jup_sol.key, jup_sol.clone())]);
+
+    assert_eq!(
+        usdc_price(&usdc_mint, &usdc_mint, &markets, &prices)?,
+        Some(1.0)
+    );
+    assert_eq!(
+        usdc_price(&sol_mint, &usdc_mint, &markets, &prices)?,
+        Some(150.0)
+    );
+    // JUP is only quoted in SOL
+    assert_eq!(usdc_price(&jup_mint, &usdc_mint, &markets, &prices)?, None);
+
+    // 100 JUP and 1 SOL on JUP/SOL
+    let position = PositionValuation {
+        market: jup_sol.key,
+        base_mint: jup_mint,
+        quote_mint: sol_mint,
+        base_units: 100.0,
+        quote_units: 1.0,
+        price: Some(0.01),
+        quote_price: usdc_price(&sol_mint, &usdc_mint, &markets, &prices)?,
+        value: 2.0,
+        liquidation_value: 2.0,
+        thin_book: false,
+    };
+    let valuation = VaultValuation {
+        usdc_mint,
+        usdc: 10.0,
+        sol_mint,
+        sol: 1.0,
+        sol_price: Some(150.0),
+        positions: vec![position],
+    };
+    assert_eq!(valuation.total(), 10.0 + 150.0 + 300.0);
+    let assets = valuation.assets();
+    assert_eq!(assets[1].mint, sol_mint);
+    assert_eq!(assets[1].amount, 2.0);
+    assert_eq!(assets[1].value, 300.0);
+    assert_eq!(assets[2].mint, jup_mint);
+    assert_eq!(assets[2].value, 150.0);
+
+    // an unpriced quote counts for nothing
+    let unpriced = PositionValuation {
+        quote_price: None,
+        ..position
+    };
+    assert_eq!(unpriced.usdc_value(), 0.0);
+    Ok(())
+}
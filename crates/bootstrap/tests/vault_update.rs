@@ -0,0 +1,94 @@
+use bootstrap::amount::{SOL_DECIMALS, USDC_DECIMALS};
+use bootstrap::vault_update::*;
+use bootstrap::{drift_vaults, phoenix_vaults};
+use solana_sdk::pubkey::Pubkey;
+
+fn settings() -> VaultSettings {
+    VaultSettings {
+        redeem_period: 60 * 60 * 24 * 7,
+        max_tokens: 1_000_000 * QUOTE_PRECISION,
+        management_fee: 20_000,
+        min_deposit_amount: 10 * QUOTE_PRECISION,
+        profit_share: 200_000,
+        hurdle_rate: 0,
+        permissioned: false,
+        delegate: Pubkey::new_unique(),
+        total_withdraw_requested: 0,
+        deposit_decimals: USDC_DECIMALS,
+    }
+}
+
+#[test]
+fn plan_update_skips_unchanged_fields() -> anyhow::Result<()> {
+    let current = settings();
+    let desired = UpdateVaultConfig {
+        management_fee: Some(current.management_fee),
+        profit_share: Some(100_000),
+        permissioned: Some(true),
+        ..Default::default()
+    };
+    let plan = plan_update(&current, &desired, phoenix_vaults::UPDATE_RULES)?;
+    assert_eq!(plan.update.management_fee, None);
+    assert_eq!(plan.update.profit_share, Some(100_000));
+    assert_eq!(plan.update.permissioned, Some(true));
+    assert_eq!(plan.changes.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn plan_update_rejects_fee_increase() {
+    let current = settings();
+    let desired = UpdateVaultConfig {
+        profit_share: Some(300_000),
+        ..Default::default()
+    };
+    assert!(plan_update(&current, &desired, phoenix_vaults::UPDATE_RULES).is_err());
+}
+
+#[test]
+fn plan_update_only_lowers_hurdle_rate_on_both_venues() -> anyhow::Result<()> {
+    let mut current = settings();
+    let raise = UpdateVaultConfig {
+        hurdle_rate: Some(50_000),
+        ..Default::default()
+    };
+    for rules in [drift_vaults::UPDATE_RULES, phoenix_vaults::UPDATE_RULES] {
+        assert!(plan_update(&current, &raise, rules).is_err());
+    }
+
+    current.hurdle_rate = 100_000;
+    for rules in [drift_vaults::UPDATE_RULES, phoenix_vaults::UPDATE_RULES] {
+        let plan = plan_update(&current, &raise, rules)?;
+        assert_eq!(plan.update.hurdle_rate, Some(50_000));
+    }
+    Ok(())
+}
+
+#[test]
+fn plan_update_rejects_redeem_period_change_with_pending_withdrawals() {
+    let mut current = settings();
+    current.total_withdraw_requested = 5 * QUOTE_PRECISION;
+    let desired = UpdateVaultConfig {
+        redeem_period: Some(60 * 60),
+        ..Default::default()
+    };
+    assert!(plan_update(&current, &desired, phoenix_vaults::UPDATE_RULES).is_err());
+
+    current.total_withdraw_requested = 0;
+    assert!(plan_update(&current, &desired, phoenix_vaults::UPDATE_RULES).is_ok());
+}
+
+#[test]
+fn plan_update_formats_amounts_in_the_deposit_mint() -> anyhow::Result<()> {
+    let mut current = settings();
+    current.deposit_decimals = SOL_DECIMALS;
+    current.max_tokens = 2_500_000_000;
+    let desired = UpdateVaultConfig {
+        max_tokens: Some(1_000_000_000),
+        ..Default::default()
+    };
+    let plan = plan_update(&current, &desired, phoenix_vaults::UPDATE_RULES)?;
+    assert_eq!(plan.changes[0].before, "2.5");
+    assert_eq!(plan.changes[0].after, "1");
+    Ok(())
+}